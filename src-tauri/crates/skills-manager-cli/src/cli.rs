@@ -0,0 +1,820 @@
+//! Headless entry point behind the `skills-manager-cli` binary.
+//!
+//! Every subcommand builds the same request types the webview sends and calls the
+//! [`SkillsManager`] method behind the corresponding Tauri command, so the CLI and the GUI
+//! cannot drift.
+
+use serde::Serialize;
+use skills_manager_core::jobs::DownloadJob;
+use skills_manager_core::types::{
+    AdoptIdeSkillRequest, DiagnosticSeverity, DownloadRequest, ExportSkillsRequest, IdeDir,
+    ImportRequest, IndexSourceState, LinkRequest, LinkStrategy, LinkTarget, LocalScanRequest,
+    MarketSearchFilters, MarketSort, RollbackSkillRequest, SourceInstallRequest,
+    SyncProjectRequest, UninstallRequest,
+};
+use skills_manager_core::SkillsManager;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "\
+Usage: skills-manager-cli <command> [options]
+
+Commands:
+  search [query] [--limit N] [--offset N]       Search the skills index
+      [--category NAME] [--author NAME] [--market ID] [--installed | --not-installed]
+      [--sort relevance|name|recent|stars|installs]
+  refresh-index                                 Fetch the latest skills index from the configured URLs
+  install <source> [--name NAME] [--update]     Install a skill from a URL, git remote, directory or
+                                                .zip / .tar.gz archive into ~/.skills-manager/skills
+      [--subpath PATH...]                       Install the listed skills of a multi-skill source
+  list-source <source>                          List every skill (SKILL.md) found in a source
+  link <skill> --target DIR... [--project DIR]  Link a managed skill into IDE skill directories
+      [--strategy symlink|relative-symlink|copy|hardlink-tree]
+      [--vendor]                                With --project, copy the skill into the project's
+                                                .skills-manager/skills and link it with relative symlinks
+  unlink <path> [--project DIR]                 Remove a linked or copied skill from an IDE directory
+  scan [--project DIR] [--ide LABEL=DIR...]     List managed skills and IDE skills
+  export <output.zip> <skill>...                Export managed skills to a zip archive
+  import <dir|archive>                          Import a skill directory or a .zip / .tar.gz archive
+  adopt <path> [--ide LABEL]                    Move an IDE skill into Skills Manager and link it back
+  validate <skill>                              Lint a skill directory; exits non-zero on errors
+  cache [clear]                                 Show or clear the downloaded archive cache
+  outdated [--update]                           List managed skills whose source has changed, and
+                                                with --update, update them
+  versions <skill>                              List the previous versions kept for a managed skill
+  rollback <skill> <version>                    Restore a previous version of a managed skill
+  sync [--project DIR] [--dry-run]              Install and link the skills the project's skills.toml
+                                                declares, prune undeclared links and write skills.lock
+
+Options:
+  --json    Print the raw command result as JSON
+  -h, --help
+
+<skill> accepts either a path or the directory name of a skill in ~/.skills-manager/skills.";
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+/// Every command with the options it takes a value for and the flags it accepts, besides
+/// `--json` and `--help`.
+const COMMANDS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "search",
+        &["limit", "offset", "category", "author", "market", "sort"],
+        &["installed", "not-installed"],
+    ),
+    ("refresh-index", &[], &[]),
+    ("install", &["name", "subpath"], &["update"]),
+    ("list-source", &[], &[]),
+    (
+        "link",
+        &["target", "project", "name", "strategy"],
+        &["vendor"],
+    ),
+    ("unlink", &["project"], &[]),
+    ("scan", &["project", "ide"], &[]),
+    ("export", &[], &[]),
+    ("import", &[], &[]),
+    ("adopt", &["ide"], &[]),
+    ("validate", &[], &[]),
+    ("cache", &[], &[]),
+    ("outdated", &[], &["update"]),
+    ("versions", &[], &[]),
+    ("rollback", &[], &[]),
+    ("sync", &["project"], &["dry-run"]),
+];
+
+const COMMON_FLAGS: &[&str] = &["json", "help"];
+
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Splits `raw` into positional arguments, `value_options` and `flags`; any other
+    /// `--option` is an error.
+    fn parse(raw: &[String], value_options: &[&str], flags: &[&str]) -> Result<Self, String> {
+        let mut args = Args {
+            positional: Vec::new(),
+            options: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = raw.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if let Some((key, value)) = name.split_once('=') {
+                    if value_options.contains(&key) {
+                        args.options.push((key.to_string(), value.to_string()));
+                        continue;
+                    }
+                }
+                if value_options.contains(&name) {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| format!("Missing value for --{}", name))?;
+                    args.options.push((name.to_string(), value.clone()));
+                } else if flags.contains(&name) || COMMON_FLAGS.contains(&name) {
+                    args.flags.push(name.to_string());
+                } else {
+                    return Err(format!("Unknown option: --{}", name));
+                }
+            } else if arg == "-h" {
+                args.flags.push("help".to_string());
+            } else {
+                args.positional.push(arg.clone());
+            }
+        }
+        Ok(args)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn options_named(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    fn positional(&self, index: usize, what: &str) -> Result<&str, String> {
+        self.positional
+            .get(index)
+            .map(|value| value.as_str())
+            .ok_or_else(|| format!("Missing {}", what))
+    }
+
+    fn number(&self, name: &str, default: u64) -> Result<u64, String> {
+        match self.option(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{} expects a number, got {}", name, value)),
+            None => Ok(default),
+        }
+    }
+}
+
+/// Accepts either an existing path or the directory name of a managed skill.
+fn resolve_skill_arg(manager: &SkillsManager, value: &str) -> CliResult<PathBuf> {
+    let path = PathBuf::from(value);
+    if path.exists() {
+        return Ok(path);
+    }
+    let managed = manager.manager_root().join(value);
+    if managed.exists() {
+        return Ok(managed);
+    }
+    Err(format!("Skill not found: {}", value).into())
+}
+
+fn absolute(path: &Path) -> CliResult<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    let cwd = std::env::current_dir()?;
+    Ok(cwd.join(path))
+}
+
+fn print_json<T: Serialize>(value: &T) -> CliResult {
+    let raw = serde_json::to_string_pretty(value)?;
+    println!("{}", raw);
+    Ok(())
+}
+
+fn link_strategy(value: &str) -> CliResult<LinkStrategy> {
+    match value {
+        "symlink" => Ok(LinkStrategy::Symlink),
+        "relative-symlink" => Ok(LinkStrategy::RelativeSymlink),
+        "copy" => Ok(LinkStrategy::Copy),
+        "hardlink-tree" => Ok(LinkStrategy::HardlinkTree),
+        other => Err(format!(
+            "Unknown link strategy: {} (expected symlink, relative-symlink, copy or hardlink-tree)",
+            other
+        )
+        .into()),
+    }
+}
+
+fn market_sort(value: &str) -> CliResult<MarketSort> {
+    match value {
+        "relevance" => Ok(MarketSort::Relevance),
+        "name" => Ok(MarketSort::Name),
+        "recent" => Ok(MarketSort::Recent),
+        "stars" => Ok(MarketSort::Stars),
+        "installs" => Ok(MarketSort::Installs),
+        other => Err(format!(
+            "Unknown sort: {} (expected relevance, name, recent, stars or installs)",
+            other
+        )
+        .into()),
+    }
+}
+
+fn run_search(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let query = args.positional.join(" ");
+    let limit = args.number("limit", 20)?;
+    let offset = args.number("offset", 0)?;
+    let filters = MarketSearchFilters {
+        category: args.option("category").map(str::to_string),
+        author: args.option("author").map(str::to_string),
+        market_id: args.option("market").map(str::to_string),
+        installed: match (args.flag("installed"), args.flag("not-installed")) {
+            (true, true) => return Err("--installed and --not-installed conflict".into()),
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        },
+        sort: args
+            .option("sort")
+            .map(market_sort)
+            .transpose()?
+            .unwrap_or_default(),
+    };
+    let response = manager.search_marketplaces(&query, limit, offset, &filters)?;
+    if json {
+        return print_json(&response);
+    }
+    for skill in &response.skills {
+        let installed = if skill.installed { "\t(installed)" } else { "" };
+        println!("{}\t{}{}", skill.name, skill.source_url, installed);
+        if !skill.description.is_empty() {
+            println!("    {}", skill.description);
+        }
+    }
+    println!(
+        "{} of {} results (offset {})",
+        response.skills.len(),
+        response.total,
+        response.offset
+    );
+    Ok(())
+}
+
+fn run_refresh_index(manager: &SkillsManager, json: bool) -> CliResult {
+    let refresh = manager.refresh_market_index()?;
+    if json {
+        return print_json(&refresh);
+    }
+    for source in &refresh.sources {
+        match (&source.state, &source.error) {
+            (_, Some(err)) => println!("  {}\tfailed: {}", source.url, err),
+            (IndexSourceState::NotModified, None) => println!("  {}\tnot modified", source.url),
+            _ => println!("  {}\tupdated", source.url),
+        }
+    }
+    println!(
+        "Index updated {}: {} skills ({} added, {} removed)",
+        refresh.updated_at.as_deref().unwrap_or("-"),
+        refresh.total,
+        refresh.added,
+        refresh.removed
+    );
+    Ok(())
+}
+
+fn run_cache(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let clear = match args.positional.first().map(String::as_str) {
+        None => false,
+        Some("clear") => true,
+        Some(other) => return Err(format!("Unknown cache action: {}", other).into()),
+    };
+    let stats = if clear {
+        manager.clear_cache()?
+    } else {
+        manager.cache_stats()?
+    };
+    if json {
+        return print_json(&stats);
+    }
+    let mib = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+    if clear {
+        println!(
+            "Removed {} archives ({:.1} MiB)",
+            stats.entries,
+            mib(stats.total_bytes)
+        );
+    } else {
+        println!(
+            "{}: {} archives, {:.1} of {:.1} MiB",
+            stats.path,
+            stats.entries,
+            mib(stats.total_bytes),
+            mib(stats.max_bytes)
+        );
+    }
+    Ok(())
+}
+
+fn run_outdated(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let statuses = manager.check_skill_updates()?;
+    let outdated: Vec<_> = statuses
+        .iter()
+        .filter(|status| status.update_available)
+        .collect();
+    if args.flag("update") {
+        for status in &outdated {
+            let skill_name = Path::new(&status.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| status.name.clone());
+            let result = manager.update_marketplace_skill(
+                DownloadRequest {
+                    source_url: status.source_url.clone(),
+                    skill_name,
+                    install_base_dir: manager.manager_root().display().to_string(),
+                    job_id: None,
+                },
+                &DownloadJob::default(),
+            )?;
+            if !json {
+                println!("Updated {}", result.installed_path);
+            }
+        }
+    }
+    if json {
+        return print_json(&statuses);
+    }
+    let short = |commit: &Option<String>| {
+        commit
+            .as_deref()
+            .map(|commit| commit.chars().take(7).collect::<String>())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for status in &statuses {
+        if let Some(err) = &status.error {
+            println!("  {}\tfailed: {}", status.name, err);
+        } else if status.update_available {
+            println!(
+                "  {}\t{} -> {}\t{} added, {} modified, {} removed",
+                status.name,
+                short(&status.installed_commit),
+                short(&status.latest_commit),
+                status.changes.added.len(),
+                status.changes.modified.len(),
+                status.changes.removed.len()
+            );
+        }
+    }
+    println!(
+        "{} of {} skills have updates",
+        outdated.len(),
+        statuses.len()
+    );
+    Ok(())
+}
+
+/// The last segment of the source path, or of its `#ref:subpath` fragment, without archive
+/// or `.git` extensions.
+fn default_skill_name(source: &str) -> String {
+    let (location, fragment) = source.split_once('#').unwrap_or((source, ""));
+    let path = match fragment.split_once(':') {
+        Some((_, subpath)) if !subpath.trim_matches('/').is_empty() => subpath,
+        _ => location.split('?').next().unwrap_or_default(),
+    };
+    let last = path
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\', ':'])
+        .next()
+        .unwrap_or_default();
+    let lower = last.to_ascii_lowercase();
+    [".tar.gz", ".tgz", ".zip", ".git"]
+        .iter()
+        .find(|extension| lower.ends_with(*extension))
+        .map_or(last, |extension| &last[..last.len() - extension.len()])
+        .to_string()
+}
+
+/// Local paths are made absolute so relative ones work from the shell.
+fn source_arg(args: &Args) -> CliResult<String> {
+    let source = args.positional(0, "source")?;
+    if Path::new(source).exists() {
+        Ok(absolute(Path::new(source))?.display().to_string())
+    } else {
+        Ok(source.to_string())
+    }
+}
+
+fn run_list_source(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let skills = manager.list_skills_in_source(&source_arg(args)?)?;
+    if json {
+        return print_json(&skills);
+    }
+    for skill in &skills {
+        let subpath = if skill.subpath.is_empty() {
+            "."
+        } else {
+            &skill.subpath
+        };
+        println!("{}\t{}", subpath, skill.name);
+    }
+    Ok(())
+}
+
+fn run_install(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let source_url = source_arg(args)?;
+    let subpaths = args.options_named("subpath");
+    if !subpaths.is_empty() {
+        let results = manager.install_skills_from_source(
+            SourceInstallRequest {
+                source_url,
+                subpaths: subpaths.into_iter().map(str::to_string).collect(),
+                install_base_dir: manager.manager_root().display().to_string(),
+                overwrite: args.flag("update"),
+                job_id: None,
+            },
+            &DownloadJob::default(),
+        )?;
+        if json {
+            return print_json(&results);
+        }
+        for result in &results {
+            println!("Installed {}", result.installed_path);
+        }
+        return Ok(());
+    }
+    let skill_name = match args.option("name") {
+        Some(name) => name.to_string(),
+        None => default_skill_name(&source_url),
+    };
+    let request = DownloadRequest {
+        source_url,
+        skill_name,
+        install_base_dir: manager.manager_root().display().to_string(),
+        job_id: None,
+    };
+    let job = DownloadJob::default();
+    let result = if args.flag("update") {
+        manager.update_marketplace_skill(request, &job)?
+    } else {
+        manager.download_marketplace_skill(request, &job)?
+    };
+    if json {
+        return print_json(&result);
+    }
+    println!("Installed {}", result.installed_path);
+    Ok(())
+}
+
+fn run_link(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let skill_path = resolve_skill_arg(manager, args.positional(0, "skill")?)?;
+    let targets = args.options_named("target");
+    if targets.is_empty() {
+        return Err("At least one --target directory is required".into());
+    }
+    let skill_name = args.option("name").map(str::to_string).unwrap_or_else(|| {
+        skill_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("skill")
+            .to_string()
+    });
+    let strategy = args.option("strategy").map(link_strategy).transpose()?;
+    let link_targets = targets
+        .iter()
+        .map(|target| {
+            Ok(LinkTarget {
+                name: target.to_string(),
+                path: absolute(Path::new(target))?.display().to_string(),
+                strategy,
+            })
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+    let project_dir = args
+        .option("project")
+        .map(|dir| absolute(Path::new(dir)))
+        .transpose()?
+        .map(|dir| dir.display().to_string());
+
+    let result = manager.link_local_skill(LinkRequest {
+        skill_path: skill_path.display().to_string(),
+        skill_name,
+        link_targets,
+        project_dir,
+        vendor: args.flag("vendor"),
+    })?;
+    if json {
+        return print_json(&result);
+    }
+    for line in &result.linked {
+        println!("linked  {}", line);
+    }
+    for line in &result.skipped {
+        println!("skipped {}", line);
+    }
+    Ok(())
+}
+
+fn run_unlink(manager: &SkillsManager, args: &Args) -> CliResult {
+    let target = absolute(Path::new(args.positional(0, "path")?))?;
+    let project_dir = args
+        .option("project")
+        .map(|dir| absolute(Path::new(dir)))
+        .transpose()?
+        .map(|dir| dir.display().to_string());
+    let message = manager.uninstall_skill(UninstallRequest {
+        target_path: target.display().to_string(),
+        project_dir,
+        ide_dirs: Vec::new(),
+    })?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn run_scan(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let ide_dirs = args
+        .options_named("ide")
+        .iter()
+        .map(|value| {
+            let (label, dir) = value
+                .split_once('=')
+                .ok_or_else(|| format!("--ide expects LABEL=DIR, got {}", value))?;
+            Ok(IdeDir {
+                label: label.to_string(),
+                relative_dir: dir.to_string(),
+            })
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+    let project_dir = args
+        .option("project")
+        .map(|dir| absolute(Path::new(dir)))
+        .transpose()?
+        .map(|dir| dir.display().to_string());
+
+    let overview = manager.scan_overview(LocalScanRequest {
+        project_dir,
+        ide_dirs,
+    })?;
+    if json {
+        return print_json(&overview);
+    }
+    println!("Managed skills:");
+    for skill in &overview.manager_skills {
+        let used_by = if skill.used_by.is_empty() {
+            "-".to_string()
+        } else {
+            skill.used_by.join(", ")
+        };
+        println!("  {}\t{}\t[{}]", skill.name, skill.path, used_by);
+    }
+    println!("IDE skills:");
+    for skill in &overview.ide_skills {
+        let managed = if skill.managed {
+            "managed"
+        } else {
+            "unmanaged"
+        };
+        println!(
+            "  {}\t{}\t{} ({}, {})",
+            skill.ide, skill.name, skill.path, skill.source, managed
+        );
+    }
+    Ok(())
+}
+
+fn run_export(manager: &SkillsManager, args: &Args) -> CliResult {
+    let export_path = absolute(Path::new(args.positional(0, "output path")?))?;
+    let target_paths = args.positional[1..]
+        .iter()
+        .map(|value| resolve_skill_arg(manager, value).map(|path| path.display().to_string()))
+        .collect::<CliResult<Vec<_>>>()?;
+    let written = manager.export_local_skills(ExportSkillsRequest {
+        target_paths,
+        export_path: export_path.display().to_string(),
+    })?;
+    println!("Exported to {}", written);
+    Ok(())
+}
+
+fn run_import(manager: &SkillsManager, args: &Args) -> CliResult {
+    let source = absolute(Path::new(
+        args.positional(0, "source directory or archive")?,
+    ))?;
+    let message = manager.import_local_skill(ImportRequest {
+        source_path: source.display().to_string(),
+    })?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn run_adopt(manager: &SkillsManager, args: &Args) -> CliResult {
+    let target = absolute(Path::new(args.positional(0, "IDE skill path")?))?;
+    let ide_label = args.option("ide").unwrap_or("IDE").to_string();
+    let message = manager.adopt_ide_skill(AdoptIdeSkillRequest {
+        target_path: target.display().to_string(),
+        ide_label,
+    })?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn run_versions(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let skill_path = resolve_skill_arg(manager, args.positional(0, "skill")?)?;
+    let versions = manager.list_skill_versions(&absolute(&skill_path)?.display().to_string())?;
+    if json {
+        return print_json(&versions);
+    }
+    for version in &versions {
+        let installed_at = version
+            .provenance
+            .as_ref()
+            .and_then(|provenance| provenance.installed_at.as_deref())
+            .unwrap_or("-");
+        println!("{}\t{}", version.id, installed_at);
+    }
+    Ok(())
+}
+
+fn run_rollback(manager: &SkillsManager, args: &Args) -> CliResult {
+    let skill_path = resolve_skill_arg(manager, args.positional(0, "skill")?)?;
+    let message = manager.rollback_skill(RollbackSkillRequest {
+        skill_path: absolute(&skill_path)?.display().to_string(),
+        version: args.positional(1, "version")?.to_string(),
+    })?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn run_sync(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let project_dir = match args.option("project") {
+        Some(dir) => absolute(Path::new(dir))?,
+        None => std::env::current_dir()?,
+    };
+    let dry_run = args.flag("dry-run");
+    let report = manager.sync_project(
+        SyncProjectRequest {
+            project_dir: project_dir.display().to_string(),
+            dry_run,
+        },
+        &DownloadJob::default(),
+    )?;
+    if json {
+        return print_json(&report);
+    }
+    for drift in &report.drift {
+        println!("  {}", drift.detail);
+    }
+    if dry_run {
+        println!("{} differences from skills.toml", report.drift.len());
+        return Ok(());
+    }
+    for skill in &report.installed {
+        println!("Installed {}", skill);
+    }
+    for path in &report.linked {
+        println!("Linked {}", path);
+    }
+    for path in &report.removed {
+        println!("Removed {}", path);
+    }
+    println!("Wrote {}", report.lock_path);
+    Ok(())
+}
+
+fn run_validate(manager: &SkillsManager, args: &Args, json: bool) -> CliResult {
+    let skill_path = resolve_skill_arg(manager, args.positional(0, "skill")?)?;
+    let report = manager.validate_skill(&absolute(&skill_path)?.display().to_string())?;
+    if json {
+        print_json(&report)?;
+    } else {
+        for item in &report.diagnostics {
+            let severity = match item.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+            };
+            let location = match item.line {
+                Some(line) => format!("{}:{}", item.file, line),
+                None => item.file.clone(),
+            };
+            println!(
+                "{}: {}: {} [{}]",
+                location, severity, item.message, item.code
+            );
+        }
+        if report.diagnostics.is_empty() {
+            println!("{}: no problems found", report.name);
+        }
+    }
+    if !report.valid {
+        return Err(format!("{} failed validation", report.name).into());
+    }
+    Ok(())
+}
+
+fn dispatch(raw: &[String]) -> CliResult {
+    let Some((command, rest)) = raw.split_first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+    if command == "help" || command == "--help" || command == "-h" {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let Some((_, value_options, flags)) = COMMANDS.iter().find(|(name, _, _)| name == command)
+    else {
+        return Err(format!("Unknown command: {}\n\n{}", command, USAGE).into());
+    };
+    let args = Args::parse(rest, value_options, flags)
+        .map_err(|err| format!("{}: {}\n\n{}", command, err, USAGE))?;
+    if args.flag("help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let json = args.flag("json");
+    let manager = SkillsManager::from_home_dir()?.with_app_version(env!("CARGO_PKG_VERSION"));
+
+    match command.as_str() {
+        "search" => run_search(&manager, &args, json),
+        "refresh-index" => run_refresh_index(&manager, json),
+        "install" => run_install(&manager, &args, json),
+        "list-source" => run_list_source(&manager, &args, json),
+        "link" => run_link(&manager, &args, json),
+        "unlink" => run_unlink(&manager, &args),
+        "scan" => run_scan(&manager, &args, json),
+        "export" => run_export(&manager, &args),
+        "import" => run_import(&manager, &args),
+        "adopt" => run_adopt(&manager, &args),
+        "validate" => run_validate(&manager, &args, json),
+        "cache" => run_cache(&manager, &args, json),
+        "outdated" => run_outdated(&manager, &args, json),
+        "versions" => run_versions(&manager, &args, json),
+        "rollback" => run_rollback(&manager, &args),
+        "sync" => run_sync(&manager, &args, json),
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE).into()),
+    }
+}
+
+/// Runs the CLI with the process arguments and returns the exit code.
+pub fn run() -> i32 {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    match dispatch(&raw) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_skill_name, Args};
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_repeated_options_and_flags() {
+        let raw = strings(&[
+            "docx",
+            "--target",
+            "/tmp/a",
+            "--target=/tmp/b",
+            "--json",
+            "--project",
+            "/work",
+        ]);
+        let args = Args::parse(&raw, &["target", "project"], &[]).unwrap();
+        assert_eq!(args.positional, vec!["docx".to_string()]);
+        assert_eq!(args.options_named("target"), vec!["/tmp/a", "/tmp/b"]);
+        assert_eq!(args.option("project"), Some("/work"));
+        assert!(args.flag("json"));
+    }
+
+    #[test]
+    fn rejects_option_without_value() {
+        let raw = strings(&["--limit"]);
+        assert!(Args::parse(&raw, &["limit"], &[]).is_err());
+    }
+
+    #[test]
+    fn rejects_options_the_command_does_not_take() {
+        let raw = strings(&["--dry-rn"]);
+        let err = Args::parse(&raw, &["project"], &["dry-run"]).err().unwrap();
+        assert_eq!(err, "Unknown option: --dry-rn");
+        let raw = strings(&["--dry-run", "--json", "-h"]);
+        assert!(Args::parse(&raw, &["project"], &["dry-run"]).is_ok());
+    }
+
+    #[test]
+    fn names_skills_after_the_source() {
+        assert_eq!(
+            default_skill_name("https://github.com/anthropics/skills/tree/main/skills/docx/"),
+            "docx"
+        );
+        assert_eq!(default_skill_name("/tmp/Team Skill.tar.gz"), "Team Skill");
+        assert_eq!(default_skill_name("git@host:team/skills.git#v1:pdf"), "pdf");
+        assert_eq!(default_skill_name("git@host:skills.git"), "skills");
+    }
+}
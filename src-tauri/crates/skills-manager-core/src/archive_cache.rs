@@ -0,0 +1,244 @@
+//! Downloaded skill archives kept on disk, so installing several skills from one repository
+//! or updating an unchanged skill does not download the same archive again.
+//!
+//! Entries are keyed by what identifies their content: the commit a GitHub ref resolves to,
+//! or the archive URL for other hosts, revalidated with its `ETag` / `Last-Modified`. The
+//! least recently used archives are evicted once the cache outgrows its size limit.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::types::ArchiveCacheStats;
+use crate::utils::path::{short_stable_hash, write_atomically};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const ARCHIVE_EXTENSION: &str = "archive";
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveMeta {
+    key: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+}
+
+pub(crate) struct CachedArchive {
+    pub(crate) body: Vec<u8>,
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+}
+
+struct CacheEntry {
+    data_path: PathBuf,
+    meta_path: PathBuf,
+    size: u64,
+    used_at: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ArchiveCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl ArchiveCache {
+    pub(crate) fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let hash = short_stable_hash(key);
+        (
+            self.dir.join(format!("{hash}.{ARCHIVE_EXTENSION}")),
+            self.dir.join(format!("{hash}.meta.json")),
+        )
+    }
+
+    /// The archive stored under `key`, marked as just used.
+    pub(crate) fn get(&self, key: &str) -> Option<CachedArchive> {
+        let (data_path, meta_path) = self.paths(key);
+        let meta = fs::read_to_string(meta_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<ArchiveMeta>(&raw).ok())
+            .filter(|meta| meta.key == key)?;
+        let body = fs::read(&data_path).ok()?;
+        // The modification time doubles as the last use for eviction.
+        if let Ok(file) = fs::File::options().write(true).open(&data_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(CachedArchive {
+            body,
+            etag: meta.etag,
+            last_modified: meta.last_modified,
+        })
+    }
+
+    /// Stores `body` under `key`, then evicts the least recently used archives until the
+    /// cache fits its limit again.
+    pub(crate) fn put(
+        &self,
+        key: &str,
+        body: &[u8],
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> SkillsResult<()> {
+        if body.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).map_err(|err| SkillsError::io(&self.dir, err))?;
+        let (data_path, meta_path) = self.paths(key);
+        let meta = ArchiveMeta {
+            key: key.to_string(),
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+        };
+        write_atomically(&data_path, body)?;
+        write_atomically(&meta_path, serde_json::to_string_pretty(&meta)?.as_bytes())?;
+        self.evict(&data_path)
+    }
+
+    fn entries(&self) -> SkillsResult<Vec<CacheEntry>> {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(SkillsError::io(&self.dir, err)),
+        };
+        let mut entries = Vec::new();
+        for item in read_dir {
+            let data_path = item.map_err(|err| SkillsError::io(&self.dir, err))?.path();
+            if data_path.extension().and_then(|ext| ext.to_str()) != Some(ARCHIVE_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&data_path) else {
+                continue;
+            };
+            entries.push(CacheEntry {
+                meta_path: data_path.with_extension("meta.json"),
+                size: metadata.len(),
+                used_at: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                data_path,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Removes the least recently used archives other than `keep` while over the limit.
+    fn evict(&self, keep: &Path) -> SkillsResult<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+        entries.sort_by_key(|entry| entry.used_at);
+        for entry in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if entry.data_path == keep {
+                continue;
+            }
+            remove_entry(&entry)?;
+            total -= entry.size;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn stats(&self) -> SkillsResult<ArchiveCacheStats> {
+        let entries = self.entries()?;
+        Ok(ArchiveCacheStats {
+            path: self.dir.display().to_string(),
+            entries: entries.len() as u64,
+            total_bytes: entries.iter().map(|entry| entry.size).sum(),
+            max_bytes: self.max_bytes,
+        })
+    }
+
+    /// Removes every cached archive and returns what was removed.
+    pub(crate) fn clear(&self) -> SkillsResult<ArchiveCacheStats> {
+        let stats = self.stats()?;
+        for entry in self.entries()? {
+            remove_entry(&entry)?;
+        }
+        Ok(stats)
+    }
+}
+
+fn remove_entry(entry: &CacheEntry) -> SkillsResult<()> {
+    for path in [&entry.data_path, &entry.meta_path] {
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(SkillsError::io(path, err)),
+        }
+    }
+    Ok(())
+}
+
+impl SkillsManager {
+    fn sized_archive_cache(&self) -> SkillsResult<ArchiveCache> {
+        let max_mb = self.load_settings()?.archive_cache_max_mb;
+        Ok(ArchiveCache::new(
+            self.cache_dir().join("archives"),
+            max_mb * 1024 * 1024,
+        ))
+    }
+
+    /// The archive cache sized from the settings, or `None` when it is turned off.
+    pub(crate) fn archive_cache(&self) -> SkillsResult<Option<ArchiveCache>> {
+        let cache = self.sized_archive_cache()?;
+        Ok((cache.max_bytes > 0).then_some(cache))
+    }
+
+    /// Archives currently cached, including any kept from before the cache was turned off.
+    pub fn cache_stats(&self) -> SkillsResult<ArchiveCacheStats> {
+        self.sized_archive_cache()?.stats()
+    }
+
+    /// Deletes every cached archive and returns what was removed.
+    pub fn clear_cache(&self) -> SkillsResult<ArchiveCacheStats> {
+        self.sized_archive_cache()?.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArchiveCache;
+    use std::fs;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn evicts_least_recently_used_archives() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("skills-manager-archives-{timestamp}"));
+        let cache = ArchiveCache::new(dir.clone(), 10);
+
+        cache.put("a", b"aaaa", Some("\"a\""), None).unwrap();
+        cache.put("b", b"bbbb", None, None).unwrap();
+        // Make "b" the older entry by reading "a" a little later.
+        std::thread::sleep(Duration::from_millis(20));
+        let hit = cache.get("a").unwrap();
+        assert_eq!(hit.body, b"aaaa");
+        assert_eq!(hit.etag.as_deref(), Some("\"a\""));
+        assert!(cache.get("missing").is_none());
+
+        cache.put("c", b"cccc", None, None).unwrap();
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().unwrap().total_bytes, 8);
+
+        // Archives larger than the whole cache are not kept.
+        cache.put("d", b"dddddddddddd", None, None).unwrap();
+        assert!(cache.get("d").is_none());
+
+        let removed = cache.clear().unwrap();
+        assert_eq!(removed.entries, 2);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::utils::download::{download_bytes, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Catalog sites whose skill pages, at `/<owner>/<repo>/<skill>`, describe a skill in the
+/// GitHub repository `<owner>/<repo>` and link to its directory.
+const CATALOG_HOSTS: &[&str] = &["officialskills.sh"];
+const GITHUB_WEB_PREFIX: &str = "https://github.com/";
+
+/// How long the skills listed in a catalog repository are reused before it is downloaded
+/// again.
+const CATALOG_LISTING_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// The skills found in a catalog repository, cached since listing them downloads it.
+#[derive(Deserialize, Serialize, Clone)]
+struct CatalogListing {
+    fetched_at: u64,
+    skills: Vec<ListedSkill>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct ListedSkill {
+    name: String,
+    subpath: String,
+    source_url: String,
+}
+
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    rest.split(['/', '?', '#']).next()
+}
+
+/// Whether `url` is a catalog page that [`SkillsManager::resolve_source_url`] can follow.
+pub(crate) fn is_catalog_url(url: &str) -> bool {
+    url_host(url.trim()).is_some_and(|host| {
+        CATALOG_HOSTS
+            .iter()
+            .any(|known| host.eq_ignore_ascii_case(known))
+    })
+}
+
+/// The path segments of `url` after the host, without its query and fragment.
+fn path_segments(url: &str) -> Vec<&str> {
+    url.split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .skip(3)
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// The GitHub repository a catalog URL describes a skill of, and the skill's name.
+fn catalog_repository(catalog_url: &str) -> Option<(String, String)> {
+    match path_segments(catalog_url.trim())[..] {
+        [owner, repo, skill] => Some((
+            format!("{GITHUB_WEB_PREFIX}{owner}/{repo}"),
+            skill.to_string(),
+        )),
+        _ => None,
+    }
+}
+
+/// Picks the GitHub directory a catalog page links to that is named like the last segment
+/// of the catalog URL. Other directories on the page belong to other skills.
+fn find_github_source(html: &str, catalog_url: &str) -> Option<String> {
+    let skill = path_segments(catalog_url)
+        .last()
+        .copied()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let mut candidates = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(GITHUB_WEB_PREFIX) {
+        let tail = &rest[start..];
+        let end = tail
+            .find(|ch: char| {
+                matches!(ch, '"' | '\'' | '<' | '>' | ')' | '\\') || ch.is_whitespace()
+            })
+            .unwrap_or(tail.len());
+        let link = tail[..end].replace("&amp;", "&");
+        rest = &tail[end..];
+
+        let link = link.split(['?', '#']).next().unwrap_or_default();
+        let directory = match link.strip_suffix("/SKILL.md") {
+            Some(parent) => parent.replacen("/blob/", "/tree/", 1),
+            None => link.trim_end_matches('/').to_string(),
+        };
+        if directory.contains("/tree/") && !candidates.contains(&directory) {
+            candidates.push(directory);
+        }
+    }
+
+    candidates
+        .iter()
+        .find(|candidate| {
+            candidate
+                .rsplit('/')
+                .next()
+                .is_some_and(|last| last.eq_ignore_ascii_case(&skill))
+        })
+        .cloned()
+}
+
+/// Writes a lookup cache; a cache that cannot be written is only looked up again.
+fn write_cache(cache_path: &Path, value: &impl Serialize) {
+    if let Some(parent) = cache_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(raw) = serde_json::to_vec_pretty(value) {
+        let _ = fs::write(cache_path, raw);
+    }
+}
+
+impl SkillsManager {
+    /// Maps a catalog page URL onto the GitHub directory it describes, using the index's
+    /// catalog mappings first, the catalog page itself second, and the skills in the
+    /// repository the URL names last. Other URLs are returned unchanged. Lookups are cached
+    /// in `~/.skills-manager/cache/catalog-sources.json`, and repository listings as
+    /// [`SkillsManager::catalog_listing`] describes.
+    pub(crate) fn resolve_source_url(&self, source_url: &str) -> SkillsResult<String> {
+        let trimmed = source_url.trim();
+        for mapping in self.catalog_mappings()? {
+            if let Some(resolved) = mapping.apply(trimmed) {
+                return Ok(resolved);
+            }
+        }
+        if !is_catalog_url(trimmed) {
+            return Ok(trimmed.to_string());
+        }
+
+        let cache_path = self.cache_dir().join("catalog-sources.json");
+        let mut resolved_urls: BTreeMap<String, String> = fs::read(&cache_path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default();
+        if let Some(resolved) = resolved_urls.get(trimmed) {
+            return Ok(resolved.clone());
+        }
+
+        let from_page = download_bytes(
+            trimmed,
+            &[("User-Agent", USER_AGENT)],
+            &self.network_config()?,
+        )
+        .ok()
+        .and_then(|page| find_github_source(&String::from_utf8_lossy(&page), trimmed));
+        let resolved = match from_page {
+            Some(resolved) => resolved,
+            None => self.find_skill_in_catalog_repository(trimmed)?,
+        };
+
+        resolved_urls.insert(trimmed.to_string(), resolved.clone());
+        write_cache(&cache_path, &resolved_urls);
+        Ok(resolved)
+    }
+
+    /// The skills in `repository`, from `~/.skills-manager/cache/catalog-repositories.json`
+    /// for a day after it was last listed, and from an older listing when it cannot be
+    /// downloaded.
+    fn catalog_listing(&self, repository: &str) -> SkillsResult<Vec<ListedSkill>> {
+        let cache_path = self.cache_dir().join("catalog-repositories.json");
+        let mut listings: BTreeMap<String, CatalogListing> = fs::read(&cache_path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let cached = listings.remove(repository);
+        if let Some(listing) = &cached {
+            if now.saturating_sub(listing.fetched_at) < CATALOG_LISTING_TTL_SECS {
+                return Ok(listing.skills.clone());
+            }
+        }
+
+        let skills: Vec<ListedSkill> = match self.list_skills_in_source(repository) {
+            Ok(listed) => listed
+                .into_iter()
+                .map(|listed| ListedSkill {
+                    name: listed.name,
+                    subpath: listed.subpath,
+                    source_url: listed.source_url,
+                })
+                .collect(),
+            Err(err) => return cached.map(|stale| stale.skills).ok_or(err),
+        };
+        listings.insert(
+            repository.to_string(),
+            CatalogListing {
+                fetched_at: now,
+                skills: skills.clone(),
+            },
+        );
+        write_cache(&cache_path, &listings);
+        Ok(skills)
+    }
+
+    /// Looks through the repository a catalog URL names for the skill it names, by directory
+    /// or by `SKILL.md` name, for pages that could not be fetched or do not link to it.
+    fn find_skill_in_catalog_repository(&self, catalog_url: &str) -> SkillsResult<String> {
+        let (repository, skill) = catalog_repository(catalog_url).ok_or_else(|| {
+            SkillsError::unsupported_source(
+                catalog_url,
+                "The catalog URL does not name a repository and a skill",
+            )
+        })?;
+        self.catalog_listing(&repository)?
+            .into_iter()
+            .find(|listed| {
+                listed.name.eq_ignore_ascii_case(&skill)
+                    || listed
+                        .subpath
+                        .rsplit('/')
+                        .next()
+                        .is_some_and(|last| last.eq_ignore_ascii_case(&skill))
+            })
+            .map(|listed| listed.source_url)
+            .ok_or_else(|| {
+                SkillsError::unsupported_source(
+                    catalog_url,
+                    format!("{} has no skill named {}", repository, skill),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catalog_repository, find_github_source, is_catalog_url};
+    use crate::utils::path::file_url;
+    use crate::SkillsManager;
+    use std::fs;
+
+    #[test]
+    fn recognizes_catalog_hosts() {
+        assert!(is_catalog_url(
+            "https://officialskills.sh/openai/skills/pdf"
+        ));
+        assert!(!is_catalog_url("https://github.com/openai/skills"));
+        assert!(!is_catalog_url(
+            "https://catalog.redhat.com/en/ai/skills/detail/x"
+        ));
+    }
+
+    #[test]
+    fn finds_the_matching_github_directory_on_a_catalog_page() {
+        let html = r#"
+            <a href="https://github.com/openai/skills">Repository</a>
+            <a href="https://github.com/openai/skills/tree/main/skills/.curated/docx">docx</a>
+            <a href="https://github.com/openai/skills/blob/main/skills/.curated/pdf/SKILL.md?plain=1">SKILL.md</a>
+        "#;
+        assert_eq!(
+            find_github_source(html, "https://officialskills.sh/openai/skills/pdf").as_deref(),
+            Some("https://github.com/openai/skills/tree/main/skills/.curated/pdf")
+        );
+        assert_eq!(
+            find_github_source(html, "https://officialskills.sh/openai/skills/other"),
+            None
+        );
+        assert_eq!(
+            find_github_source("<p>no links</p>", "https://officialskills.sh/a/b/c"),
+            None
+        );
+    }
+
+    #[test]
+    fn names_the_repository_and_skill_of_a_catalog_url() {
+        assert_eq!(
+            catalog_repository("https://officialskills.sh/microsoft/skills/azure-ai?tab=1"),
+            Some((
+                "https://github.com/microsoft/skills".to_string(),
+                "azure-ai".to_string()
+            ))
+        );
+        assert_eq!(
+            catalog_repository("https://officialskills.sh/microsoft"),
+            None
+        );
+    }
+
+    #[test]
+    fn applies_catalog_mappings_from_the_bundled_index() {
+        let home =
+            std::env::temp_dir().join(format!("skills-manager-catalog-{}", std::process::id()));
+        let manager = SkillsManager::new(&home);
+        assert_eq!(
+            manager
+                .resolve_source_url("https://officialskills.sh/anthropics/skills/docx")
+                .unwrap(),
+            "https://github.com/anthropics/skills/tree/main/skills/docx"
+        );
+        assert_eq!(
+            manager
+                .resolve_source_url("https://github.com/owner/repo")
+                .unwrap(),
+            "https://github.com/owner/repo"
+        );
+    }
+
+    #[test]
+    fn reuses_the_listing_of_a_catalog_repository() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-catalog-listing-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let repo = home.join("repo");
+        for skill in ["docx", "pdf"] {
+            fs::create_dir_all(repo.join("skills").join(skill)).unwrap();
+            fs::write(
+                repo.join("skills").join(skill).join("SKILL.md"),
+                format!("---\nname: {skill}\n---\n"),
+            )
+            .unwrap();
+        }
+        let manager = SkillsManager::new(&home);
+        let repository = file_url(&repo);
+
+        let listed = manager.catalog_listing(&repository).unwrap();
+        assert_eq!(listed.len(), 2);
+        // Gone from disk, the repository is still listed from the cache.
+        fs::remove_dir_all(&repo).unwrap();
+        let cached = manager.catalog_listing(&repository).unwrap();
+        assert_eq!(
+            cached
+                .iter()
+                .map(|skill| skill.subpath.as_str())
+                .collect::<Vec<_>>(),
+            ["skills/docx", "skills/pdf"]
+        );
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
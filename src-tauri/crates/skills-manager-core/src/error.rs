@@ -0,0 +1,351 @@
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+
+/// Every failure surfaced by [`crate::SkillsManager`].
+///
+/// Serializes as `{ "code": "<Variant>", "message": "<English text>", ...fields }`. The
+/// `code` values and field names are stable so the frontend and scripts can branch on the
+/// kind of failure and localize the text themselves; `message` is only a fallback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillsError {
+    HomeDirUnavailable,
+    InvalidRequest {
+        reason: String,
+    },
+    PathOutsideAllowedRoot {
+        path: String,
+    },
+    NotFound {
+        path: String,
+    },
+    AlreadyExists {
+        path: String,
+    },
+    MissingSkillMd {
+        path: String,
+    },
+    SymlinkRejected {
+        path: String,
+    },
+    InvalidIdeDir {
+        label: String,
+    },
+    UnsupportedSource {
+        url: String,
+        reason: String,
+    },
+    NetworkError {
+        url: String,
+        status: Option<u16>,
+        detail: String,
+    },
+    ZipSlip {
+        entry: String,
+    },
+    InvalidArchive {
+        detail: String,
+    },
+    InvalidMarketIndex {
+        url: String,
+        detail: String,
+    },
+    Cancelled {
+        job_id: String,
+    },
+    /// Offline mode is on, so `url` was not fetched.
+    Offline {
+        url: String,
+    },
+    /// The server refused the request until its rate limit resets at `reset_at` (RFC 3339, UTC).
+    RateLimited {
+        url: String,
+        reset_at: Option<String>,
+    },
+    LinkFailed {
+        skill: String,
+        target: String,
+        detail: String,
+    },
+    Io {
+        path: Option<String>,
+        detail: String,
+    },
+    Internal {
+        detail: String,
+    },
+}
+
+pub type SkillsResult<T> = Result<T, SkillsError>;
+
+impl SkillsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SkillsError::HomeDirUnavailable => "HomeDirUnavailable",
+            SkillsError::InvalidRequest { .. } => "InvalidRequest",
+            SkillsError::PathOutsideAllowedRoot { .. } => "PathOutsideAllowedRoot",
+            SkillsError::NotFound { .. } => "NotFound",
+            SkillsError::AlreadyExists { .. } => "AlreadyExists",
+            SkillsError::MissingSkillMd { .. } => "MissingSkillMd",
+            SkillsError::SymlinkRejected { .. } => "SymlinkRejected",
+            SkillsError::InvalidIdeDir { .. } => "InvalidIdeDir",
+            SkillsError::UnsupportedSource { .. } => "UnsupportedSource",
+            SkillsError::NetworkError { .. } => "NetworkError",
+            SkillsError::ZipSlip { .. } => "ZipSlip",
+            SkillsError::InvalidArchive { .. } => "InvalidArchive",
+            SkillsError::InvalidMarketIndex { .. } => "InvalidMarketIndex",
+            SkillsError::Cancelled { .. } => "Cancelled",
+            SkillsError::RateLimited { .. } => "RateLimited",
+            SkillsError::Offline { .. } => "Offline",
+            SkillsError::LinkFailed { .. } => "LinkFailed",
+            SkillsError::Io { .. } => "Io",
+            SkillsError::Internal { .. } => "Internal",
+        }
+    }
+
+    pub fn invalid_request(reason: impl Into<String>) -> Self {
+        SkillsError::InvalidRequest {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn outside_allowed_root(path: &Path) -> Self {
+        SkillsError::PathOutsideAllowedRoot {
+            path: path.display().to_string(),
+        }
+    }
+
+    pub fn not_found(path: &Path) -> Self {
+        SkillsError::NotFound {
+            path: path.display().to_string(),
+        }
+    }
+
+    pub fn already_exists(path: &Path) -> Self {
+        SkillsError::AlreadyExists {
+            path: path.display().to_string(),
+        }
+    }
+
+    pub fn missing_skill_md(path: &Path) -> Self {
+        SkillsError::MissingSkillMd {
+            path: path.display().to_string(),
+        }
+    }
+
+    pub fn symlink_rejected(path: &Path) -> Self {
+        SkillsError::SymlinkRejected {
+            path: path.display().to_string(),
+        }
+    }
+
+    pub fn unsupported_source(url: &str, reason: impl Into<String>) -> Self {
+        SkillsError::UnsupportedSource {
+            url: url.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Wraps an I/O error together with the path it happened on.
+    pub fn io(path: &Path, err: impl fmt::Display) -> Self {
+        SkillsError::Io {
+            path: Some(path.display().to_string()),
+            detail: err.to_string(),
+        }
+    }
+
+    pub fn internal(detail: impl fmt::Display) -> Self {
+        SkillsError::Internal {
+            detail: detail.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SkillsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkillsError::HomeDirUnavailable => write!(f, "Unable to determine the home directory"),
+            SkillsError::InvalidRequest { reason } => write!(f, "{}", reason),
+            SkillsError::PathOutsideAllowedRoot { path } => {
+                write!(f, "Path is outside the allowed directories: {}", path)
+            }
+            SkillsError::NotFound { path } => write!(f, "Path does not exist: {}", path),
+            SkillsError::AlreadyExists { path } => write!(f, "Target already exists: {}", path),
+            SkillsError::MissingSkillMd { path } => {
+                write!(f, "Directory does not contain SKILL.md: {}", path)
+            }
+            SkillsError::SymlinkRejected { path } => {
+                write!(f, "Refusing to follow symlinked content: {}", path)
+            }
+            SkillsError::InvalidIdeDir { label } => write!(f, "Invalid IDE directory: {}", label),
+            SkillsError::UnsupportedSource { url, reason } => {
+                write!(f, "Unsupported source {}: {}", url, reason)
+            }
+            SkillsError::NetworkError {
+                url,
+                status: Some(status),
+                detail,
+            } => write!(f, "Request to {} failed with HTTP {}: {}", url, status, detail),
+            SkillsError::NetworkError {
+                url,
+                status: None,
+                detail,
+            } => write!(f, "Request to {} failed: {}", url, detail),
+            SkillsError::ZipSlip { entry } => write!(
+                f,
+                "Zip Slip attack detected: {} attempts to write outside of the extraction directory",
+                entry
+            ),
+            SkillsError::InvalidArchive { detail } => write!(f, "Invalid archive: {}", detail),
+            SkillsError::InvalidMarketIndex { url, detail } => {
+                write!(f, "Invalid skills index at {}: {}", url, detail)
+            }
+            SkillsError::Cancelled { .. } => write!(f, "The download was cancelled"),
+            SkillsError::Offline { url } => {
+                write!(f, "Offline mode is on, so {} was not fetched", url)
+            }
+            SkillsError::RateLimited {
+                url,
+                reset_at: Some(reset_at),
+            } => write!(
+                f,
+                "Rate limit exceeded for {}; it resets at {}",
+                url, reset_at
+            ),
+            SkillsError::RateLimited { url, reset_at: None } => {
+                write!(f, "Rate limit exceeded for {}", url)
+            }
+            SkillsError::LinkFailed {
+                skill,
+                target,
+                detail,
+            } => write!(
+                f,
+                "Failed to create a link for {} in {}: {}",
+                skill, target, detail
+            ),
+            SkillsError::Io {
+                path: Some(path),
+                detail,
+            } => write!(f, "{}: {}", path, detail),
+            SkillsError::Io { path: None, detail } => write!(f, "{}", detail),
+            SkillsError::Internal { detail } => write!(f, "{}", detail),
+        }
+    }
+}
+
+impl std::error::Error for SkillsError {}
+
+impl Serialize for SkillsError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            SkillsError::HomeDirUnavailable => {}
+            SkillsError::InvalidRequest { reason } => map.serialize_entry("reason", reason)?,
+            SkillsError::PathOutsideAllowedRoot { path }
+            | SkillsError::NotFound { path }
+            | SkillsError::AlreadyExists { path }
+            | SkillsError::MissingSkillMd { path }
+            | SkillsError::SymlinkRejected { path } => map.serialize_entry("path", path)?,
+            SkillsError::InvalidIdeDir { label } => map.serialize_entry("label", label)?,
+            SkillsError::UnsupportedSource { url, reason } => {
+                map.serialize_entry("url", url)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            SkillsError::NetworkError {
+                url,
+                status,
+                detail,
+            } => {
+                map.serialize_entry("url", url)?;
+                map.serialize_entry("status", status)?;
+                map.serialize_entry("detail", detail)?;
+            }
+            SkillsError::ZipSlip { entry } => map.serialize_entry("entry", entry)?,
+            SkillsError::InvalidMarketIndex { url, detail } => {
+                map.serialize_entry("url", url)?;
+                map.serialize_entry("detail", detail)?;
+            }
+            SkillsError::Cancelled { job_id } => map.serialize_entry("jobId", job_id)?,
+            SkillsError::Offline { url } => map.serialize_entry("url", url)?,
+            SkillsError::RateLimited { url, reset_at } => {
+                map.serialize_entry("url", url)?;
+                map.serialize_entry("resetAt", reset_at)?;
+            }
+            SkillsError::InvalidArchive { detail } | SkillsError::Internal { detail } => {
+                map.serialize_entry("detail", detail)?
+            }
+            SkillsError::LinkFailed {
+                skill,
+                target,
+                detail,
+            } => {
+                map.serialize_entry("skill", skill)?;
+                map.serialize_entry("target", target)?;
+                map.serialize_entry("detail", detail)?;
+            }
+            SkillsError::Io { path, detail } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("detail", detail)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl From<std::io::Error> for SkillsError {
+    fn from(err: std::io::Error) -> Self {
+        SkillsError::Io {
+            path: None,
+            detail: err.to_string(),
+        }
+    }
+}
+
+impl From<walkdir::Error> for SkillsError {
+    fn from(err: walkdir::Error) -> Self {
+        SkillsError::Io {
+            path: err.path().map(|path| path.display().to_string()),
+            detail: err.to_string(),
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for SkillsError {
+    fn from(err: zip::result::ZipError) -> Self {
+        SkillsError::InvalidArchive {
+            detail: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for SkillsError {
+    fn from(err: serde_json::Error) -> Self {
+        SkillsError::internal(err)
+    }
+}
+
+impl From<std::path::StripPrefixError> for SkillsError {
+    fn from(err: std::path::StripPrefixError) -> Self {
+        SkillsError::internal(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkillsError;
+
+    #[test]
+    fn serializes_code_message_and_fields() {
+        let error = SkillsError::AlreadyExists {
+            path: "/tmp/skills/docx".to_string(),
+        };
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "AlreadyExists");
+        assert_eq!(value["path"], "/tmp/skills/docx");
+        assert_eq!(value["message"], "Target already exists: /tmp/skills/docx");
+    }
+}
@@ -0,0 +1,153 @@
+//! Progress reporting and cancellation for downloads.
+//!
+//! A [`DownloadJob`] is handed to the install methods of [`crate::SkillsManager`]; the
+//! download loop reports bytes received through it and stops with
+//! [`SkillsError::Cancelled`] once it has been cancelled. [`DownloadJobs`] keeps the jobs
+//! in flight by ID so a separate call can cancel them.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::types::DownloadProgress;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+type ProgressSink = Arc<dyn Fn(DownloadProgress) + Send + Sync>;
+
+/// One download that can be followed and aborted. The default job has no ID, reports
+/// nowhere and is never cancelled.
+#[derive(Clone, Default)]
+pub struct DownloadJob {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    on_progress: Option<ProgressSink>,
+}
+
+impl DownloadJob {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Calls `on_progress` as the response body streams in.
+    pub fn with_progress(
+        mut self,
+        on_progress: impl Fn(DownloadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with [`SkillsError::Cancelled`] once the job has been cancelled.
+    pub(crate) fn check(&self) -> SkillsResult<()> {
+        if self.is_cancelled() {
+            return Err(SkillsError::Cancelled {
+                job_id: self.id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn report(&self, received: u64, total: Option<u64>) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(DownloadProgress {
+                job_id: self.id.clone(),
+                received,
+                total,
+            });
+        }
+    }
+}
+
+impl fmt::Debug for DownloadJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DownloadJob")
+            .field("id", &self.id)
+            .field("cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The jobs currently running, by ID.
+#[derive(Debug, Default)]
+pub struct DownloadJobs {
+    jobs: Mutex<HashMap<String, DownloadJob>>,
+}
+
+impl DownloadJobs {
+    /// Registers `job` so [`DownloadJobs::cancel`] can reach it, replacing any finished job
+    /// that used the same ID.
+    pub fn start(&self, job: DownloadJob) -> DownloadJob {
+        self.lock().insert(job.id.clone(), job.clone());
+        job
+    }
+
+    pub fn finish(&self, job_id: &str) {
+        self.lock().remove(job_id);
+    }
+
+    /// Cancels the job with this ID. Returns `false` when no such job is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.lock().get(job_id) {
+            Some(job) => {
+                job.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, DownloadJob>> {
+        // A panic while holding the lock cannot leave the map half-updated.
+        self.jobs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DownloadJob, DownloadJobs};
+    use crate::error::SkillsError;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn cancels_registered_jobs_and_reports_progress() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let jobs = DownloadJobs::default();
+        let job = jobs.start(DownloadJob::new("docx").with_progress(move |progress| {
+            sink.lock()
+                .unwrap()
+                .push((progress.received, progress.total))
+        }));
+
+        job.report(512, Some(1024));
+        assert!(job.check().is_ok());
+        assert!(jobs.cancel("docx"));
+        assert_eq!(
+            job.check(),
+            Err(SkillsError::Cancelled {
+                job_id: "docx".to_string()
+            })
+        );
+        assert_eq!(*reports.lock().unwrap(), vec![(512, Some(1024))]);
+
+        jobs.finish("docx");
+        assert!(!jobs.cancel("docx"));
+    }
+}
@@ -0,0 +1,29 @@
+//! Skill management logic shared by the desktop app and the CLI.
+//!
+//! Everything is reached through [`SkillsManager`], which is rooted at an explicit home
+//! directory so callers can point it at a temporary directory instead of the real
+//! `~/.skills-manager/skills`.
+
+mod archive_cache;
+mod catalog;
+mod error;
+pub mod jobs;
+mod manager;
+pub mod manifest;
+mod market;
+mod market_index;
+mod market_sources;
+mod network;
+mod project_sync;
+mod provenance;
+mod search;
+pub mod settings;
+mod skills;
+pub mod types;
+mod updates;
+mod utils;
+mod validate;
+mod versions;
+
+pub use crate::error::{SkillsError, SkillsResult};
+pub use crate::manager::SkillsManager;
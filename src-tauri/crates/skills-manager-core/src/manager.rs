@@ -0,0 +1,71 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::utils::path::{normalize_path, resolve_canonical};
+use std::path::{Path, PathBuf};
+
+/// Handle to a Skills Manager installation.
+///
+/// `home` stands in for the user's home directory: default IDE skill directories are
+/// resolved against it, and managed skills live under `manager_root`.
+#[derive(Debug, Clone)]
+pub struct SkillsManager {
+    home: PathBuf,
+    manager_root: PathBuf,
+    app_version: String,
+}
+
+impl SkillsManager {
+    /// Creates a manager that stores skills in `<home>/.skills-manager/skills`.
+    pub fn new(home: impl Into<PathBuf>) -> Self {
+        let home = home.into();
+        let manager_root = home.join(".skills-manager/skills");
+        Self::with_manager_root(home, manager_root)
+    }
+
+    /// Creates a manager with a custom skill storage directory.
+    pub fn with_manager_root(home: impl Into<PathBuf>, manager_root: impl Into<PathBuf>) -> Self {
+        Self {
+            home: home.into(),
+            manager_root: manager_root.into(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Sets the version recorded with installed skills, which defaults to this crate's.
+    pub fn with_app_version(mut self, app_version: impl Into<String>) -> Self {
+        self.app_version = app_version.into();
+        self
+    }
+
+    /// Creates a manager rooted at the current user's home directory.
+    pub fn from_home_dir() -> SkillsResult<Self> {
+        dirs::home_dir()
+            .map(Self::new)
+            .ok_or(SkillsError::HomeDirUnavailable)
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    pub fn manager_root(&self) -> &Path {
+        &self.manager_root
+    }
+
+    pub fn app_version(&self) -> &str {
+        &self.app_version
+    }
+
+    /// `<home>/.skills-manager`, which holds settings and caches next to the default skill root.
+    pub fn data_dir(&self) -> PathBuf {
+        self.home.join(".skills-manager")
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.data_dir().join("cache")
+    }
+
+    /// The manager root with symlinks resolved, for containment checks.
+    pub(crate) fn canonical_manager_root(&self) -> PathBuf {
+        resolve_canonical(&self.manager_root).unwrap_or_else(|| normalize_path(&self.manager_root))
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Frontmatter declared at the top of a `SKILL.md`.
+///
+/// Standard keys are parsed into typed fields; anything else is kept verbatim in `extra`
+/// so the UI can still show author-specific keys.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillManifest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub compatibility: Option<String>,
+    #[serde(
+        default,
+        rename(deserialize = "allowed-tools", serialize = "allowedTools"),
+        deserialize_with = "deserialize_tool_list"
+    )]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Accepts both `allowed-tools: Read Grep` and a YAML list.
+fn deserialize_tool_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ToolList {
+        Inline(String),
+        List(Vec<String>),
+    }
+
+    Ok(match Option::<ToolList>::deserialize(deserializer)? {
+        Some(ToolList::Inline(raw)) => raw
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|tool| !tool.is_empty())
+            .map(|tool| tool.to_string())
+            .collect(),
+        Some(ToolList::List(tools)) => tools,
+        None => Vec::new(),
+    })
+}
+
+/// A `SKILL.md` split into its frontmatter block and markdown body.
+pub struct SkillDocument<'a> {
+    pub frontmatter: Option<&'a str>,
+    /// 1-based line of the first frontmatter line, used to report YAML errors in file terms.
+    pub frontmatter_line: usize,
+    pub body: &'a str,
+    /// 1-based line where the body starts.
+    pub body_line: usize,
+}
+
+pub fn split_frontmatter(content: &str) -> SkillDocument<'_> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let no_frontmatter = SkillDocument {
+        frontmatter: None,
+        frontmatter_line: 0,
+        body: content,
+        body_line: 1,
+    };
+
+    let mut lines = content.split_inclusive('\n');
+    match lines.next() {
+        Some(first) if first.trim_end() == "---" => {}
+        _ => return no_frontmatter,
+    }
+
+    let start = content
+        .find('\n')
+        .map(|idx| idx + 1)
+        .unwrap_or(content.len());
+    let mut offset = start;
+    for (line_no, line) in (2..).zip(lines) {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return SkillDocument {
+                frontmatter: Some(&content[start..offset]),
+                frontmatter_line: 2,
+                body: &content[offset + line.len()..],
+                body_line: line_no + 1,
+            };
+        }
+        offset += line.len();
+    }
+
+    no_frontmatter
+}
+
+/// Parses the frontmatter of a `SKILL.md`. Returns `Ok(None)` when there is no frontmatter.
+pub fn parse_skill_manifest(content: &str) -> Result<Option<SkillManifest>, serde_yaml::Error> {
+    let document = split_frontmatter(content);
+    let Some(frontmatter) = document.frontmatter else {
+        return Ok(None);
+    };
+    if frontmatter.trim().is_empty() {
+        return Ok(Some(SkillManifest::default()));
+    }
+    serde_yaml::from_str(frontmatter).map(Some)
+}
+
+/// Reads and parses `<skill_dir>/SKILL.md`, ignoring unreadable files and malformed YAML.
+pub fn read_skill_manifest(skill_dir: &Path) -> Option<SkillManifest> {
+    let content = fs::read_to_string(skill_dir.join("SKILL.md")).ok()?;
+    parse_skill_manifest(&content).ok().flatten()
+}
+
+/// First paragraph line of the markdown body, used when no description is declared.
+pub fn first_body_paragraph(content: &str) -> String {
+    split_frontmatter(content)
+        .body
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_body_paragraph, parse_skill_manifest, split_frontmatter};
+
+    #[test]
+    fn parses_standard_fields_and_keeps_unknown_keys() {
+        let content = r#"---
+name: "pdf-tools"
+description: >
+  Extract text and tables
+  from PDF files.
+license: Apache-2.0
+allowed-tools: Read Grep, Bash
+metadata:
+  version: "1.2"
+x-team: docs
+---
+# PDF tools
+
+Body text.
+"#;
+        let manifest = parse_skill_manifest(content).unwrap().unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("pdf-tools"));
+        assert_eq!(
+            manifest.description.as_deref(),
+            Some("Extract text and tables from PDF files.\n")
+        );
+        assert_eq!(manifest.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(manifest.allowed_tools, vec!["Read", "Grep", "Bash"]);
+        assert_eq!(manifest.metadata["version"], "1.2");
+        assert_eq!(manifest.extra["x-team"], "docs");
+    }
+
+    #[test]
+    fn accepts_allowed_tools_as_a_list() {
+        let content = "---\nname: lint\nallowed-tools:\n  - Read\n  - Edit\n---\n";
+        let manifest = parse_skill_manifest(content).unwrap().unwrap();
+        assert_eq!(manifest.allowed_tools, vec!["Read", "Edit"]);
+    }
+
+    #[test]
+    fn returns_none_without_frontmatter() {
+        let content = "# Title\n\nFirst paragraph.\n";
+        assert!(parse_skill_manifest(content).unwrap().is_none());
+        assert_eq!(first_body_paragraph(content), "First paragraph.");
+    }
+
+    #[test]
+    fn tracks_body_line_after_frontmatter() {
+        let document = split_frontmatter("---\nname: a\n---\nbody\n");
+        assert_eq!(document.frontmatter, Some("name: a\n"));
+        assert_eq!(document.body, "body\n");
+        assert_eq!(document.body_line, 4);
+    }
+}
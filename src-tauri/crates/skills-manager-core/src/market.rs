@@ -0,0 +1,782 @@
+use crate::catalog::is_catalog_url;
+use crate::error::{SkillsError, SkillsResult};
+use crate::jobs::DownloadJob;
+use crate::market_index::{CachedSkill, CatalogMapping};
+use crate::market_sources::build_source;
+use crate::provenance::write_provenance;
+use crate::search::SearchQuery;
+use crate::settings::MarketSourceConfig;
+use crate::skills::read_skill_metadata;
+use crate::types::{
+    DownloadRequest, DownloadResult, FacetCount, LocalSkill, MarketFacets, MarketSearchFilters,
+    MarketSort, MarketSourceError, RemoteSkillView, RemoteSkillsViewResponse, SourceInstallRequest,
+    SourceSkill,
+};
+use crate::utils::download::{
+    download_skill_to_dir, install_target_dir, is_downloadable_source_url, FetchContext,
+    FetchedSource,
+};
+use crate::utils::staging::swap_in_skill_dir;
+use crate::SkillsManager;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Whether the downloader can install `source_url`, directly or after resolving a catalog
+/// page URL through `mappings` or the catalog page itself.
+fn is_supported_market_source_url(source_url: &str, mappings: &[CatalogMapping]) -> bool {
+    let trimmed = source_url.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+
+    if is_catalog_url(trimmed) {
+        return true;
+    }
+    if let Some(resolved) = mappings.iter().find_map(|mapping| mapping.apply(trimmed)) {
+        return is_supported_market_source_url(&resolved, &[]);
+    }
+
+    // Local directories are only checked when the skill is installed.
+    trimmed.starts_with("file://") || is_downloadable_source_url(trimmed)
+}
+
+/// Results from different sources that point at the same place are the same skill.
+fn source_url_key(source_url: &str) -> String {
+    source_url
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_ascii_lowercase()
+}
+
+/// Mirrors the frontend's `normalizeSkillName`, so `installed` agrees with the badges the
+/// UI derives from local skill names.
+fn normalize_skill_name(name: &str) -> String {
+    let lowered = name.trim().to_ascii_lowercase();
+    let stem = lowered
+        .strip_suffix(".git")
+        .or_else(|| lowered.strip_suffix(".zip"))
+        .unwrap_or(&lowered);
+    stem.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// What is already in the manager directory, keyed the ways a market result can match it.
+struct InstalledSkills {
+    source_keys: HashSet<String>,
+    names: HashSet<String>,
+}
+
+impl InstalledSkills {
+    fn new(local_skills: &[LocalSkill]) -> Self {
+        let mut source_keys = HashSet::new();
+        let mut names = HashSet::new();
+        for skill in local_skills {
+            if let Some(source_url) = &skill.source_url {
+                source_keys.insert(source_url_key(source_url));
+            }
+            names.insert(normalize_skill_name(&skill.name));
+            let dir_name = std::path::Path::new(&skill.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            names.insert(normalize_skill_name(&dir_name));
+        }
+        names.remove("");
+        Self { source_keys, names }
+    }
+
+    fn contains(&self, skill: &CachedSkill) -> bool {
+        self.source_keys
+            .contains(&source_url_key(&skill.source_url))
+            || self.names.contains(&normalize_skill_name(&skill.name))
+    }
+}
+
+/// A matched result plus the fields only sorting needs.
+struct Candidate {
+    view: RemoteSkillView,
+    added_at: Option<String>,
+    position: usize,
+}
+
+/// `None` and blank filters match everything; values compare case-insensitively.
+fn matches_filter(filter: Option<&str>, value: &str) -> bool {
+    match filter.map(str::trim).filter(|filter| !filter.is_empty()) {
+        Some(filter) => filter.eq_ignore_ascii_case(value.trim()),
+        None => true,
+    }
+}
+
+/// Which of the category, author, market and installed filters `view` passes, in that order.
+fn filter_matches(filters: &MarketSearchFilters, view: &RemoteSkillView) -> [bool; 4] {
+    [
+        matches_filter(filters.category.as_deref(), &view.namespace),
+        matches_filter(filters.author.as_deref(), &view.author),
+        matches_filter(filters.market_id.as_deref(), &view.market_id),
+        filters
+            .installed
+            .is_none_or(|installed| installed == view.installed),
+    ]
+}
+
+fn market_facets(filters: &MarketSearchFilters, candidates: &[Candidate]) -> MarketFacets {
+    let mut counts: [HashMap<String, FacetCount>; 3] = Default::default();
+    for candidate in candidates {
+        let view = &candidate.view;
+        let passes = filter_matches(filters, view);
+        let facets = [
+            (&view.namespace, &view.namespace),
+            (&view.author, &view.author),
+            (&view.market_id, &view.market_label),
+        ];
+        for (facet, (value, label)) in facets.into_iter().enumerate() {
+            let others_pass = passes
+                .iter()
+                .enumerate()
+                .all(|(index, passed)| index == facet || *passed);
+            if !others_pass || value.trim().is_empty() {
+                continue;
+            }
+            counts[facet]
+                .entry(value.clone())
+                .or_insert_with(|| FacetCount {
+                    value: value.clone(),
+                    label: label.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    let [categories, authors, markets] = counts.map(|counts| {
+        let mut counts: Vec<FacetCount> = counts.into_values().collect();
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        counts
+    });
+    MarketFacets {
+        categories,
+        authors,
+        markets,
+    }
+}
+
+/// Stable, so ties keep the relevance order and then the source and index order.
+fn sort_candidates(candidates: &mut [Candidate], sort: MarketSort) {
+    candidates.sort_by(|a, b| b.view.score.total_cmp(&a.view.score));
+    match sort {
+        MarketSort::Relevance => {}
+        MarketSort::Name => {
+            candidates.sort_by_cached_key(|candidate| candidate.view.name.to_lowercase())
+        }
+        MarketSort::Recent => candidates.sort_by(|a, b| {
+            b.added_at
+                .cmp(&a.added_at)
+                .then_with(|| b.position.cmp(&a.position))
+        }),
+        MarketSort::Stars => {
+            candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.view.stars))
+        }
+        MarketSort::Installs => {
+            candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.view.installs))
+        }
+    }
+}
+
+fn remote_skill_view(
+    config: &MarketSourceConfig,
+    skill: &CachedSkill,
+    score: f64,
+    installed: bool,
+) -> RemoteSkillView {
+    RemoteSkillView {
+        id: format!("{}:{}", config.id, skill.slug),
+        name: skill.name.clone(),
+        namespace: skill.category.clone(),
+        source_url: skill.source_url.clone(),
+        description: skill.summary.clone(),
+        description_zh: skill.summary_zh.clone(),
+        author: skill.author.clone(),
+        installs: skill.installs,
+        stars: skill.stars,
+        market_id: config.id.clone(),
+        market_label: config.label.clone(),
+        score,
+        installed,
+    }
+}
+
+impl SkillsManager {
+    pub fn search_marketplaces(
+        &self,
+        query: &str,
+        limit: u64,
+        offset: u64,
+        filters: &MarketSearchFilters,
+    ) -> SkillsResult<RemoteSkillsViewResponse> {
+        let query = SearchQuery::new(query);
+        let limit = if limit == 0 { 20 } else { limit };
+
+        let configs: Vec<MarketSourceConfig> = self
+            .load_settings()?
+            .market_sources
+            .into_iter()
+            .filter(|config| config.enabled)
+            .collect();
+        // Remote sources can be slow, so they load in parallel; results are merged in the
+        // configured order so deduplication stays deterministic.
+        let loaded: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = configs
+                .iter()
+                .map(|config| scope.spawn(move || build_source(config).load(self)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(SkillsError::internal("Market source loader panicked"))
+                    })
+                })
+                .collect()
+        });
+
+        let installed = InstalledSkills::new(&self.manager_skills());
+        let mut seen_urls = HashSet::new();
+        let mut candidates = Vec::new();
+        let mut source_errors = Vec::new();
+        for (config, result) in configs.iter().zip(loaded) {
+            let index = match result {
+                Ok(index) => index,
+                Err(error) => {
+                    source_errors.push(MarketSourceError {
+                        market_id: config.id.clone(),
+                        market_label: config.label.clone(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            for (position, skill) in index.skills.iter().enumerate().filter(|(_, skill)| {
+                is_supported_market_source_url(&skill.source_url, &index.catalog_mappings)
+            }) {
+                let Some(score) = query.score(skill) else {
+                    continue;
+                };
+                if seen_urls.insert(source_url_key(&skill.source_url)) {
+                    candidates.push(Candidate {
+                        view: remote_skill_view(config, skill, score, installed.contains(skill)),
+                        added_at: skill.added_at.clone(),
+                        position,
+                    });
+                }
+            }
+        }
+
+        let facets = market_facets(filters, &candidates);
+        candidates.retain(|candidate| {
+            filter_matches(filters, &candidate.view)
+                .iter()
+                .all(|passed| *passed)
+        });
+        sort_candidates(&mut candidates, filters.sort);
+
+        let total = candidates.len() as u64;
+        let skills: Vec<RemoteSkillView> = candidates
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|candidate| candidate.view)
+            .collect();
+
+        Ok(RemoteSkillsViewResponse {
+            skills,
+            total,
+            limit,
+            offset,
+            source_errors,
+            facets,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::items_after_test_module)]
+mod tests {
+    use super::is_supported_market_source_url;
+    use crate::error::SkillsError;
+    use crate::jobs::DownloadJob;
+    use crate::market_index::CatalogMapping;
+    use crate::settings::{MarketSourceConfig, MarketSourceKind, Settings};
+    use crate::types::{DownloadRequest, MarketSearchFilters, MarketSort, SourceInstallRequest};
+    use crate::utils::path::file_url;
+    use crate::SkillsManager;
+    use std::fs;
+
+    #[test]
+    fn recognizes_installable_market_sources() {
+        assert!(is_supported_market_source_url(
+            "https://github.com/owner/repo",
+            &[]
+        ));
+        assert!(is_supported_market_source_url(
+            "https://github.com/owner/repo/tree/main/skills/example",
+            &[]
+        ));
+        assert!(is_supported_market_source_url(
+            "https://example.com/files/skill.zip?download=1",
+            &[]
+        ));
+        assert!(is_supported_market_source_url(
+            "https://gitlab.example.com/team/skills/-/tree/main/docx",
+            &[]
+        ));
+    }
+
+    #[test]
+    fn accepts_resolvable_catalog_urls() {
+        assert!(is_supported_market_source_url(
+            "https://officialskills.sh/anthropics/skills/docx",
+            &[]
+        ));
+        let mappings = [CatalogMapping {
+            catalog: "https://skills.example.com/".to_string(),
+            source: "https://github.com/example/skills/tree/main/".to_string(),
+        }];
+        assert!(is_supported_market_source_url(
+            "https://skills.example.com/docx",
+            &mappings
+        ));
+        assert!(!is_supported_market_source_url(
+            "https://skills.example.com/docx",
+            &[]
+        ));
+    }
+
+    #[test]
+    fn rejects_sources_the_downloader_cannot_install() {
+        assert!(!is_supported_market_source_url(
+            "https://github.com/owner/repo/blob/main/SKILL.md",
+            &[]
+        ));
+        assert!(!is_supported_market_source_url(
+            "https://catalog.redhat.com/en/ai/skills/detail/example",
+            &[]
+        ));
+    }
+
+    #[test]
+    fn fans_out_across_sources_and_dedupes_by_source_url() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-market-sources-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let skill_dir = home.join("shared/docx");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: docx\ndescription: Internal Word skill\n---\n",
+        )
+        .unwrap();
+
+        let manager = SkillsManager::new(&home);
+        let local = |id: &str| MarketSourceConfig {
+            id: id.to_string(),
+            label: id.to_string(),
+            enabled: true,
+            kind: MarketSourceKind::LocalDir {
+                path: home.join("shared").display().to_string(),
+            },
+        };
+        let missing = MarketSourceConfig {
+            kind: MarketSourceKind::LocalDir {
+                path: home.join("missing").display().to_string(),
+            },
+            ..local("broken")
+        };
+        manager
+            .save_settings(&Settings {
+                market_sources: vec![local("internal"), local("mirror"), missing],
+                ..Settings::default()
+            })
+            .unwrap();
+
+        let response = manager
+            .search_marketplaces("internal word", 20, 0, &MarketSearchFilters::default())
+            .unwrap();
+        assert_eq!(response.total, 1);
+        assert_eq!(response.skills[0].market_id, "internal");
+        assert_eq!(response.skills[0].id, "internal:docx");
+        assert_eq!(response.source_errors.len(), 1);
+        assert_eq!(response.source_errors[0].market_id, "broken");
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn filters_sorts_and_counts_facets() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-market-filters-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let write_skill = |dir: std::path::PathBuf, name: &str| {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: The {name} skill\n---\n"),
+            )
+            .unwrap();
+        };
+        write_skill(home.join("team-a/gamma"), "gamma");
+        write_skill(home.join("team-a/alpha"), "alpha");
+        write_skill(home.join("team-b/beta"), "beta");
+
+        let manager = SkillsManager::new(&home);
+        write_skill(manager.manager_root().join("alpha"), "alpha");
+        let local = |id: &str, dir: &str| MarketSourceConfig {
+            id: id.to_string(),
+            label: id.to_uppercase(),
+            enabled: true,
+            kind: MarketSourceKind::LocalDir {
+                path: home.join(dir).display().to_string(),
+            },
+        };
+        manager
+            .save_settings(&Settings {
+                market_sources: vec![local("a", "team-a"), local("b", "team-b")],
+                ..Settings::default()
+            })
+            .unwrap();
+        let search = |filters: MarketSearchFilters| {
+            manager.search_marketplaces("", 20, 0, &filters).unwrap()
+        };
+
+        let response = search(MarketSearchFilters {
+            market_id: Some("b".to_string()),
+            ..MarketSearchFilters::default()
+        });
+        assert_eq!(response.total, 1);
+        assert_eq!(response.skills[0].name, "beta");
+        let markets: Vec<_> = response
+            .facets
+            .markets
+            .iter()
+            .map(|facet| (facet.value.as_str(), facet.label.as_str(), facet.count))
+            .collect();
+        assert_eq!(markets, [("a", "A", 2), ("b", "B", 1)]);
+        assert_eq!(response.facets.categories.len(), 1);
+        assert_eq!(response.facets.categories[0].value, "team-b");
+
+        let response = search(MarketSearchFilters {
+            installed: Some(true),
+            ..MarketSearchFilters::default()
+        });
+        assert_eq!(response.total, 1);
+        assert_eq!(response.skills[0].name, "alpha");
+        assert!(response.skills[0].installed);
+
+        let response = search(MarketSearchFilters {
+            installed: Some(false),
+            sort: MarketSort::Name,
+            ..MarketSearchFilters::default()
+        });
+        let names: Vec<_> = response
+            .skills
+            .iter()
+            .map(|skill| skill.name.as_str())
+            .collect();
+        assert_eq!(names, ["beta", "gamma"]);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn lists_and_installs_several_skills_from_one_source() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-multi-install-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let repo = home.join("repo");
+        for name in ["docx", "pdf", "xlsx"] {
+            let dir = repo.join("skills").join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: Handles {name} files\n---\n"),
+            )
+            .unwrap();
+        }
+        let manager = SkillsManager::new(&home);
+        let source_url = file_url(&repo);
+
+        let listed = manager.list_skills_in_source(&source_url).unwrap();
+        let subpaths: Vec<_> = listed.iter().map(|skill| skill.subpath.as_str()).collect();
+        assert_eq!(subpaths, ["skills/docx", "skills/pdf", "skills/xlsx"]);
+        assert_eq!(listed[1].name, "pdf");
+        assert_eq!(listed[1].description, "Handles pdf files");
+        assert_eq!(listed[1].source_url, file_url(&repo.join("skills/pdf")));
+
+        let request = || SourceInstallRequest {
+            source_url: source_url.clone(),
+            subpaths: vec!["skills/docx".to_string(), "skills/xlsx".to_string()],
+            install_base_dir: manager.manager_root().display().to_string(),
+            overwrite: false,
+            job_id: None,
+        };
+        let job = DownloadJob::default();
+        let installed = manager.install_skills_from_source(request(), &job).unwrap();
+        assert_eq!(installed.len(), 2);
+        assert!(manager.manager_root().join("xlsx/SKILL.md").is_file());
+        assert!(!manager.manager_root().join("pdf").exists());
+        let metadata =
+            fs::read_to_string(manager.manager_root().join("docx/.skills-manager.json")).unwrap();
+        assert!(metadata.contains("skills/docx"));
+
+        let error = manager
+            .install_skills_from_source(request(), &job)
+            .unwrap_err();
+        assert!(matches!(error, SkillsError::AlreadyExists { .. }));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn failed_updates_keep_the_installed_version() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-atomic-update-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let source = home.join("source/docx");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("SKILL.md"), "# v1").unwrap();
+        let manager = SkillsManager::new(&home);
+        let request = || DownloadRequest {
+            source_url: file_url(&source),
+            skill_name: "docx".to_string(),
+            install_base_dir: manager.manager_root().display().to_string(),
+            job_id: None,
+        };
+        let job = DownloadJob::default();
+        manager.download_marketplace_skill(request(), &job).unwrap();
+        let installed = manager.manager_root().join("docx");
+
+        // The new version is in place when writing its metadata fails.
+        fs::write(source.join("SKILL.md"), "# v2").unwrap();
+        fs::create_dir_all(source.join(".skills-manager.json")).unwrap();
+        manager
+            .update_marketplace_skill(request(), &job)
+            .unwrap_err();
+        assert_eq!(
+            fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+            "# v1"
+        );
+        assert!(installed.join(".skills-manager.json").is_file());
+
+        fs::remove_dir_all(&source).unwrap();
+        manager
+            .update_marketplace_skill(request(), &job)
+            .unwrap_err();
+        assert_eq!(
+            fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+            "# v1"
+        );
+        let entries: Vec<_> = fs::read_dir(manager.manager_root())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["docx"]);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
+
+impl SkillsManager {
+    pub(crate) fn fetch_context<'a>(&self, job: &'a DownloadJob) -> SkillsResult<FetchContext<'a>> {
+        Ok(FetchContext {
+            job,
+            cache: self.archive_cache()?,
+            net: self.network_config()?,
+        })
+    }
+
+    /// Installs the skill at `request.source_url`, reporting progress to and stopping on
+    /// cancellation of `job`.
+    pub fn download_marketplace_skill(
+        &self,
+        request: DownloadRequest,
+        job: &DownloadJob,
+    ) -> SkillsResult<DownloadResult> {
+        if request.install_base_dir.trim().is_empty() {
+            return Err(SkillsError::invalid_request(
+                "An install directory is required",
+            ));
+        }
+
+        let install_base_dir = PathBuf::from(&request.install_base_dir);
+        let resolved_url = self.resolve_source_url(&request.source_url)?;
+        let ctx = self.fetch_context(job)?;
+        let history = self.version_history()?;
+        let installed = download_skill_to_dir(
+            &resolved_url,
+            &request.skill_name,
+            &install_base_dir,
+            self.manager_root(),
+            false,
+            &ctx,
+        )?;
+        let provenance = self.new_provenance(
+            &request.source_url,
+            &resolved_url,
+            installed.subpath.clone(),
+            &installed.origin,
+        );
+        write_provenance(&installed.dir, &provenance)?;
+
+        Ok(DownloadResult {
+            installed_path: installed.finish(history.as_ref())?.display().to_string(),
+        })
+    }
+
+    /// Downloads `source_url` once and lists every skill in it, for sources such as
+    /// `anthropics/skills` that bundle many.
+    pub fn list_skills_in_source(&self, source_url: &str) -> SkillsResult<Vec<SourceSkill>> {
+        let resolved_url = self.resolve_source_url(source_url)?;
+        let job = DownloadJob::default();
+        let ctx = self.fetch_context(&job)?;
+        let fetched = FetchedSource::open(&resolved_url, &ctx)?;
+        fetched
+            .skill_subpaths()?
+            .into_iter()
+            .map(|subpath| {
+                let subpath_str = subpath.to_string_lossy().replace('\\', "/");
+                let metadata = read_skill_metadata(&fetched.skill_dir(&subpath_str)?);
+                Ok(SourceSkill {
+                    source_url: fetched.skill_source_url(&subpath),
+                    subpath: subpath_str,
+                    name: metadata.name,
+                    description: metadata.description,
+                })
+            })
+            .collect()
+    }
+
+    /// Installs the selected skills from one download of the source. Every target is checked
+    /// before anything is written, so a conflict leaves the install directory untouched, and
+    /// a failure part way puts back every skill replaced so far.
+    pub fn install_skills_from_source(
+        &self,
+        request: SourceInstallRequest,
+        job: &DownloadJob,
+    ) -> SkillsResult<Vec<DownloadResult>> {
+        if request.install_base_dir.trim().is_empty() {
+            return Err(SkillsError::invalid_request(
+                "An install directory is required",
+            ));
+        }
+        if request.subpaths.is_empty() {
+            return Err(SkillsError::invalid_request(
+                "Select at least one skill to install",
+            ));
+        }
+
+        let install_base_dir = PathBuf::from(&request.install_base_dir);
+        let resolved_url = self.resolve_source_url(&request.source_url)?;
+        let ctx = self.fetch_context(job)?;
+        let fetched = FetchedSource::open(&resolved_url, &ctx)?;
+
+        let mut planned = Vec::new();
+        let mut targets = HashSet::new();
+        for subpath in &request.subpaths {
+            let skill_dir = fetched.skill_dir(subpath)?;
+            let source_url = fetched.skill_source_url(std::path::Path::new(subpath.trim()));
+            let target_dir = install_target_dir(
+                &read_skill_metadata(&skill_dir).name,
+                &source_url,
+                &install_base_dir,
+                self.manager_root(),
+                request.overwrite,
+            )?;
+            if !targets.insert(target_dir.clone()) {
+                return Err(SkillsError::already_exists(&target_dir));
+            }
+            planned.push((skill_dir, target_dir, source_url));
+        }
+        job.check()?;
+
+        let mut swaps = Vec::new();
+        let mut results = Vec::new();
+        for (skill_dir, target_dir, source_url) in planned {
+            swaps.push(swap_in_skill_dir(&skill_dir, &target_dir)?);
+            let provenance = self.new_provenance(
+                &source_url,
+                &source_url,
+                fetched.subpath_of(&skill_dir),
+                &fetched.origin,
+            );
+            write_provenance(&target_dir, &provenance)?;
+            results.push(DownloadResult {
+                installed_path: target_dir.display().to_string(),
+            });
+        }
+        let history = self.version_history()?;
+        // Every swap is finished before any archiving error is reported, since dropping one
+        // would put its previous version back.
+        let archived: Vec<SkillsResult<()>> = swaps
+            .into_iter()
+            .map(|swap| swap.finish(history.as_ref()))
+            .collect();
+        archived.into_iter().collect::<SkillsResult<()>>()?;
+        for result in &results {
+            self.refresh_managed_copies(std::path::Path::new(&result.installed_path));
+        }
+        Ok(results)
+    }
+
+    pub fn update_marketplace_skill(
+        &self,
+        request: DownloadRequest,
+        job: &DownloadJob,
+    ) -> SkillsResult<DownloadResult> {
+        if request.install_base_dir.trim().is_empty() {
+            return Err(SkillsError::invalid_request(
+                "An install directory is required",
+            ));
+        }
+        if request.source_url.trim().is_empty() {
+            return Err(SkillsError::invalid_request(
+                "A source URL is required to update a skill",
+            ));
+        }
+
+        let install_base_dir = PathBuf::from(&request.install_base_dir);
+        let resolved_url = self.resolve_source_url(&request.source_url)?;
+        let ctx = self.fetch_context(job)?;
+        let history = self.version_history()?;
+        let installed = download_skill_to_dir(
+            &resolved_url,
+            &request.skill_name,
+            &install_base_dir,
+            self.manager_root(),
+            true,
+            &ctx,
+        )?;
+        let provenance = self.new_provenance(
+            &request.source_url,
+            &resolved_url,
+            installed.subpath.clone(),
+            &installed.origin,
+        );
+        write_provenance(&installed.dir, &provenance)?;
+        let installed_path = installed.finish(history.as_ref())?;
+        self.refresh_managed_copies(&installed_path);
+
+        Ok(DownloadResult {
+            installed_path: installed_path.display().to_string(),
+        })
+    }
+}
@@ -0,0 +1,422 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::network::NetworkConfig;
+use crate::settings::{MarketSourceKind, Settings};
+use crate::types::{IndexSourceState, MarketIndexRefresh, MarketIndexSourceStatus};
+use crate::utils::download::{conditional_get, ConditionalFetch};
+use crate::utils::path::{short_stable_hash, write_atomically};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Shipped with the binary so the marketplace works before the first refresh and offline.
+const BUNDLED_INDEX: &str = include_str!("../../../data/skills-index.json");
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct CachedSkill {
+    pub slug: String,
+    pub name: String,
+    pub summary: String,
+    #[serde(default)]
+    pub summary_zh: String,
+    pub source_url: String,
+    pub category: String,
+    pub author: String,
+    #[serde(default)]
+    pub stars: u64,
+    #[serde(default)]
+    pub installs: u64,
+    /// When the skill was added to its market, as an RFC 3339 timestamp if the source knows.
+    #[serde(default)]
+    pub added_at: Option<String>,
+}
+
+/// Rewrites catalog page URLs starting with `catalog` into installable URLs starting with
+/// `source`, e.g. an officialskills.sh owner/repo prefix into the repo's GitHub tree.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct CatalogMapping {
+    pub catalog: String,
+    pub source: String,
+}
+
+impl CatalogMapping {
+    pub fn apply(&self, url: &str) -> Option<String> {
+        url.strip_prefix(&self.catalog)
+            .map(|rest| format!("{}{}", self.source, rest))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SkillsIndex {
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    catalog_mappings: Vec<CatalogMapping>,
+    skills: Vec<CachedSkill>,
+}
+
+/// The merged skills index the marketplace searches.
+#[derive(Debug, Default)]
+pub(crate) struct MarketIndex {
+    pub updated_at: Option<String>,
+    pub catalog_mappings: Vec<CatalogMapping>,
+    pub skills: Vec<CachedSkill>,
+}
+
+/// Validators stored next to each cached index document.
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+type IndexKey = (PathBuf, Vec<String>);
+
+/// Parsing an index takes a few milliseconds, so merged results are kept per cache
+/// directory and URL list until the next refresh.
+static LOADED_INDEXES: OnceLock<Mutex<HashMap<IndexKey, Arc<MarketIndex>>>> = OnceLock::new();
+
+fn loaded_indexes() -> &'static Mutex<HashMap<IndexKey, Arc<MarketIndex>>> {
+    LOADED_INDEXES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn parse_index(raw: &[u8]) -> Result<SkillsIndex, serde_json::Error> {
+    serde_json::from_slice(raw)
+}
+
+fn bundled_index() -> SkillsIndex {
+    parse_index(BUNDLED_INDEX.as_bytes()).unwrap_or(SkillsIndex {
+        updated_at: None,
+        catalog_mappings: vec![],
+        skills: vec![],
+    })
+}
+
+/// Adds `bundled` to the cached indexes as a layer of its own, ahead of the cached copies
+/// that are older than it, so an index shipped with a newer app is not hidden by a stale
+/// cache, and behind the rest, so it still fills in what they lack.
+fn with_bundled_layer(mut cached: Vec<SkillsIndex>, bundled: SkillsIndex) -> Vec<SkillsIndex> {
+    let position = cached
+        .iter()
+        .position(|index| index.updated_at < bundled.updated_at)
+        .unwrap_or(cached.len());
+    cached.insert(position, bundled);
+    cached
+}
+
+/// Concatenates indexes in priority order, keeping the first entry for each slug.
+fn merge_indexes(indexes: Vec<SkillsIndex>) -> MarketIndex {
+    let mut seen = HashSet::new();
+    let mut merged = MarketIndex::default();
+    for index in indexes {
+        if index.updated_at > merged.updated_at {
+            merged.updated_at = index.updated_at;
+        }
+        for mapping in index.catalog_mappings {
+            if !merged.catalog_mappings.contains(&mapping) {
+                merged.catalog_mappings.push(mapping);
+            }
+        }
+        for skill in index.skills {
+            if seen.insert(skill.slug.clone()) {
+                merged.skills.push(skill);
+            }
+        }
+    }
+    merged
+}
+
+fn index_cache_dir(manager: &SkillsManager) -> PathBuf {
+    manager.cache_dir().join("market-index")
+}
+
+fn cache_paths(cache_dir: &Path, url: &str) -> (PathBuf, PathBuf) {
+    let key = short_stable_hash(url);
+    (
+        cache_dir.join(format!("{}.json", key)),
+        cache_dir.join(format!("{}.meta.json", key)),
+    )
+}
+
+fn read_meta(path: &Path, url: &str) -> CacheMeta {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<CacheMeta>(&raw).ok())
+        .filter(|meta| meta.url == url)
+        .unwrap_or_default()
+}
+
+fn load_cached_indexes(cache_dir: &Path, urls: &[String]) -> Vec<SkillsIndex> {
+    urls.iter()
+        .filter_map(|url| {
+            let (data_path, _) = cache_paths(cache_dir, url);
+            let raw = fs::read(data_path).ok()?;
+            parse_index(&raw).ok()
+        })
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Every index URL that `refresh_market_index` revalidates, without duplicates.
+fn refreshable_urls(settings: &Settings) -> Vec<String> {
+    let mut urls = settings.market_index_urls.clone();
+    for source in settings
+        .market_sources
+        .iter()
+        .filter(|source| source.enabled)
+    {
+        if let MarketSourceKind::IndexUrl { url } = &source.kind {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+    }
+    urls
+}
+
+impl SkillsManager {
+    /// The built-in skills index: cached copies of `market_index_urls` layered with the
+    /// index bundled with the app.
+    pub(crate) fn market_index(&self) -> SkillsResult<Arc<MarketIndex>> {
+        let urls = self.load_settings()?.market_index_urls;
+        self.load_index(&urls, true)
+    }
+
+    /// A user-added index URL, fetched on first use.
+    pub(crate) fn index_url_index(&self, url: &str) -> SkillsResult<Arc<MarketIndex>> {
+        let cache_dir = index_cache_dir(self);
+        let (data_path, _) = cache_paths(&cache_dir, url);
+        if !data_path.exists() {
+            fs::create_dir_all(&cache_dir).map_err(|err| SkillsError::io(&cache_dir, err))?;
+            refresh_source(&cache_dir, url, &self.network_config()?)?;
+        }
+        self.load_index(&[url.to_string()], false)
+    }
+
+    fn load_index(&self, urls: &[String], with_bundled: bool) -> SkillsResult<Arc<MarketIndex>> {
+        let key = (index_cache_dir(self), urls.to_vec());
+        let mut loaded = loaded_indexes().lock().map_err(SkillsError::internal)?;
+        if let Some(index) = loaded.get(&key) {
+            return Ok(index.clone());
+        }
+
+        let mut layers = load_cached_indexes(&key.0, urls);
+        if with_bundled {
+            layers = with_bundled_layer(layers, bundled_index());
+        }
+        let index = Arc::new(merge_indexes(layers));
+        loaded.insert(key, index.clone());
+        Ok(index)
+    }
+
+    /// Catalog mappings declared by the built-in index and cached index URL sources.
+    pub(crate) fn catalog_mappings(&self) -> SkillsResult<Vec<CatalogMapping>> {
+        let settings = self.load_settings()?;
+        let mut mappings = self.market_index()?.catalog_mappings.clone();
+        for url in refreshable_urls(&settings) {
+            if settings.market_index_urls.contains(&url) {
+                continue;
+            }
+            let index = self.load_index(&[url], false)?;
+            mappings.extend(index.catalog_mappings.iter().cloned());
+        }
+        Ok(mappings)
+    }
+
+    /// Slugs across the built-in index and every cached index URL source.
+    fn indexed_slugs(&self, settings: &Settings) -> SkillsResult<HashSet<String>> {
+        let mut slugs: HashSet<String> = self
+            .market_index()?
+            .skills
+            .iter()
+            .map(|skill| skill.slug.clone())
+            .collect();
+        for url in refreshable_urls(settings) {
+            if settings.market_index_urls.contains(&url) {
+                continue;
+            }
+            let index = self.load_index(&[url], false)?;
+            slugs.extend(index.skills.iter().map(|skill| skill.slug.clone()));
+        }
+        Ok(slugs)
+    }
+
+    /// Revalidates every index URL (the built-in index and user-added index sources) and
+    /// reports how the indexed skills changed.
+    ///
+    /// Sources that fail keep their previous cached copy. The call only fails when every
+    /// source failed.
+    pub fn refresh_market_index(&self) -> SkillsResult<MarketIndexRefresh> {
+        let settings = self.load_settings()?;
+        let urls = refreshable_urls(&settings);
+        if urls.is_empty() {
+            return Err(SkillsError::invalid_request(
+                "No market index URLs are configured",
+            ));
+        }
+        let cache_dir = index_cache_dir(self);
+        fs::create_dir_all(&cache_dir).map_err(|err| SkillsError::io(&cache_dir, err))?;
+
+        let before = self.indexed_slugs(&settings)?;
+        let net = self.network_config()?;
+        let mut sources = Vec::new();
+        let mut first_error = None;
+        for url in &urls {
+            let state = match refresh_source(&cache_dir, url, &net) {
+                Ok(state) => state,
+                Err(err) => {
+                    sources.push(MarketIndexSourceStatus {
+                        url: url.clone(),
+                        state: IndexSourceState::Failed,
+                        error: Some(err.clone()),
+                    });
+                    first_error.get_or_insert(err);
+                    continue;
+                }
+            };
+            sources.push(MarketIndexSourceStatus {
+                url: url.clone(),
+                state,
+                error: None,
+            });
+        }
+        if let Some(err) = first_error {
+            if sources
+                .iter()
+                .all(|source| source.state == IndexSourceState::Failed)
+            {
+                return Err(err);
+            }
+        }
+
+        loaded_indexes()
+            .lock()
+            .map_err(SkillsError::internal)?
+            .retain(|(dir, _), _| *dir != cache_dir);
+        let after = self.indexed_slugs(&settings)?;
+        let builtin = self.market_index()?;
+
+        Ok(MarketIndexRefresh {
+            updated_at: builtin.updated_at.clone(),
+            total: after.len() as u64,
+            added: after.difference(&before).count() as u64,
+            removed: before.difference(&after).count() as u64,
+            sources,
+        })
+    }
+}
+
+fn refresh_source(
+    cache_dir: &Path,
+    url: &str,
+    net: &NetworkConfig,
+) -> SkillsResult<IndexSourceState> {
+    let (data_path, meta_path) = cache_paths(cache_dir, url);
+    let meta = if data_path.exists() {
+        read_meta(&meta_path, url)
+    } else {
+        CacheMeta::default()
+    };
+
+    match conditional_get(
+        url,
+        meta.etag.as_deref(),
+        meta.last_modified.as_deref(),
+        net,
+    )? {
+        ConditionalFetch::NotModified => Ok(IndexSourceState::NotModified),
+        ConditionalFetch::Fetched {
+            body,
+            etag,
+            last_modified,
+        } => {
+            parse_index(&body).map_err(|err| SkillsError::InvalidMarketIndex {
+                url: url.to_string(),
+                detail: err.to_string(),
+            })?;
+            write_atomically(&data_path, &body)?;
+            let meta = CacheMeta {
+                url: url.to_string(),
+                etag,
+                last_modified,
+                fetched_at: now_secs(),
+            };
+            write_atomically(&meta_path, serde_json::to_string_pretty(&meta)?.as_bytes())?;
+            Ok(IndexSourceState::Updated)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bundled_index, merge_indexes, parse_index, with_bundled_layer};
+
+    #[test]
+    fn bundled_index_parses() {
+        let index = bundled_index();
+        assert!(index.updated_at.is_some());
+        assert!(!index.skills.is_empty());
+    }
+
+    #[test]
+    fn merges_indexes_by_slug_and_keeps_the_newest_date() {
+        let first = parse_index(
+            br#"{"updated_at":"2026-07-04","skills":[
+                {"slug":"a","name":"a","summary":"","source_url":"https://github.com/o/a","category":"c","author":"o"}
+            ]}"#,
+        )
+        .unwrap();
+        let second = parse_index(
+            br#"{"updated_at":"2026-09-01","skills":[
+                {"slug":"a","name":"dup","summary":"","source_url":"https://github.com/o/a2","category":"c","author":"o"},
+                {"slug":"b","name":"b","summary":"","source_url":"https://github.com/o/b","category":"c","author":"o"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let merged = merge_indexes(vec![first, second]);
+        assert_eq!(merged.updated_at.as_deref(), Some("2026-09-01"));
+        let names: Vec<&str> = merged.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn a_newer_bundled_index_wins_over_an_older_cache() {
+        let index = |updated_at: &str, name: &str| {
+            parse_index(
+                format!(
+                    r#"{{"updated_at":"{updated_at}","skills":[
+                        {{"slug":"a","name":"{name}","summary":"","source_url":"https://github.com/o/a","category":"c","author":"o"}}
+                    ]}}"#
+                )
+                .as_bytes(),
+            )
+            .unwrap()
+        };
+
+        let merged = merge_indexes(with_bundled_layer(
+            vec![index("2026-07-04", "cached")],
+            index("2026-09-01", "bundled"),
+        ));
+        assert_eq!(merged.skills[0].name, "bundled");
+        assert_eq!(merged.updated_at.as_deref(), Some("2026-09-01"));
+
+        let merged = merge_indexes(with_bundled_layer(
+            vec![index("2026-10-01", "cached")],
+            index("2026-09-01", "bundled"),
+        ));
+        assert_eq!(merged.skills[0].name, "cached");
+    }
+}
@@ -0,0 +1,347 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::market_index::{CachedSkill, MarketIndex};
+use crate::network::NetworkConfig;
+use crate::settings::{MarketSourceConfig, MarketSourceKind};
+use crate::skills::read_skill_metadata;
+use crate::utils::download::{download_bytes, USER_AGENT};
+use crate::utils::path::{file_url, short_stable_hash};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// How long a GitHub listing is reused before the API is queried again.
+const GITHUB_LISTING_TTL_SECS: u64 = 60 * 60;
+
+/// A marketplace that can list its skills. Searching, ranking and deduplication happen in
+/// `search_marketplaces` so every source behaves the same.
+pub(crate) trait MarketSource {
+    fn load(&self, manager: &SkillsManager) -> SkillsResult<Arc<MarketIndex>>;
+}
+
+pub(crate) fn build_source(config: &MarketSourceConfig) -> Box<dyn MarketSource> {
+    match &config.kind {
+        MarketSourceKind::SkillsIndex => Box::new(SkillsIndexSource),
+        MarketSourceKind::IndexUrl { url } => Box::new(IndexUrlSource { url: url.clone() }),
+        MarketSourceKind::LocalDir { path } => Box::new(LocalDirSource {
+            path: PathBuf::from(path),
+        }),
+        MarketSourceKind::Github { org, topic } => Box::new(GithubSource {
+            org: org.clone(),
+            topic: topic.clone(),
+        }),
+    }
+}
+
+struct SkillsIndexSource;
+
+impl MarketSource for SkillsIndexSource {
+    fn load(&self, manager: &SkillsManager) -> SkillsResult<Arc<MarketIndex>> {
+        manager.market_index()
+    }
+}
+
+struct IndexUrlSource {
+    url: String,
+}
+
+impl MarketSource for IndexUrlSource {
+    fn load(&self, manager: &SkillsManager) -> SkillsResult<Arc<MarketIndex>> {
+        manager.index_url_index(&self.url)
+    }
+}
+
+struct LocalDirSource {
+    path: PathBuf,
+}
+
+impl LocalDirSource {
+    fn resolve(&self, manager: &SkillsManager) -> PathBuf {
+        match self.path.strip_prefix("~") {
+            Ok(rest) => manager.home().join(rest),
+            Err(_) => self.path.clone(),
+        }
+    }
+}
+
+impl MarketSource for LocalDirSource {
+    /// Lists every directory up to three levels deep that contains a `SKILL.md`, without
+    /// descending into the skills it finds.
+    fn load(&self, manager: &SkillsManager) -> SkillsResult<Arc<MarketIndex>> {
+        let root = self.resolve(manager);
+        if !root.is_dir() {
+            return Err(SkillsError::not_found(&root));
+        }
+        let author = root
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("local")
+            .to_string();
+
+        let mut skills = Vec::new();
+        let mut walker = WalkDir::new(&root)
+            .max_depth(3)
+            .sort_by_file_name()
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_dir() || !entry.path().join("SKILL.md").is_file() {
+                continue;
+            }
+            let metadata = read_skill_metadata(entry.path());
+            let relative = entry
+                .path()
+                .strip_prefix(&root)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            skills.push(CachedSkill {
+                slug: if relative.is_empty() {
+                    author.clone()
+                } else {
+                    relative.replace('/', "-")
+                },
+                name: metadata.name,
+                summary: metadata.description,
+                summary_zh: String::new(),
+                source_url: file_url(entry.path()),
+                category: author.clone(),
+                author: author.clone(),
+                stars: 0,
+                installs: 0,
+                added_at: None,
+            });
+            if entry.depth() > 0 {
+                walker.skip_current_dir();
+            }
+        }
+
+        Ok(Arc::new(MarketIndex {
+            skills,
+            ..MarketIndex::default()
+        }))
+    }
+}
+
+struct GithubSource {
+    org: Option<String>,
+    topic: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubSearchResponse {
+    items: Vec<GithubRepo>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct GithubRepo {
+    full_name: String,
+    html_url: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    stargazers_count: u64,
+    #[serde(default)]
+    created_at: Option<String>,
+    owner: GithubOwner,
+}
+
+#[derive(Deserialize, Serialize)]
+struct GithubOwner {
+    login: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct GithubListingCache {
+    fetched_at: u64,
+    repos: Vec<GithubRepo>,
+}
+
+impl GithubSource {
+    fn search_query(&self) -> SkillsResult<String> {
+        let mut terms = Vec::new();
+        if let Some(org) = self.org.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            terms.push(format!("org:{}", org));
+        }
+        if let Some(topic) = self
+            .topic
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            terms.push(format!("topic:{}", topic));
+        }
+        if terms.is_empty() {
+            return Err(SkillsError::invalid_request(
+                "GitHub market sources need an organization or a topic",
+            ));
+        }
+        Ok(terms.join(" "))
+    }
+
+    fn fetch(&self, query: &str, net: &NetworkConfig) -> SkillsResult<Vec<GithubRepo>> {
+        let url = format!(
+            "https://api.github.com/search/repositories?q={}&sort=stars&per_page=100",
+            urlencoding::encode(query)
+        );
+        let raw = download_bytes(
+            &url,
+            &[
+                ("Accept", "application/vnd.github+json"),
+                ("X-GitHub-Api-Version", "2022-11-28"),
+                ("User-Agent", USER_AGENT),
+            ],
+            net,
+        )?;
+        let response: GithubSearchResponse =
+            serde_json::from_slice(&raw).map_err(|err| SkillsError::InvalidMarketIndex {
+                url,
+                detail: err.to_string(),
+            })?;
+        Ok(response.items)
+    }
+}
+
+impl MarketSource for GithubSource {
+    /// Lists matching repositories, reusing a cached listing for an hour and falling back
+    /// to a stale one when GitHub cannot be reached.
+    fn load(&self, manager: &SkillsManager) -> SkillsResult<Arc<MarketIndex>> {
+        let query = self.search_query()?;
+        let cache_path = manager
+            .cache_dir()
+            .join("market-sources")
+            .join(format!("github-{}.json", short_stable_hash(&query)));
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let cached = fs::read(&cache_path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<GithubListingCache>(&raw).ok());
+
+        let repos = match cached {
+            Some(cache) if now.saturating_sub(cache.fetched_at) < GITHUB_LISTING_TTL_SECS => {
+                cache.repos
+            }
+            cached => match self.fetch(&query, &manager.network_config()?) {
+                Ok(repos) => {
+                    let cache = GithubListingCache {
+                        fetched_at: now,
+                        repos,
+                    };
+                    write_listing_cache(&cache_path, &cache);
+                    cache.repos
+                }
+                Err(err) => match cached {
+                    Some(stale) => stale.repos,
+                    None => return Err(err),
+                },
+            },
+        };
+
+        let category = self
+            .topic
+            .clone()
+            .or_else(|| self.org.clone())
+            .unwrap_or_default();
+        let skills = repos
+            .into_iter()
+            .map(|repo| CachedSkill {
+                slug: repo.full_name.replace('/', "-"),
+                name: repo.full_name,
+                summary: repo.description.unwrap_or_default(),
+                summary_zh: String::new(),
+                source_url: repo.html_url,
+                category: category.clone(),
+                author: repo.owner.login,
+                stars: repo.stargazers_count,
+                installs: 0,
+                added_at: repo.created_at,
+            })
+            .collect();
+        Ok(Arc::new(MarketIndex {
+            skills,
+            ..MarketIndex::default()
+        }))
+    }
+}
+
+/// Best effort: a listing that cannot be cached is simply fetched again next time.
+fn write_listing_cache(path: &Path, cache: &GithubListingCache) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(raw) = serde_json::to_vec(cache) {
+        let _ = fs::write(path, raw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_source, GithubSource};
+    use crate::settings::{MarketSourceConfig, MarketSourceKind};
+    use crate::SkillsManager;
+    use std::fs;
+
+    #[test]
+    fn builds_github_search_query() {
+        let source = GithubSource {
+            org: Some("acme".to_string()),
+            topic: Some("claude-skill".to_string()),
+        };
+        assert_eq!(
+            source.search_query().unwrap(),
+            "org:acme topic:claude-skill"
+        );
+
+        let empty = GithubSource {
+            org: None,
+            topic: Some(" ".to_string()),
+        };
+        assert!(empty.search_query().is_err());
+    }
+
+    #[test]
+    fn lists_skills_in_a_local_directory() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-local-source-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let market = home.join("team-skills");
+        fs::create_dir_all(market.join("docx/nested")).unwrap();
+        fs::create_dir_all(market.join("group/pdf")).unwrap();
+        fs::write(
+            market.join("docx/SKILL.md"),
+            "---\nname: docx\ndescription: Word files\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            market.join("docx/nested/SKILL.md"),
+            "---\nname: nested\n---\n",
+        )
+        .unwrap();
+        fs::write(market.join("group/pdf/SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+
+        let source = build_source(&MarketSourceConfig {
+            id: "team".to_string(),
+            label: "Team".to_string(),
+            enabled: true,
+            kind: MarketSourceKind::LocalDir {
+                path: "~/team-skills".to_string(),
+            },
+        });
+        let index = source.load(&SkillsManager::new(&home)).unwrap();
+        let slugs: Vec<&str> = index.skills.iter().map(|s| s.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["docx", "group-pdf"]);
+        assert_eq!(index.skills[0].summary, "Word files");
+        assert!(index.skills[0].source_url.starts_with("file://"));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
@@ -0,0 +1,505 @@
+//! Connection settings shared by every request a [`SkillsManager`] makes.
+//!
+//! A GitHub token lifts the anonymous API rate limit and gives access to private
+//! repositories. It is taken from `GITHUB_TOKEN` / `GH_TOKEN`, then the app settings, then
+//! the `gh` CLI's `hosts.yml`, and is only ever sent to GitHub over HTTPS.
+//!
+//! Requests go through the proxy from the settings or `HTTPS_PROXY` / `HTTP_PROXY` unless
+//! `NO_PROXY` exempts the host, and trust an extra PEM bundle on top of the system roots.
+//! In offline mode every request fails with [`SkillsError::Offline`] before connecting.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::settings::Settings;
+use crate::utils::time::format_utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NetworkConfig {
+    pub(crate) github_token: Option<String>,
+    pub(crate) https_proxy: Option<String>,
+    pub(crate) http_proxy: Option<String>,
+    pub(crate) no_proxy: Vec<String>,
+    pub(crate) ca_bundle: Option<PathBuf>,
+    pub(crate) offline: bool,
+    pub(crate) retries: u32,
+    pub(crate) github_mirrors: Vec<String>,
+}
+
+impl NetworkConfig {
+    fn resolve(settings: &Settings, home: &Path, env: &dyn Fn(&str) -> Option<String>) -> Self {
+        let env_var = |name: &str| {
+            env(name)
+                .or_else(|| env(&name.to_ascii_lowercase()))
+                .filter(|value| !value.trim().is_empty())
+        };
+        let configured_proxy = settings
+            .proxy_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .map(str::to_string);
+        let mut no_proxy: Vec<String> = env_var("NO_PROXY")
+            .map(|list| list.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        no_proxy.extend(settings.no_proxy.iter().cloned());
+        no_proxy.retain(|entry| !entry.trim().is_empty());
+
+        Self {
+            github_token: resolve_github_token(settings.github_token.as_deref(), home, env),
+            https_proxy: configured_proxy
+                .clone()
+                .or_else(|| env_var("HTTPS_PROXY"))
+                .or_else(|| env_var("ALL_PROXY")),
+            http_proxy: configured_proxy
+                .or_else(|| env_var("HTTP_PROXY"))
+                .or_else(|| env_var("ALL_PROXY")),
+            no_proxy,
+            ca_bundle: settings
+                .ca_bundle_path
+                .as_deref()
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            offline: settings.offline,
+            retries: settings.download_retries,
+            github_mirrors: settings
+                .github_mirrors
+                .iter()
+                .map(|mirror| mirror.trim().to_string())
+                .filter(|mirror| !mirror.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The `Authorization` header value for `url`, if it goes to GitHub and a token is known.
+    pub(crate) fn authorization(&self, url: &str) -> Option<String> {
+        let token = self.github_token.as_deref()?;
+        is_github_url(url).then(|| format!("Bearer {token}"))
+    }
+
+    /// Where else `url` can be downloaded from, in order. Only GitHub URLs are mirrored.
+    pub(crate) fn mirror_urls(&self, url: &str) -> Vec<String> {
+        let mirrored = url_host(url).is_some_and(|host| {
+            host == "github.com"
+                || host.ends_with(".github.com")
+                || host == "raw.githubusercontent.com"
+        });
+        if !mirrored {
+            return Vec::new();
+        }
+        self.github_mirrors
+            .iter()
+            .map(|mirror| {
+                if mirror.contains("{url}") {
+                    mirror.replace("{url}", url)
+                } else {
+                    format!("{}/{url}", mirror.trim_end_matches('/'))
+                }
+            })
+            .collect()
+    }
+
+    /// Fails with [`SkillsError::Offline`] when offline mode is on.
+    pub(crate) fn ensure_online(&self, url: &str) -> SkillsResult<()> {
+        if self.offline {
+            return Err(SkillsError::Offline {
+                url: url.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The proxy `url` goes through, if any.
+    pub(crate) fn proxy_for(&self, url: &str) -> Option<&str> {
+        let proxy = if url.starts_with("https://") {
+            self.https_proxy.as_deref()
+        } else {
+            self.http_proxy.as_deref()
+        }?;
+        let host = url_host(url)?;
+        let exempt = self.no_proxy.iter().any(|entry| {
+            let entry = entry.trim().to_ascii_lowercase();
+            let entry = entry.trim_start_matches("*.").trim_start_matches('.');
+            let entry = entry.split(':').next().unwrap_or_default();
+            entry == "*" || host == entry || host.ends_with(&format!(".{entry}"))
+        });
+        (!exempt).then_some(proxy)
+    }
+
+    /// An agent for `url` with the proxy and certificates applied. The timeout covers
+    /// connecting and each read.
+    pub(crate) fn agent(&self, url: &str, timeout: Duration) -> SkillsResult<ureq::Agent> {
+        self.ensure_online(url)?;
+        let mut builder = ureq::AgentBuilder::new()
+            .redirects(5)
+            .timeout_connect(timeout)
+            .timeout_read(timeout);
+        if let Some(proxy) = self.proxy_for(url) {
+            let proxy = ureq::Proxy::new(proxy).map_err(|err| {
+                SkillsError::invalid_request(format!("Invalid proxy URL {proxy}: {err}"))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(ca_bundle) = &self.ca_bundle {
+            builder = builder.tls_connector(Arc::new(tls_connector(ca_bundle)?));
+        }
+        Ok(builder.build())
+    }
+
+    /// `-c` options that make `git` use the same proxy and certificates.
+    pub(crate) fn git_config_args(&self, url: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(proxy) = self.proxy_for(url) {
+            args.extend(["-c".to_string(), format!("http.proxy={proxy}")]);
+        }
+        if let Some(ca_bundle) = &self.ca_bundle {
+            args.extend([
+                "-c".to_string(),
+                format!("http.sslCAInfo={}", ca_bundle.display()),
+            ]);
+        }
+        args
+    }
+}
+
+/// A TLS connector that trusts the certificates in `ca_bundle` besides the system roots.
+fn tls_connector(ca_bundle: &Path) -> SkillsResult<ureq::native_tls::TlsConnector> {
+    let pem = fs::read(ca_bundle).map_err(|err| SkillsError::io(ca_bundle, err))?;
+    let invalid = |err: ureq::native_tls::Error| {
+        SkillsError::invalid_request(format!("Invalid CA bundle {}: {err}", ca_bundle.display()))
+    };
+    let mut builder = ureq::native_tls::TlsConnector::builder();
+    for certificate in pem_certificates(&pem) {
+        builder.add_root_certificate(
+            ureq::native_tls::Certificate::from_pem(certificate.as_bytes()).map_err(invalid)?,
+        );
+    }
+    builder.build().map_err(invalid)
+}
+
+/// Splits a PEM bundle into its certificates, since each is added on its own.
+fn pem_certificates(pem: &[u8]) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    let text = String::from_utf8_lossy(pem);
+    text.split_inclusive(END)
+        .filter_map(|part| {
+            let start = part.find("-----BEGIN CERTIFICATE-----")?;
+            part.ends_with(END).then(|| part[start..].to_string())
+        })
+        .collect()
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    Some(host.to_ascii_lowercase())
+}
+
+fn is_github_url(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    // A `user@` part could make another host look like GitHub.
+    if authority.contains('@') {
+        return false;
+    }
+    let host = authority
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    host == "github.com" || host.ends_with(".github.com")
+}
+
+#[derive(Deserialize, Default)]
+struct GhHost {
+    #[serde(default)]
+    oauth_token: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    users: HashMap<String, GhUser>,
+}
+
+#[derive(Deserialize, Default)]
+struct GhUser {
+    #[serde(default)]
+    oauth_token: Option<String>,
+}
+
+/// `hosts.yml` of the `gh` CLI, which honours `GH_CONFIG_DIR` and `XDG_CONFIG_HOME`.
+fn gh_hosts_path(home: &Path, env: &dyn Fn(&str) -> Option<String>) -> PathBuf {
+    if let Some(dir) = env("GH_CONFIG_DIR") {
+        return PathBuf::from(dir).join("hosts.yml");
+    }
+    if let Some(dir) = env("XDG_CONFIG_HOME") {
+        return PathBuf::from(dir).join("gh/hosts.yml");
+    }
+    if cfg!(windows) {
+        if let Some(dir) = env("APPDATA") {
+            return PathBuf::from(dir).join("GitHub CLI/hosts.yml");
+        }
+    }
+    home.join(".config/gh/hosts.yml")
+}
+
+/// The github.com token `gh auth login` stored in plain text. Newer `gh` versions keep it
+/// in the system keyring instead, where it is not read.
+fn gh_cli_token(hosts_path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(hosts_path).ok()?;
+    let mut hosts: HashMap<String, GhHost> = serde_yaml::from_str(&raw).ok()?;
+    let host = hosts.remove("github.com")?;
+    host.oauth_token.or_else(|| {
+        let mut users = host.users;
+        users.remove(host.user.as_deref()?)?.oauth_token
+    })
+}
+
+fn resolve_github_token(
+    settings_token: Option<&str>,
+    home: &Path,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let usable = |token: String| Some(token.trim().to_string()).filter(|token| !token.is_empty());
+    env("GITHUB_TOKEN")
+        .and_then(usable)
+        .or_else(|| env("GH_TOKEN").and_then(usable))
+        .or_else(|| settings_token.map(str::to_string).and_then(usable))
+        .or_else(|| gh_cli_token(&gh_hosts_path(home, env)).and_then(usable))
+}
+
+/// Recognises a rate-limit response: `429`, or a `403` that says no requests are left or
+/// when to retry, as GitHub sends them. The reset time comes from GitHub's
+/// `x-ratelimit-reset` epoch, else from `retry-after` seconds counted from `now`.
+pub(crate) fn rate_limit_error<'h>(
+    url: &str,
+    status: u16,
+    header: &dyn Fn(&str) -> Option<&'h str>,
+    now: u64,
+) -> Option<SkillsError> {
+    let number = |name: &str| header(name).and_then(|value| value.trim().parse::<u64>().ok());
+    let retry_after = number("retry-after");
+    let exhausted = number("x-ratelimit-remaining") == Some(0);
+    if status != 429 && !(status == 403 && (exhausted || retry_after.is_some())) {
+        return None;
+    }
+    let reset = number("x-ratelimit-reset")
+        .filter(|_| exhausted)
+        .or_else(|| retry_after.map(|seconds| now + seconds));
+    Some(SkillsError::RateLimited {
+        url: url.to_string(),
+        reset_at: reset.map(format_utc),
+    })
+}
+
+impl SkillsManager {
+    pub(crate) fn network_config(&self) -> SkillsResult<NetworkConfig> {
+        Ok(NetworkConfig::resolve(
+            &self.load_settings()?,
+            self.home(),
+            &|name| std::env::var(name).ok(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pem_certificates, rate_limit_error, resolve_github_token, NetworkConfig};
+    use crate::error::SkillsError;
+    use crate::settings::Settings;
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn sends_the_token_to_github_only() {
+        let config = NetworkConfig {
+            github_token: Some("ghp_secret".to_string()),
+            ..NetworkConfig::default()
+        };
+        let auth = Some("Bearer ghp_secret".to_string());
+        assert_eq!(
+            config.authorization("https://api.github.com/repos/a/b/zipball/HEAD"),
+            auth
+        );
+        assert_eq!(config.authorization("https://github.com/a/b"), auth);
+        assert_eq!(
+            config.authorization("http://api.github.com/repos/a/b"),
+            None
+        );
+        assert_eq!(config.authorization("https://gitlab.com/a/b"), None);
+        assert_eq!(config.authorization("https://github.com.evil.dev/a"), None);
+        assert_eq!(config.authorization("https://github.com@evil.dev/a"), None);
+        assert_eq!(
+            NetworkConfig::default().authorization("https://github.com"),
+            None
+        );
+    }
+
+    #[test]
+    fn resolves_tokens_from_env_settings_and_gh_hosts() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let home = std::env::temp_dir().join(format!("skills-manager-gh-{timestamp}"));
+        let hosts = home.join(".config/gh/hosts.yml");
+        fs::create_dir_all(hosts.parent().unwrap()).unwrap();
+        fs::write(
+            &hosts,
+            "github.com:\n    user: octocat\n    users:\n        octocat:\n            oauth_token: gho_cli\n",
+        )
+        .unwrap();
+
+        let no_env = |_: &str| None;
+        let env = |name: &str| (name == "GH_TOKEN").then(|| "gh_env".to_string());
+        assert_eq!(
+            resolve_github_token(Some("ghp_app"), &home, &env).as_deref(),
+            Some("gh_env")
+        );
+        assert_eq!(
+            resolve_github_token(Some("ghp_app"), &home, &no_env).as_deref(),
+            Some("ghp_app")
+        );
+        assert_eq!(
+            resolve_github_token(None, &home, &no_env).as_deref(),
+            Some("gho_cli")
+        );
+
+        fs::remove_file(&hosts).unwrap();
+        assert_eq!(resolve_github_token(None, &home, &no_env), None);
+
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn maps_rate_limit_responses_to_their_reset_time() {
+        let url = "https://api.github.com/repos/a/b";
+        let github = |name: &str| match name {
+            "x-ratelimit-remaining" => Some("0"),
+            "x-ratelimit-reset" => Some("1700000000"),
+            _ => None,
+        };
+        assert_eq!(
+            rate_limit_error(url, 403, &github, 0),
+            Some(SkillsError::RateLimited {
+                url: url.to_string(),
+                reset_at: Some("2023-11-14T22:13:20Z".to_string()),
+            })
+        );
+
+        let retry = |name: &str| (name == "retry-after").then_some("60");
+        let limited = rate_limit_error(url, 429, &retry, 1_700_000_000).unwrap();
+        assert_eq!(
+            limited.to_string(),
+            format!("Rate limit exceeded for {url}; it resets at 2023-11-14T22:14:20Z")
+        );
+
+        // A plain 403, such as a private repository without access, is not a rate limit.
+        assert_eq!(rate_limit_error(url, 403, &|_| None, 0), None);
+        assert_eq!(rate_limit_error(url, 404, &github, 0), None);
+    }
+
+    #[test]
+    fn routes_requests_through_the_proxy_unless_exempt_or_offline() {
+        let env = |name: &str| match name {
+            "https_proxy" => Some("http://env-proxy:3128".to_string()),
+            "NO_PROXY" => Some("localhost,.corp.example".to_string()),
+            _ => None,
+        };
+        let home = Path::new("/nonexistent");
+        let config = NetworkConfig::resolve(&Settings::default(), home, &env);
+        assert_eq!(
+            config.proxy_for("https://github.com/a/b"),
+            Some("http://env-proxy:3128")
+        );
+        assert_eq!(config.proxy_for("http://example.com/a.zip"), None);
+        assert_eq!(config.proxy_for("https://git.corp.example/a"), None);
+        assert_eq!(config.proxy_for("https://localhost:8080/a"), None);
+
+        let settings = Settings {
+            proxy_url: Some("http://app-proxy:8080".to_string()),
+            no_proxy: vec!["github.com".to_string()],
+            ca_bundle_path: Some("/etc/corp/root.pem".to_string()),
+            ..Settings::default()
+        };
+        let config = NetworkConfig::resolve(&settings, home, &env);
+        assert_eq!(
+            config.proxy_for("http://example.com/a.zip"),
+            Some("http://app-proxy:8080")
+        );
+        assert_eq!(config.proxy_for("https://api.github.com/repos/a/b"), None);
+        assert_eq!(
+            config.git_config_args("https://gitlab.com/a/b.git"),
+            [
+                "-c",
+                "http.proxy=http://app-proxy:8080",
+                "-c",
+                "http.sslCAInfo=/etc/corp/root.pem"
+            ]
+        );
+
+        let offline = NetworkConfig::resolve(
+            &Settings {
+                offline: true,
+                ..Settings::default()
+            },
+            home,
+            &|_| None,
+        );
+        assert_eq!(
+            offline
+                .agent("https://github.com/a/b", Duration::from_secs(1))
+                .err(),
+            Some(SkillsError::Offline {
+                url: "https://github.com/a/b".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn splits_pem_bundles_into_certificates() {
+        let pem = b"# corp roots\n-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n\
+            -----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----\n";
+        let certificates = pem_certificates(pem);
+        assert_eq!(certificates.len(), 2);
+        assert!(certificates[1].contains("BBBB"));
+        assert!(certificates[0].starts_with("-----BEGIN CERTIFICATE-----"));
+    }
+
+    #[test]
+    fn mirrors_github_urls_only() {
+        let settings = Settings {
+            github_mirrors: vec![
+                "https://ghfast.top/{url}".to_string(),
+                " https://mirror.example/ ".to_string(),
+            ],
+            ..Settings::default()
+        };
+        let config = NetworkConfig::resolve(&settings, Path::new("/nonexistent"), &|_| None);
+        let url = "https://codeload.github.com/a/b/zip/main";
+        assert_eq!(
+            config.mirror_urls(url),
+            [
+                format!("https://ghfast.top/{url}"),
+                format!("https://mirror.example/{url}")
+            ]
+        );
+        assert!(config
+            .mirror_urls("https://gitlab.com/a/b/-/archive/main.zip")
+            .is_empty());
+    }
+}
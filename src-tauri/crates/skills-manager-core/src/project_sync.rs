@@ -0,0 +1,929 @@
+//! Skills declared per project. `skills.toml` lists the skills a project wants, the source
+//! of each and the IDE directories they are linked into; `skills.lock` records the commit
+//! every one was installed at. Both are meant to be committed with the project, and
+//! [`SkillsManager::sync_project`] brings the project back in line with them. A manifest
+//! with `vendor = true` also copies the skills into the project's `.skills-manager/skills`
+//! and links them there with relative symlinks, so the project works wherever it is checked
+//! out.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::jobs::DownloadJob;
+use crate::manager::SkillsManager;
+use crate::provenance::{read_provenance, write_provenance};
+use crate::skills::{
+    is_stale_copy, linked_skill_target, read_managed_copy, refresh_managed_copy, remove_path,
+    vendor_skill, PROJECT_VENDOR_DIR,
+};
+use crate::types::{
+    LinkRequest, LinkStrategy, LinkTarget, SkillProvenance, SyncDrift, SyncDriftKind,
+    SyncProjectRequest, SyncReport,
+};
+use crate::updates::diff_skill_dirs;
+use crate::utils::download::{
+    download_skill_to_dir, is_commit_addressable, pinned_source_url, FetchContext,
+};
+use crate::utils::path::{normalize_path, resolve_canonical, sanitize_skill_dir_name};
+use crate::utils::security::is_safe_relative_dir;
+use crate::versions::VersionHistory;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROJECT_MANIFEST_FILE: &str = "skills.toml";
+pub const PROJECT_LOCK_FILE: &str = "skills.lock";
+
+const LOCK_HEADER: &str =
+    "# Written by Skills Manager when it syncs skills.toml; commit it with the project.\n\n";
+
+/// `skills.toml`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ProjectManifest {
+    /// IDE skill directories, relative to the project, for skills that do not list their own.
+    #[serde(default)]
+    targets: Vec<String>,
+    /// How skills that do not name their own strategy are linked.
+    strategy: Option<LinkStrategy>,
+    /// Link copies of the skills kept in the project instead of the managed skills.
+    #[serde(default)]
+    vendor: bool,
+    #[serde(default)]
+    skills: BTreeMap<String, ManifestSkill>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ManifestSkill {
+    source: String,
+    targets: Option<Vec<String>>,
+    strategy: Option<LinkStrategy>,
+}
+
+impl ProjectManifest {
+    fn targets_of<'a>(&'a self, skill: &'a ManifestSkill) -> &'a [String] {
+        skill.targets.as_deref().unwrap_or(&self.targets)
+    }
+}
+
+/// `skills.lock`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ProjectLock {
+    #[serde(default, rename = "skill")]
+    skills: Vec<LockedSkill>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LockedSkill {
+    name: String,
+    source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    archive_sha256: Option<String>,
+    /// Where the skill was linked, so links left behind by a removed target can be found.
+    #[serde(default)]
+    targets: Vec<String>,
+}
+
+fn read_toml<T: DeserializeOwned>(path: &Path) -> SkillsResult<T> {
+    let raw = fs::read_to_string(path).map_err(|err| SkillsError::io(path, err))?;
+    toml::from_str(&raw).map_err(|err| {
+        SkillsError::invalid_request(format!("{}: {}", path.display(), err.message()))
+    })
+}
+
+fn short_commit(commit: Option<&str>) -> String {
+    commit
+        .map(|commit| commit.chars().take(7).collect())
+        .unwrap_or_else(|| "no commit".to_string())
+}
+
+/// One run of [`SkillsManager::sync_project`].
+struct ProjectSync<'a> {
+    manager: &'a SkillsManager,
+    project_dir: PathBuf,
+    dry_run: bool,
+    ctx: FetchContext<'a>,
+    history: Option<VersionHistory>,
+    report: SyncReport,
+}
+
+impl ProjectSync<'_> {
+    fn drift(&mut self, skill: &str, kind: SyncDriftKind, detail: String) {
+        self.report.drift.push(SyncDrift {
+            skill: skill.to_string(),
+            kind,
+            detail,
+        });
+    }
+
+    fn is_managed(&self, path: &Path) -> bool {
+        let vendor_dir = self.project_dir.join(PROJECT_VENDOR_DIR);
+        path.starts_with(self.manager.canonical_manager_root())
+            || path.starts_with(normalize_path(self.manager.manager_root()))
+            || resolve_canonical(&vendor_dir).is_some_and(|dir| path.starts_with(dir))
+            || path.starts_with(normalize_path(&vendor_dir))
+    }
+
+    /// Whether `skill_dir` holds a skill this project does not own: one installed without
+    /// provenance, or from another source than the manifest names and than the lock recorded
+    /// for it before, so it was installed by the user or another project. Reported as a
+    /// conflict.
+    fn conflicts(
+        &mut self,
+        name: &str,
+        source: &str,
+        locked_source: Option<&str>,
+        skill_dir: &Path,
+    ) -> bool {
+        if !skill_dir.join("SKILL.md").is_file() {
+            return false;
+        }
+        let installed_from = read_provenance(skill_dir).map(|p| p.source_url.trim().to_string());
+        let detail = match installed_from.as_deref() {
+            Some(installed) if installed == source || Some(installed) == locked_source => {
+                return false
+            }
+            Some(installed) => format!(
+                "{} in the manager directory was installed from {} and was left alone",
+                name, installed
+            ),
+            None => format!(
+                "{} in the manager directory was not installed from a source and was left alone",
+                name
+            ),
+        };
+        self.drift(name, SyncDriftKind::Conflict, detail);
+        true
+    }
+
+    /// Installs the skill into `skill_dir` unless it is there already, from `source` and at
+    /// `locked_commit` when there is one. Returns the provenance of what ends up installed.
+    fn install(
+        &mut self,
+        name: &str,
+        source: &str,
+        locked_commit: Option<&str>,
+        skill_dir: &Path,
+    ) -> SkillsResult<Option<SkillProvenance>> {
+        // A skill without provenance is a conflict, reported before installing.
+        let installed = skill_dir
+            .join("SKILL.md")
+            .is_file()
+            .then(|| read_provenance(skill_dir))
+            .flatten();
+        let drift = match &installed {
+            None => Some((
+                SyncDriftKind::NotInstalled,
+                format!("{} is not installed", name),
+            )),
+            Some(provenance) if provenance.source_url.trim() != source => Some((
+                SyncDriftKind::SourceChanged,
+                format!("{} was installed from {}", name, provenance.source_url),
+            )),
+            Some(provenance) => match (locked_commit, provenance.commit.as_deref()) {
+                (Some(commit), installed_commit) if installed_commit != Some(commit) => Some((
+                    SyncDriftKind::CommitChanged,
+                    format!(
+                        "{} is at {}, locked to {}",
+                        name,
+                        short_commit(installed_commit),
+                        short_commit(Some(commit))
+                    ),
+                )),
+                // Installed before its source was resolved to a commit, so it cannot be locked.
+                (None, None) if is_commit_addressable(source) => Some((
+                    SyncDriftKind::CommitChanged,
+                    format!("{} has no recorded commit to lock", name),
+                )),
+                _ => None,
+            },
+        };
+        let Some((kind, detail)) = drift else {
+            return Ok(installed);
+        };
+        self.drift(name, kind, detail);
+        if self.dry_run {
+            return Ok(installed);
+        }
+
+        let manager = self.manager;
+        let resolved_url = manager.resolve_source_url(source)?;
+        let install_url = match locked_commit {
+            Some(commit) => pinned_source_url(&resolved_url, commit)?,
+            None => resolved_url.clone(),
+        };
+        let installed = download_skill_to_dir(
+            &install_url,
+            name,
+            manager.manager_root(),
+            manager.manager_root(),
+            true,
+            &self.ctx,
+        )?;
+        let provenance = manager.new_provenance(
+            source,
+            &resolved_url,
+            installed.subpath.clone(),
+            &installed.origin,
+        );
+        write_provenance(&installed.dir, &provenance)?;
+        let installed_dir = installed.finish(self.history.as_ref())?;
+        manager.refresh_managed_copies(&installed_dir);
+        self.report.installed.push(name.to_string());
+        Ok(Some(provenance))
+    }
+
+    /// Copies the managed skill at `skill_dir` into the project unless the copy there has
+    /// the same provenance and files. Returns where the copy goes.
+    fn vendor(
+        &mut self,
+        name: &str,
+        skill_dir: &Path,
+        provenance: Option<&SkillProvenance>,
+    ) -> SkillsResult<PathBuf> {
+        let dir_name = skill_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let vendored = self.project_dir.join(PROJECT_VENDOR_DIR).join(&dir_name);
+        let current = vendored.join("SKILL.md").is_file()
+            && read_provenance(&vendored).as_ref() == provenance
+            && diff_skill_dirs(&vendored, skill_dir).is_ok_and(|changes| changes.is_empty());
+        if current {
+            return Ok(vendored);
+        }
+        let detail = format!(
+            "{}/{} does not match the installed skill",
+            PROJECT_VENDOR_DIR, dir_name
+        );
+        self.drift(name, SyncDriftKind::NotVendored, detail);
+        if self.dry_run {
+            return Ok(vendored);
+        }
+        vendor_skill(skill_dir, &self.project_dir, &dir_name)
+    }
+
+    /// Links `skill_dir` into `target` of the project unless it is linked there already.
+    /// Returns where the link goes.
+    fn link(
+        &mut self,
+        name: &str,
+        skill_dir: &Path,
+        target: &str,
+        strategy: Option<LinkStrategy>,
+    ) -> SkillsResult<PathBuf> {
+        let dir_name = skill_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let target_dir = self.project_dir.join(target);
+        let link_path = target_dir.join(&dir_name);
+        let shown = format!("{}/{}", target, dir_name);
+
+        if fs::symlink_metadata(&link_path).is_ok() {
+            let skill_dir =
+                resolve_canonical(skill_dir).unwrap_or_else(|| normalize_path(skill_dir));
+            match linked_skill_target(&link_path) {
+                Some(linked) if linked == skill_dir => {
+                    if let Some((_, strategy)) = read_managed_copy(&link_path)
+                        .filter(|_| is_stale_copy(&link_path, &skill_dir))
+                    {
+                        let detail = format!("{} is an outdated copy", shown);
+                        self.drift(name, SyncDriftKind::StaleCopy, detail);
+                        if !self.dry_run {
+                            refresh_managed_copy(&link_path, &skill_dir, strategy)?;
+                            self.report.linked.push(link_path.display().to_string());
+                        }
+                    }
+                    return Ok(link_path);
+                }
+                Some(linked) if self.is_managed(&linked) => {
+                    let detail = format!("{} links to {}", shown, linked.display());
+                    self.drift(name, SyncDriftKind::LinkedElsewhere, detail);
+                    if self.dry_run {
+                        return Ok(link_path);
+                    }
+                    remove_path(&link_path)?;
+                }
+                _ => {
+                    let detail = format!(
+                        "{} is not managed by Skills Manager and was left alone",
+                        shown
+                    );
+                    self.drift(name, SyncDriftKind::Conflict, detail);
+                    return Ok(link_path);
+                }
+            }
+        } else {
+            self.drift(
+                name,
+                SyncDriftKind::NotLinked,
+                format!("{} is not linked", shown),
+            );
+            if self.dry_run {
+                return Ok(link_path);
+            }
+        }
+
+        let result = self.manager.link_local_skill(LinkRequest {
+            skill_path: skill_dir.display().to_string(),
+            skill_name: dir_name,
+            link_targets: vec![LinkTarget {
+                name: target.to_string(),
+                path: target_dir.display().to_string(),
+                strategy,
+            }],
+            project_dir: Some(self.project_dir.display().to_string()),
+            vendor: false,
+        })?;
+        if !result.linked.is_empty() {
+            self.report.linked.push(link_path.display().to_string());
+        }
+        Ok(link_path)
+    }
+
+    /// Removes the managed links in `target` that are not among `declared_links`.
+    fn prune(&mut self, target: &str, declared_links: &BTreeSet<PathBuf>) -> SkillsResult<()> {
+        let Ok(entries) = fs::read_dir(self.project_dir.join(target)) else {
+            return Ok(());
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        for path in paths {
+            if declared_links.contains(&path)
+                || !linked_skill_target(&path).is_some_and(|linked| self.is_managed(&linked))
+            {
+                continue;
+            }
+            let skill = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let detail = format!(
+                "{}/{} is not declared in {}",
+                target, skill, PROJECT_MANIFEST_FILE
+            );
+            self.drift(&skill, SyncDriftKind::Undeclared, detail);
+            if !self.dry_run {
+                remove_path(&path)?;
+                self.report.removed.push(path.display().to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SkillsManager {
+    /// Makes `request.project_dir` match its `skills.toml`: skills missing from the manager
+    /// directory, installed from another source or at another commit than `skills.lock`
+    /// pins are (re)installed and vendored into the project if the manifest asks, declared
+    /// links are created, and managed links the manifest does not declare are removed from
+    /// the directories it covers. Skills of the IDE's own, and managed skills of the same name
+    /// that the user or another project installed from another source, are never touched.
+    /// Everything found is reported as drift, and a dry run stops there.
+    pub fn sync_project(
+        &self,
+        request: SyncProjectRequest,
+        job: &DownloadJob,
+    ) -> SkillsResult<SyncReport> {
+        let project_dir = PathBuf::from(&request.project_dir);
+        if !project_dir.is_dir() {
+            return Err(SkillsError::not_found(&project_dir));
+        }
+        let manifest_path = project_dir.join(PROJECT_MANIFEST_FILE);
+        if !manifest_path.is_file() {
+            return Err(SkillsError::not_found(&manifest_path));
+        }
+        let manifest: ProjectManifest = read_toml(&manifest_path)?;
+        let lock_path = project_dir.join(PROJECT_LOCK_FILE);
+        let lock: ProjectLock = if lock_path.is_file() {
+            read_toml(&lock_path)?
+        } else {
+            ProjectLock::default()
+        };
+
+        for (name, skill) in &manifest.skills {
+            if skill.source.trim().is_empty() {
+                return Err(SkillsError::invalid_request(format!(
+                    "{} in {} has no source",
+                    name, PROJECT_MANIFEST_FILE
+                )));
+            }
+            if let Some(target) = manifest
+                .targets_of(skill)
+                .iter()
+                .find(|target| !is_safe_relative_dir(target))
+            {
+                return Err(SkillsError::InvalidIdeDir {
+                    label: target.clone(),
+                });
+            }
+        }
+
+        let mut sync = ProjectSync {
+            manager: self,
+            project_dir,
+            dry_run: request.dry_run,
+            ctx: self.fetch_context(job)?,
+            history: self.version_history()?,
+            report: SyncReport {
+                project_dir: request.project_dir,
+                lock_path: lock_path.display().to_string(),
+                installed: Vec::new(),
+                linked: Vec::new(),
+                removed: Vec::new(),
+                drift: Vec::new(),
+            },
+        };
+        let mut locked_skills = Vec::new();
+        let mut declared_links = BTreeSet::new();
+        for (name, skill) in &manifest.skills {
+            let source = skill.source.trim();
+            let locked = lock.skills.iter().find(|locked| locked.name == *name);
+            let locked_commit = locked
+                .filter(|locked| locked.source.trim() == source)
+                .and_then(|locked| locked.commit.as_deref());
+            let skill_dir = self
+                .manager_root()
+                .join(sanitize_skill_dir_name(name, source));
+            let targets = manifest.targets_of(skill);
+            if sync.conflicts(
+                name,
+                source,
+                locked.map(|locked| locked.source.trim()),
+                &skill_dir,
+            ) {
+                // Its links and lock entry stay as they were.
+                for target in targets {
+                    declared_links.insert(
+                        sync.project_dir
+                            .join(target)
+                            .join(skill_dir.file_name().unwrap_or_default()),
+                    );
+                }
+                if let Some(locked) = locked {
+                    locked_skills.push(locked.clone());
+                }
+                continue;
+            }
+            let provenance = sync.install(name, source, locked_commit, &skill_dir)?;
+            let linked_dir = if manifest.vendor {
+                sync.vendor(name, &skill_dir, provenance.as_ref())?
+            } else {
+                skill_dir
+            };
+            for target in targets {
+                let strategy = skill.strategy.or(manifest.strategy);
+                declared_links.insert(sync.link(name, &linked_dir, target, strategy)?);
+            }
+            if let Some(provenance) = provenance {
+                locked_skills.push(LockedSkill {
+                    name: name.clone(),
+                    source: source.to_string(),
+                    commit: provenance.commit,
+                    subpath: provenance.subpath,
+                    archive_sha256: provenance.archive_sha256,
+                    targets: targets.to_vec(),
+                });
+            }
+        }
+
+        // Directories the lock lists but the manifest no longer does still get pruned.
+        let covered_dirs: BTreeSet<&String> = manifest
+            .targets
+            .iter()
+            .chain(
+                manifest
+                    .skills
+                    .values()
+                    .flat_map(|skill| manifest.targets_of(skill)),
+            )
+            .chain(lock.skills.iter().flat_map(|locked| &locked.targets))
+            .filter(|target| is_safe_relative_dir(target))
+            .collect();
+        for target in covered_dirs {
+            sync.prune(target, &declared_links)?;
+        }
+
+        if !sync.dry_run {
+            let raw = toml::to_string(&ProjectLock {
+                skills: locked_skills,
+            })
+            .map_err(SkillsError::internal)?;
+            fs::write(&lock_path, format!("{LOCK_HEADER}{raw}"))
+                .map_err(|err| SkillsError::io(&lock_path, err))?;
+        }
+        Ok(sync.report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PROJECT_LOCK_FILE, PROJECT_MANIFEST_FILE};
+    use crate::jobs::DownloadJob;
+    use crate::provenance::write_provenance;
+    use crate::types::{DownloadRequest, SkillProvenance, SyncDriftKind, SyncProjectRequest};
+    use crate::utils::path::file_url;
+    use crate::SkillsManager;
+    use std::fs;
+    use std::io::{Cursor, Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_skill(dir: &Path, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("SKILL.md"), content).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn installs_links_and_prunes_to_match_the_manifest() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-project-sync-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let job = DownloadJob::default();
+        for name in ["docx", "pdf", "old"] {
+            write_skill(&home.join("source").join(name), &format!("# {name}"));
+        }
+        manager
+            .download_marketplace_skill(
+                DownloadRequest {
+                    source_url: file_url(&home.join("source/old")),
+                    skill_name: "old".to_string(),
+                    install_base_dir: manager.manager_root().display().to_string(),
+                    job_id: None,
+                },
+                &job,
+            )
+            .unwrap();
+
+        let project = home.join("project");
+        let claude = project.join(".claude/skills");
+        write_skill(&claude.join("mine"), "# mine");
+        write_skill(&claude.join("pdf"), "# the project's own pdf");
+        std::os::unix::fs::symlink(manager.manager_root().join("old"), claude.join("old")).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!(
+                "targets = [\".claude/skills\"]\n\n\
+                 [skills.docx]\nsource = \"{}\"\n\n\
+                 [skills.pdf]\nsource = \"{}\"\ntargets = [\".claude/skills\", \".codex/skills\"]\n",
+                file_url(&home.join("source/docx")),
+                file_url(&home.join("source/pdf")),
+            ),
+        )
+        .unwrap();
+        let request = |dry_run| SyncProjectRequest {
+            project_dir: project.display().to_string(),
+            dry_run,
+        };
+
+        let report = manager.sync_project(request(true), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                SyncDriftKind::NotInstalled,
+                SyncDriftKind::NotLinked,
+                SyncDriftKind::NotInstalled,
+                SyncDriftKind::Conflict,
+                SyncDriftKind::NotLinked,
+                SyncDriftKind::Undeclared,
+            ]
+        );
+        assert!(!manager.manager_root().join("docx").exists());
+        assert!(claude.join("old").exists());
+        assert!(!project.join(PROJECT_LOCK_FILE).exists());
+
+        let report = manager.sync_project(request(false), &job).unwrap();
+        assert_eq!(report.installed, ["docx", "pdf"]);
+        assert_eq!(report.linked.len(), 2);
+        assert_eq!(
+            fs::read_to_string(claude.join("docx/SKILL.md")).unwrap(),
+            "# docx"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join(".codex/skills/pdf/SKILL.md")).unwrap(),
+            "# pdf"
+        );
+        assert_eq!(
+            fs::read_to_string(claude.join("pdf/SKILL.md")).unwrap(),
+            "# the project's own pdf"
+        );
+        assert!(claude.join("mine/SKILL.md").exists());
+        assert!(fs::symlink_metadata(claude.join("old")).is_err());
+        assert!(manager.manager_root().join("old").exists());
+        let lock = fs::read_to_string(project.join(PROJECT_LOCK_FILE)).unwrap();
+        assert!(lock.contains("name = \"docx\""));
+
+        let report = manager.sync_project(request(false), &job).unwrap();
+        assert!(report.installed.is_empty());
+        assert!(report.linked.is_empty());
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(kinds, [SyncDriftKind::Conflict]);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn vendors_skills_into_the_project_with_relative_links() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-project-vendor-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let job = DownloadJob::default();
+        let source = home.join("source/docx");
+        write_skill(&source, "# v1");
+
+        let project = home.join("project");
+        let claude = project.join(".claude/skills");
+        fs::create_dir_all(&claude).unwrap();
+        // Installed and linked before the project vendored its skills.
+        let install = || DownloadRequest {
+            source_url: file_url(&source),
+            skill_name: "docx".to_string(),
+            install_base_dir: manager.manager_root().display().to_string(),
+            job_id: None,
+        };
+        manager.download_marketplace_skill(install(), &job).unwrap();
+        std::os::unix::fs::symlink(manager.manager_root().join("docx"), claude.join("docx"))
+            .unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!(
+                "targets = [\".claude/skills\"]\nvendor = true\n\n[skills.docx]\nsource = \"{}\"\n",
+                file_url(&source),
+            ),
+        )
+        .unwrap();
+        let request = || SyncProjectRequest {
+            project_dir: project.display().to_string(),
+            dry_run: false,
+        };
+
+        let report = manager.sync_project(request(), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(
+            kinds,
+            [SyncDriftKind::NotVendored, SyncDriftKind::LinkedElsewhere]
+        );
+        assert_eq!(
+            fs::read_link(claude.join("docx")).unwrap(),
+            Path::new("../../.skills-manager/skills/docx")
+        );
+        let vendored = project.join(".skills-manager/skills/docx");
+        assert_eq!(
+            fs::read_to_string(vendored.join("SKILL.md")).unwrap(),
+            "# v1"
+        );
+
+        // The project still works once it is moved.
+        let moved = home.join("moved");
+        fs::rename(&project, &moved).unwrap();
+        assert_eq!(
+            fs::read_to_string(moved.join(".claude/skills/docx/SKILL.md")).unwrap(),
+            "# v1"
+        );
+        fs::rename(&moved, &project).unwrap();
+        let report = manager.sync_project(request(), &job).unwrap();
+        assert!(report.drift.is_empty());
+
+        // An update of the managed skill reaches the vendored copy on the next sync.
+        write_skill(&source, "# v2");
+        manager.update_marketplace_skill(install(), &job).unwrap();
+        let report = manager.sync_project(request(), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(kinds, [SyncDriftKind::NotVendored]);
+        assert_eq!(
+            fs::read_to_string(claude.join("docx/SKILL.md")).unwrap(),
+            "# v2"
+        );
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn installs_the_locked_commit_of_a_forge_source() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-project-pinned-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("skills/docx/SKILL.md", SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(b"# docx").unwrap();
+        let archive = archive.finish().unwrap().into_inner();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let source = format!(
+            "http://{}/owner/skills/src/branch/main/docx",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 2048];
+            let read = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                archive.len()
+            )
+            .unwrap();
+            stream.write_all(&archive).unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        let project = home.join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!("[skills.docx]\nsource = \"{source}\"\n"),
+        )
+        .unwrap();
+        fs::write(
+            project.join(PROJECT_LOCK_FILE),
+            format!("[[skill]]\nname = \"docx\"\nsource = \"{source}\"\ncommit = \"{sha}\"\n"),
+        )
+        .unwrap();
+        let request = || SyncProjectRequest {
+            project_dir: project.display().to_string(),
+            dry_run: false,
+        };
+
+        let job = DownloadJob::default();
+        let report = manager.sync_project(request(), &job).unwrap();
+        assert_eq!(report.installed, ["docx"]);
+        let fetched = server.join().unwrap();
+        assert!(fetched.starts_with(&format!("GET /owner/skills/archive/{sha}.zip ")));
+        assert_eq!(
+            fs::read_to_string(manager.manager_root().join("docx/SKILL.md")).unwrap(),
+            "# docx"
+        );
+        let lock = fs::read_to_string(project.join(PROJECT_LOCK_FILE)).unwrap();
+        assert!(lock.contains(&format!("commit = \"{sha}\"")));
+
+        // The install records the locked commit, so the next sync has nothing to do.
+        let report = manager.sync_project(request(), &job).unwrap();
+        assert!(report.installed.is_empty());
+        assert!(report.drift.is_empty());
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn leaves_a_managed_skill_from_another_source_alone() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-project-conflict-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let job = DownloadJob::default();
+        let theirs = home.join("theirs/docx");
+        let ours = home.join("ours/docx");
+        write_skill(&theirs, "# theirs");
+        write_skill(&ours, "# ours");
+        let install = |source: &Path| DownloadRequest {
+            source_url: file_url(source),
+            skill_name: "docx".to_string(),
+            install_base_dir: manager.manager_root().display().to_string(),
+            job_id: None,
+        };
+        manager
+            .download_marketplace_skill(install(&theirs), &job)
+            .unwrap();
+
+        let project = home.join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!(
+                "targets = [\".claude/skills\"]\n\n[skills.docx]\nsource = \"{}\"\n",
+                file_url(&ours)
+            ),
+        )
+        .unwrap();
+        let request = || SyncProjectRequest {
+            project_dir: project.display().to_string(),
+            dry_run: false,
+        };
+
+        let report = manager.sync_project(request(), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(kinds, [SyncDriftKind::Conflict]);
+        assert!(report.installed.is_empty());
+        assert_eq!(
+            fs::read_to_string(manager.manager_root().join("docx/SKILL.md")).unwrap(),
+            "# theirs"
+        );
+        assert!(fs::symlink_metadata(project.join(".claude/skills/docx")).is_err());
+
+        // A skill the project installed itself follows the manifest to its new source.
+        fs::remove_dir_all(manager.manager_root().join("docx")).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!("[skills.docx]\nsource = \"{}\"\n", file_url(&theirs)),
+        )
+        .unwrap();
+        manager.sync_project(request(), &job).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!("[skills.docx]\nsource = \"{}\"\n", file_url(&ours)),
+        )
+        .unwrap();
+        let report = manager.sync_project(request(), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(kinds, [SyncDriftKind::SourceChanged]);
+        assert_eq!(
+            fs::read_to_string(manager.manager_root().join("docx/SKILL.md")).unwrap(),
+            "# ours"
+        );
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn locks_the_commit_a_forge_branch_resolves_to() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-project-forge-lock-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("skills/docx/SKILL.md", SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(b"# docx").unwrap();
+        let archive = archive.finish().unwrap().into_inner();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let source = format!(
+            "http://{}/owner/skills/src/branch/main/docx",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            for body in [format!("[{{\"sha\":\"{sha}\"}}]").into_bytes(), archive] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 2048];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+
+        // Installed from the branch before its commit was recorded.
+        let skill_dir = manager.manager_root().join("docx");
+        write_skill(&skill_dir, "# docx");
+        let provenance = SkillProvenance {
+            source_url: source.clone(),
+            ..SkillProvenance::default()
+        };
+        write_provenance(&skill_dir, &provenance).unwrap();
+        let project = home.join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join(PROJECT_MANIFEST_FILE),
+            format!("[skills.docx]\nsource = \"{source}\"\n"),
+        )
+        .unwrap();
+        let request = || SyncProjectRequest {
+            project_dir: project.display().to_string(),
+            dry_run: false,
+        };
+
+        let job = DownloadJob::default();
+        let report = manager.sync_project(request(), &job).unwrap();
+        let kinds: Vec<_> = report.drift.iter().map(|drift| drift.kind).collect();
+        assert_eq!(kinds, [SyncDriftKind::CommitChanged]);
+        server.join().unwrap();
+        let lock = fs::read_to_string(project.join(PROJECT_LOCK_FILE)).unwrap();
+        assert!(lock.contains(&format!("commit = \"{sha}\"")));
+
+        let report = manager.sync_project(request(), &job).unwrap();
+        assert!(report.drift.is_empty());
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
@@ -0,0 +1,143 @@
+//! The `.skills-manager.json` written into every skill installed from a source, recording
+//! exactly what was installed so it can be audited and checked for updates later.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::types::SkillProvenance;
+use crate::utils::download::FetchedOrigin;
+use crate::utils::time::now_utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub(crate) const PROVENANCE_FILE: &str = ".skills-manager.json";
+
+/// The file keeps the snake_case keys of its first version, which only had `source_url`.
+#[derive(Serialize, Deserialize)]
+struct InstalledSkillMetadata {
+    source_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resolved_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    installed_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    archive_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    app_version: Option<String>,
+}
+
+pub(crate) fn read_provenance(skill_dir: &Path) -> Option<SkillProvenance> {
+    let raw = fs::read_to_string(skill_dir.join(PROVENANCE_FILE)).ok()?;
+    let metadata: InstalledSkillMetadata = serde_json::from_str(&raw).ok()?;
+    Some(SkillProvenance {
+        source_url: metadata.source_url,
+        resolved_url: metadata.resolved_url,
+        commit: metadata.commit,
+        subpath: metadata.subpath,
+        installed_at: metadata.installed_at,
+        archive_sha256: metadata.archive_sha256,
+        etag: metadata.etag,
+        last_modified: metadata.last_modified,
+        app_version: metadata.app_version,
+    })
+}
+
+pub(crate) fn write_provenance(skill_dir: &Path, provenance: &SkillProvenance) -> SkillsResult<()> {
+    let provenance = provenance.clone();
+    let metadata = InstalledSkillMetadata {
+        source_url: provenance.source_url,
+        resolved_url: provenance.resolved_url,
+        commit: provenance.commit,
+        subpath: provenance.subpath,
+        installed_at: provenance.installed_at,
+        archive_sha256: provenance.archive_sha256,
+        etag: provenance.etag,
+        last_modified: provenance.last_modified,
+        app_version: provenance.app_version,
+    };
+    let raw = serde_json::to_string_pretty(&metadata)?;
+    let path = skill_dir.join(PROVENANCE_FILE);
+    fs::write(&path, raw).map_err(|err| SkillsError::io(&path, err))
+}
+
+impl SkillsManager {
+    /// Provenance for a skill installed just now from `source_url`, which was downloaded
+    /// from `resolved_url`.
+    pub(crate) fn new_provenance(
+        &self,
+        source_url: &str,
+        resolved_url: &str,
+        subpath: Option<String>,
+        origin: &FetchedOrigin,
+    ) -> SkillProvenance {
+        let origin = origin.clone();
+        SkillProvenance {
+            source_url: source_url.to_string(),
+            resolved_url: (resolved_url.trim() != source_url.trim())
+                .then(|| resolved_url.trim().to_string()),
+            commit: origin.commit,
+            subpath,
+            installed_at: Some(now_utc()),
+            archive_sha256: origin.archive_sha256,
+            etag: origin.etag,
+            last_modified: origin.last_modified,
+            app_version: Some(self.app_version().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn round_trips_provenance_and_reads_legacy_files() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("skills-manager-provenance-{timestamp}"));
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(
+            dir.join(PROVENANCE_FILE),
+            r#"{"source_url":"https://github.com/acme/skills"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            read_provenance(&dir),
+            Some(SkillProvenance {
+                source_url: "https://github.com/acme/skills".to_string(),
+                ..SkillProvenance::default()
+            })
+        );
+
+        let provenance = SkillProvenance {
+            source_url: "https://officialskills.sh/acme/skills/docx".to_string(),
+            resolved_url: Some("https://github.com/acme/skills/tree/main/docx".to_string()),
+            commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            subpath: Some("docx".to_string()),
+            installed_at: Some("2026-01-02T03:04:05Z".to_string()),
+            archive_sha256: Some("ab".repeat(32)),
+            etag: None,
+            last_modified: None,
+            app_version: Some("1.2.3".to_string()),
+        };
+        write_provenance(&dir, &provenance).unwrap();
+        let raw = fs::read_to_string(dir.join(PROVENANCE_FILE)).unwrap();
+        assert!(raw.contains("\"installed_at\""));
+        assert!(!raw.contains("etag"));
+        assert_eq!(read_provenance(&dir), Some(provenance));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::market_index::CachedSkill;
+
+/// Relative weight of a hit in each field.
+const NAME_WEIGHT: f64 = 3.0;
+const SLUG_WEIGHT: f64 = 2.5;
+const AUTHOR_WEIGHT: f64 = 1.5;
+const CATEGORY_WEIGHT: f64 = 1.0;
+const SUMMARY_WEIGHT: f64 = 1.0;
+
+/// Extra score when the whole query is the skill's name, slug or last name segment.
+const EXACT_NAME_BONUS: f64 = 10.0;
+const NAME_PREFIX_BONUS: f64 = 3.0;
+
+/// A parsed search query. Tokens are lowercased; CJK runs are kept apart from Latin text
+/// because they are matched by substring rather than by word.
+pub(crate) struct SearchQuery {
+    normalized: String,
+    tokens: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum MatchKind {
+    Fuzzy,
+    Substring,
+    Prefix,
+    Exact,
+}
+
+impl MatchKind {
+    fn factor(self) -> f64 {
+        match self {
+            MatchKind::Exact => 1.0,
+            MatchKind::Prefix => 0.8,
+            MatchKind::Substring => 0.6,
+            MatchKind::Fuzzy => 0.4,
+        }
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+fn has_cjk(text: &str) -> bool {
+    text.chars().any(is_cjk)
+}
+
+/// Splits on anything that is not a letter or digit, and between CJK and non-CJK runs.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut current_cjk = false;
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if !ch.is_alphanumeric() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let cjk = is_cjk(ch);
+        if !current.is_empty() && cjk != current_cjk {
+            tokens.push(std::mem::take(&mut current));
+        }
+        current_cjk = cjk;
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Typos tolerated for a token: none for short words, one for medium, two for long ones.
+fn max_typos(token: &str) -> usize {
+    match token.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal string alignment distance (Levenshtein plus adjacent transpositions), or `None`
+/// once it exceeds `limit`.
+fn edit_distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    let mut prev_prev: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        let mut row_min = current[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (prev[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(prev_prev[j - 2] + 1);
+            }
+            row_min = row_min.min(current[j]);
+        }
+        if row_min > limit {
+            return None;
+        }
+        prev_prev = std::mem::replace(&mut prev, current);
+    }
+    Some(prev[b.len()]).filter(|distance| *distance <= limit)
+}
+
+/// CJK text has no word boundaries, so a CJK token matches as a substring, or when most
+/// of its character bigrams appear in the field (e.g. `文档编辑` against `编辑和分析文档`).
+fn match_cjk(token: &str, field: &str) -> Option<MatchKind> {
+    if field.contains(token) {
+        return Some(MatchKind::Substring);
+    }
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() < 3 {
+        return None;
+    }
+    let bigrams: Vec<String> = chars.windows(2).map(|pair| pair.iter().collect()).collect();
+    let hits = bigrams
+        .iter()
+        .filter(|bigram| field.contains(bigram.as_str()))
+        .count();
+    (hits * 3 >= bigrams.len() * 2).then_some(MatchKind::Fuzzy)
+}
+
+fn match_word(token: &str, field: &str, words: &[String]) -> Option<MatchKind> {
+    if words.iter().any(|word| word == token) {
+        return Some(MatchKind::Exact);
+    }
+    if words.iter().any(|word| word.starts_with(token)) {
+        return Some(MatchKind::Prefix);
+    }
+    if field.contains(token) {
+        return Some(MatchKind::Substring);
+    }
+    let limit = max_typos(token);
+    if limit > 0
+        && words
+            .iter()
+            .any(|word| edit_distance(token, word, limit).is_some())
+    {
+        return Some(MatchKind::Fuzzy);
+    }
+    None
+}
+
+struct Field {
+    weight: f64,
+    text: String,
+    words: Vec<String>,
+}
+
+impl Field {
+    fn new(weight: f64, text: &str) -> Self {
+        Field {
+            weight,
+            text: text.to_lowercase(),
+            words: tokenize(text),
+        }
+    }
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> Self {
+        SearchQuery {
+            normalized: tokenize(query).join(" "),
+            tokens: tokenize(query),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Relevance of `skill`, or `None` when any query token matches none of its fields.
+    /// An empty query matches everything with a score of zero.
+    pub fn score(&self, skill: &CachedSkill) -> Option<f64> {
+        if self.is_empty() {
+            return Some(0.0);
+        }
+
+        let fields = [
+            Field::new(NAME_WEIGHT, &skill.name),
+            Field::new(SLUG_WEIGHT, &skill.slug),
+            Field::new(AUTHOR_WEIGHT, &skill.author),
+            Field::new(CATEGORY_WEIGHT, &skill.category),
+            Field::new(SUMMARY_WEIGHT, &skill.summary),
+            Field::new(SUMMARY_WEIGHT, &skill.summary_zh),
+        ];
+
+        let mut score = 0.0;
+        for token in &self.tokens {
+            let cjk = has_cjk(token);
+            let best = fields
+                .iter()
+                .filter_map(|field| {
+                    let kind = if cjk {
+                        match_cjk(token, &field.text)
+                    } else {
+                        match_word(token, &field.text, &field.words)
+                    }?;
+                    Some(field.weight * kind.factor())
+                })
+                .fold(None, |best: Option<f64>, value| {
+                    Some(best.map_or(value, |best| best.max(value)))
+                });
+            score += best?;
+        }
+
+        let name = tokenize(&skill.name).join(" ");
+        let short_name = skill.name.rsplit('/').next().unwrap_or_default();
+        let short_name = tokenize(short_name).join(" ");
+        let slug = tokenize(&skill.slug).join(" ");
+        if self.normalized == name || self.normalized == short_name || self.normalized == slug {
+            score += EXACT_NAME_BONUS;
+        } else if short_name.starts_with(&self.normalized) || name.starts_with(&self.normalized) {
+            score += NAME_PREFIX_BONUS;
+        }
+        Some(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, tokenize, SearchQuery};
+    use crate::market_index::CachedSkill;
+
+    fn skill(name: &str, summary: &str, summary_zh: &str) -> CachedSkill {
+        CachedSkill {
+            slug: name.replace('/', "-"),
+            name: name.to_string(),
+            summary: summary.to_string(),
+            summary_zh: summary_zh.to_string(),
+            source_url: format!("https://github.com/{}", name),
+            category: "Official Claude Skills".to_string(),
+            author: name.split('/').next().unwrap_or_default().to_string(),
+            stars: 0,
+            installs: 0,
+            added_at: None,
+        }
+    }
+
+    #[test]
+    fn matches_chinese_summary_text() {
+        let docx = skill(
+            "anthropics/docx",
+            "Create, edit, and analyze Word documents",
+            "创建、编辑和分析Word文档",
+        );
+
+        assert!(SearchQuery::new("文档").score(&docx).is_some());
+        assert!(SearchQuery::new("文档编辑").score(&docx).is_some());
+        assert!(SearchQuery::new("表格").score(&docx).is_none());
+    }
+
+    #[test]
+    fn splits_cjk_from_latin_runs() {
+        assert_eq!(
+            tokenize("Word文档 pdf-tools"),
+            vec!["word", "文档", "pdf", "tools"]
+        );
+    }
+
+    #[test]
+    fn ranks_exact_name_above_summary_hits() {
+        let docx = skill("anthropics/docx", "Create Word documents", "");
+        let mention = skill("acme/office", "Convert pdf and docx files", "");
+        let query = SearchQuery::new("docx");
+        assert!(query.score(&docx).unwrap() > query.score(&mention).unwrap());
+    }
+
+    #[test]
+    fn requires_every_token_and_tolerates_typos() {
+        let pdf = skill(
+            "anthropics/pdf",
+            "Extract text and tables from PDF files",
+            "",
+        );
+        assert!(SearchQuery::new("pdf tables").score(&pdf).is_some());
+        assert!(SearchQuery::new("pdf slides").score(&pdf).is_none());
+        assert!(SearchQuery::new("extarct tabels").score(&pdf).is_some());
+        assert!(SearchQuery::new("pfd").score(&pdf).is_none());
+    }
+
+    #[test]
+    fn computes_bounded_edit_distance() {
+        assert_eq!(edit_distance("python", "pyhton", 1), Some(1));
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const DEFAULT_MARKET_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/Rito-w/skills-manager/main/src-tauri/data/skills-index.json";
+
+/// `market_id` of the built-in skills index.
+pub const BUILTIN_MARKET_ID: &str = "cached";
+
+/// Size the archive cache is trimmed back to unless the settings say otherwise.
+pub const DEFAULT_ARCHIVE_CACHE_MB: u64 = 512;
+
+/// How often a request that failed with a connection or server error is sent again.
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 2;
+
+/// How many previous versions of each managed skill are kept for rollback by default.
+pub const DEFAULT_SKILL_VERSIONS: u32 = 5;
+
+/// User settings stored in `~/.skills-manager/settings.json`.
+///
+/// Missing keys fall back to their defaults so older files keep loading.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Skills index documents merged into the built-in index, in priority order.
+    pub market_index_urls: Vec<String>,
+    /// Marketplaces searched by `search_marketplaces`, in priority order.
+    pub market_sources: Vec<MarketSourceConfig>,
+    /// Upper bound for downloaded archives kept in `~/.skills-manager/cache/archives`, in
+    /// MiB. `0` turns the cache off.
+    pub archive_cache_max_mb: u64,
+    /// Token sent to GitHub when neither `GITHUB_TOKEN` nor `GH_TOKEN` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_token: Option<String>,
+    /// Proxy for every download, such as `http://proxy.corp:3128`. Overrides `HTTPS_PROXY`
+    /// and `HTTP_PROXY`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>,
+    /// Hosts reached without the proxy, in addition to those in `NO_PROXY`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub no_proxy: Vec<String>,
+    /// PEM file with extra root certificates to trust, for proxies that re-sign TLS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_path: Option<String>,
+    /// Fails every network request at once; market search then only uses cached indexes.
+    pub offline: bool,
+    /// Extra attempts for requests that fail with a connection or server error.
+    pub download_retries: u32,
+    /// GitHub mirrors tried in order when GitHub fails, such as `https://ghfast.top/{url}`.
+    /// `{url}` stands for the GitHub URL; without it the URL is appended.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub github_mirrors: Vec<String>,
+    /// Previous versions of each managed skill kept in `~/.skills-manager/versions` when it
+    /// is updated. `0` turns the history off.
+    pub skill_versions_kept: u32,
+}
+
+/// Where a marketplace gets its skills from.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MarketSourceKind {
+    /// The index shipped with the app, refreshed from `market_index_urls`.
+    SkillsIndex,
+    /// A JSON document in the same format as `data/skills-index.json`.
+    IndexUrl { url: String },
+    /// A directory whose subdirectories contain `SKILL.md`.
+    LocalDir { path: String },
+    /// GitHub repositories of an organization and/or with a topic.
+    Github {
+        #[serde(default)]
+        org: Option<String>,
+        #[serde(default)]
+        topic: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSourceConfig {
+    /// Stable identifier reported as `market_id` on search results.
+    pub id: String,
+    pub label: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub kind: MarketSourceKind,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            market_index_urls: vec![DEFAULT_MARKET_INDEX_URL.to_string()],
+            market_sources: vec![MarketSourceConfig {
+                id: BUILTIN_MARKET_ID.to_string(),
+                label: "Skills Index".to_string(),
+                enabled: true,
+                kind: MarketSourceKind::SkillsIndex,
+            }],
+            archive_cache_max_mb: DEFAULT_ARCHIVE_CACHE_MB,
+            github_token: None,
+            proxy_url: None,
+            no_proxy: Vec::new(),
+            ca_bundle_path: None,
+            offline: false,
+            download_retries: DEFAULT_DOWNLOAD_RETRIES,
+            github_mirrors: Vec::new(),
+            skill_versions_kept: DEFAULT_SKILL_VERSIONS,
+        }
+    }
+}
+
+impl SkillsManager {
+    pub fn settings_path(&self) -> PathBuf {
+        self.data_dir().join("settings.json")
+    }
+
+    /// Loads settings, returning the defaults when the file does not exist yet.
+    pub fn load_settings(&self) -> SkillsResult<Settings> {
+        let path = self.settings_path();
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Settings::default())
+            }
+            Err(err) => return Err(SkillsError::io(&path, err)),
+        };
+        serde_json::from_str(&raw).map_err(|err| SkillsError::io(&path, err))
+    }
+
+    pub fn save_settings(&self, settings: &Settings) -> SkillsResult<()> {
+        let mut ids = HashSet::new();
+        for source in &settings.market_sources {
+            if source.id.trim().is_empty() {
+                return Err(SkillsError::invalid_request(
+                    "Market sources need a non-empty id",
+                ));
+            }
+            if !ids.insert(source.id.as_str()) {
+                return Err(SkillsError::invalid_request(format!(
+                    "Duplicate market source id: {}",
+                    source.id
+                )));
+            }
+        }
+
+        let path = self.settings_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| SkillsError::io(parent, err))?;
+        }
+        let raw = serde_json::to_string_pretty(settings)?;
+        // The file can hold the GitHub token, so only its owner may read it. Files written
+        // before are narrowed down before anything new goes in.
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options
+            .open(&path)
+            .map_err(|err| SkillsError::io(&path, err))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))
+                .map_err(|err| SkillsError::io(&path, err))?;
+        }
+        file.write_all(raw.as_bytes())
+            .map_err(|err| SkillsError::io(&path, err))
+    }
+
+    /// Stores `token` as the GitHub token, or removes the stored one for `None` or an empty
+    /// token.
+    pub fn set_github_token(&self, token: Option<String>) -> SkillsResult<()> {
+        let mut settings = self.load_settings()?;
+        settings.github_token = token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        self.save_settings(&settings)
+    }
+}
+
+/// Settings as shown to the app's webview, which never gets the GitHub token back and only
+/// learns whether one is stored.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsView {
+    #[serde(flatten)]
+    pub settings: Settings,
+    pub has_github_token: bool,
+}
+
+impl From<Settings> for SettingsView {
+    fn from(mut settings: Settings) -> Self {
+        let has_github_token = settings.github_token.take().is_some();
+        SettingsView {
+            settings,
+            has_github_token,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MarketSourceKind, Settings, SettingsView, DEFAULT_MARKET_INDEX_URL};
+    use crate::SkillsManager;
+    use std::fs;
+
+    #[test]
+    fn fills_missing_keys_with_defaults() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.market_index_urls, vec![DEFAULT_MARKET_INDEX_URL]);
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"marketIndexUrls":["https://example.com/index.json"]}"#)
+                .unwrap();
+        assert_eq!(
+            settings.market_index_urls,
+            vec!["https://example.com/index.json"]
+        );
+    }
+
+    #[test]
+    fn parses_tagged_market_sources() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"marketSources":[
+                {"id":"acme","label":"Acme","kind":"indexUrl","url":"https://acme.dev/index.json"},
+                {"id":"team","label":"Team","kind":"github","org":"acme","enabled":false}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.market_sources[0].kind,
+            MarketSourceKind::IndexUrl {
+                url: "https://acme.dev/index.json".to_string()
+            }
+        );
+        assert!(settings.market_sources[0].enabled);
+        assert!(!settings.market_sources[1].enabled);
+        assert_eq!(
+            settings.market_sources[1].kind,
+            MarketSourceKind::Github {
+                org: Some("acme".to_string()),
+                topic: None
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_the_github_token_private() {
+        let home =
+            std::env::temp_dir().join(format!("skills-manager-settings-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let path = manager.settings_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{}").unwrap();
+
+        manager
+            .set_github_token(Some(" ghp_secret ".to_string()))
+            .unwrap();
+        let settings = manager.load_settings().unwrap();
+        assert_eq!(settings.github_token.as_deref(), Some("ghp_secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let view = serde_json::to_value(SettingsView::from(settings)).unwrap();
+        assert_eq!(view["hasGithubToken"], true);
+        assert!(view.get("githubToken").is_none());
+
+        manager.set_github_token(Some(String::new())).unwrap();
+        assert_eq!(manager.load_settings().unwrap().github_token, None);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
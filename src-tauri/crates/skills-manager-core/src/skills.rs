@@ -0,0 +1,1374 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::jobs::DownloadJob;
+use crate::manifest::{first_body_paragraph, parse_skill_manifest, SkillManifest};
+use crate::provenance::read_provenance;
+use crate::types::{
+    AdoptIdeSkillRequest, DeleteLocalSkillRequest, ExportSkillsRequest, IdeSkill, ImportRequest,
+    InstallResult, LinkRequest, LinkStrategy, LocalScanRequest, LocalSkill, LocalSkillPreview,
+    Overview, ProjectIdeDir, ProjectScanRequest, ProjectScanResult, UninstallRequest,
+};
+use crate::updates::diff_skill_dirs;
+use crate::utils::download::{
+    archive_stem, copy_dir_recursive, download_skill_to_dir, hardlink_dir_recursive, FetchContext,
+};
+use crate::utils::path::{
+    file_url, normalize_path, relative_path, resolve_canonical, sanitize_skill_dir_name,
+};
+use crate::utils::security::{is_absolute_ide_path, is_valid_ide_path};
+use crate::utils::staging::{swap_in_dir_with, swap_in_skill_dir, STAGING_PREFIX};
+use crate::SkillsManager;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub(crate) const MANAGED_COPY_MARKER: &str = ".skills-manager-source";
+
+/// The IDE skill directories under the home directory, for when a request names none.
+const DEFAULT_IDE_DIRS: [(&str, &str); 10] = [
+    ("Antigravity", ".gemini/antigravity/skills"),
+    ("Claude", ".claude/skills"),
+    ("CodeBuddy", ".codebuddy/skills"),
+    ("Codex", ".codex/skills"),
+    ("Cursor", ".cursor/skills"),
+    ("Kiro", ".kiro/skills"),
+    ("Qoder", ".qoder/skills"),
+    ("Trae", ".trae/skills"),
+    ("VSCode", ".github/skills"),
+    ("Windsurf", ".windsurf/skills"),
+];
+
+/// Where a project keeps the skills vendored into it, relative to the project.
+pub(crate) const PROJECT_VENDOR_DIR: &str = ".skills-manager/skills";
+
+pub(crate) struct SkillMetadata {
+    pub name: String,
+    pub description: String,
+    pub manifest: Option<SkillManifest>,
+}
+
+pub(crate) fn read_skill_metadata(skill_dir: &Path) -> SkillMetadata {
+    let dir_name = skill_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("skill")
+        .to_string();
+
+    let skill_file = skill_dir.join("SKILL.md");
+    if !skill_file.exists() {
+        return SkillMetadata {
+            name: dir_name,
+            description: String::new(),
+            manifest: None,
+        };
+    }
+
+    let content = fs::read_to_string(&skill_file).unwrap_or_default();
+    let manifest = parse_skill_manifest(&content).ok().flatten();
+
+    let name = manifest
+        .as_ref()
+        .and_then(|manifest| manifest.name.as_deref())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or(dir_name);
+    let description = manifest
+        .as_ref()
+        .and_then(|manifest| manifest.description.as_deref())
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| first_body_paragraph(&content));
+
+    SkillMetadata {
+        name,
+        description,
+        manifest,
+    }
+}
+
+fn managed_copy_marker_path(skill_dir: &Path) -> PathBuf {
+    skill_dir.join(MANAGED_COPY_MARKER)
+}
+
+/// What the marker in a managed copy or hard-link tree records. Markers written before the
+/// strategy was recorded hold only the path of the managed skill.
+#[derive(Serialize, Deserialize)]
+struct ManagedCopyMarker {
+    source: String,
+    strategy: LinkStrategy,
+}
+
+/// The managed skill a copy or hard-link tree was made from, and how it was made. Old
+/// markers count as copies.
+pub(crate) fn read_managed_copy(skill_dir: &Path) -> Option<(PathBuf, LinkStrategy)> {
+    let marker_path = managed_copy_marker_path(skill_dir);
+    let raw = fs::read_to_string(marker_path).ok()?;
+    let (source, strategy) = match serde_json::from_str::<ManagedCopyMarker>(&raw) {
+        Ok(marker) => (marker.source, marker.strategy),
+        Err(_) => (raw.trim().to_string(), LinkStrategy::Copy),
+    };
+    if source.is_empty() {
+        return None;
+    }
+    let target = resolve_canonical(Path::new(&source)).unwrap_or_else(|| PathBuf::from(source));
+    Some((target, strategy))
+}
+
+fn read_managed_copy_target(skill_dir: &Path) -> Option<PathBuf> {
+    read_managed_copy(skill_dir).map(|(target, _)| target)
+}
+
+/// Whether the copy or hard-link tree at `copy` no longer has the files of the managed
+/// skill at `skill_path`, which was replaced since.
+pub(crate) fn is_stale_copy(copy: &Path, skill_path: &Path) -> bool {
+    !diff_skill_dirs(copy, skill_path).is_ok_and(|changes| changes.is_empty())
+}
+
+/// Replaces the copy or hard-link tree at `copy` with a fresh one of `skill_path`, made
+/// the same way.
+pub(crate) fn refresh_managed_copy(
+    copy: &Path,
+    skill_path: &Path,
+    strategy: LinkStrategy,
+) -> SkillsResult<()> {
+    swap_in_dir_with(copy, |staging_dir| {
+        fill_skill_dir(skill_path, staging_dir, strategy)
+    })?
+    .finish(None)
+}
+
+/// Copies or hard-links the managed skill at `skill_path` to `dest` and writes the marker
+/// naming it.
+fn fill_skill_dir(skill_path: &Path, dest: &Path, strategy: LinkStrategy) -> SkillsResult<()> {
+    if strategy == LinkStrategy::HardlinkTree {
+        hardlink_dir_recursive(skill_path, dest)?;
+    } else {
+        copy_dir_recursive(skill_path, dest)?;
+    }
+    write_managed_copy_marker(dest, skill_path, strategy)
+}
+
+fn write_managed_copy_marker(
+    skill_dir: &Path,
+    manager_skill_path: &Path,
+    strategy: LinkStrategy,
+) -> SkillsResult<()> {
+    let marker_path = managed_copy_marker_path(skill_dir);
+    let marker = ManagedCopyMarker {
+        source: manager_skill_path.display().to_string(),
+        strategy,
+    };
+    fs::write(&marker_path, serde_json::to_string_pretty(&marker)?)
+        .map_err(|err| SkillsError::io(&marker_path, err))
+}
+
+/// Where the symlink or managed copy at `path` points, made absolute but not required to
+/// exist. `None` for anything else, such as a skill directory of the IDE's own.
+pub(crate) fn linked_skill_target(path: &Path) -> Option<PathBuf> {
+    let Ok(link_target) = fs::read_link(path) else {
+        return read_managed_copy_target(path);
+    };
+    let absolute_target = match path.parent() {
+        Some(parent) if link_target.is_relative() => parent.join(&link_target),
+        _ => link_target,
+    };
+    Some(resolve_canonical(&absolute_target).unwrap_or_else(|| normalize_path(&absolute_target)))
+}
+
+fn collect_skills_from_dir(base: &Path, source: &str, ide: Option<&str>) -> Vec<LocalSkill> {
+    let mut skills = Vec::new();
+    if !base.exists() {
+        return skills;
+    }
+
+    let entries = match fs::read_dir(base) {
+        Ok(entries) => entries,
+        Err(_) => return skills,
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(item) => item,
+            Err(_) => continue,
+        };
+        let path = entry.path();
+        if !path.is_dir() || !path.join("SKILL.md").exists() {
+            continue;
+        }
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(STAGING_PREFIX)
+        {
+            continue;
+        }
+        let SkillMetadata {
+            name,
+            description,
+            manifest,
+        } = read_skill_metadata(&path);
+        let provenance = read_provenance(&path);
+        skills.push(LocalSkill {
+            id: path.display().to_string(),
+            name,
+            description,
+            manifest,
+            path: path.display().to_string(),
+            source: source.to_string(),
+            source_url: provenance
+                .as_ref()
+                .map(|provenance| provenance.source_url.clone()),
+            ide: ide.map(|value| value.to_string()),
+            used_by: Vec::new(),
+            provenance,
+        });
+    }
+
+    skills
+}
+
+fn collect_ide_skills(
+    base: &Path,
+    ide_label: &str,
+    manager_map: &[(PathBuf, usize)],
+    manager_skills: &mut [LocalSkill],
+) -> Vec<IdeSkill> {
+    let mut skills = Vec::new();
+    if !base.exists() {
+        return skills;
+    }
+
+    let entries = match fs::read_dir(base) {
+        Ok(entries) => entries,
+        Err(_) => return skills,
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(item) => item,
+            Err(_) => continue,
+        };
+        let path = entry.path();
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let link_target = fs::read_link(&path).ok();
+        let managed_copy_target = read_managed_copy_target(&path);
+        if !metadata.is_dir() && link_target.is_none() {
+            continue;
+        }
+
+        let skill_dir = path.as_path();
+        let has_skill_file = skill_dir.join("SKILL.md").exists();
+        if !has_skill_file && link_target.is_none() && managed_copy_target.is_none() {
+            continue;
+        }
+
+        let SkillMetadata {
+            name,
+            description,
+            manifest,
+        } = read_skill_metadata(skill_dir);
+
+        let path = skill_dir.to_path_buf();
+        let mut managed = false;
+        let mut stale = false;
+        let source = if let Some(link_target) = link_target {
+            let absolute_target = if link_target.is_relative() {
+                if let Some(parent) = path.parent() {
+                    parent.join(&link_target)
+                } else {
+                    link_target.clone()
+                }
+            } else {
+                link_target
+            };
+
+            if let Some(target) = resolve_canonical(&absolute_target) {
+                for (manager_path, idx) in manager_map {
+                    if *manager_path == target {
+                        managed = true;
+                        if let Some(skill) = manager_skills.get_mut(*idx) {
+                            if !skill.used_by.contains(&ide_label.to_string()) {
+                                skill.used_by.push(ide_label.to_string());
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+            "link"
+        } else if let Some(copy_target) = managed_copy_target {
+            for (manager_path, idx) in manager_map {
+                if *manager_path == copy_target {
+                    managed = true;
+                    stale = is_stale_copy(&path, manager_path);
+                    if let Some(skill) = manager_skills.get_mut(*idx) {
+                        if !skill.used_by.contains(&ide_label.to_string()) {
+                            skill.used_by.push(ide_label.to_string());
+                        }
+                    }
+                    break;
+                }
+            }
+            "link"
+        } else {
+            "local"
+        };
+
+        skills.push(IdeSkill {
+            id: path.display().to_string(),
+            name,
+            description,
+            manifest,
+            path: path.display().to_string(),
+            ide: ide_label.to_string(),
+            source: source.to_string(),
+            managed,
+            stale,
+        });
+    }
+
+    skills
+}
+
+pub(crate) fn remove_path(path: &Path) -> SkillsResult<()> {
+    let metadata = fs::symlink_metadata(path).map_err(|err| SkillsError::io(path, err))?;
+    if metadata.file_type().is_symlink() {
+        // `path.is_dir()` follows symlinks and may report true for a symlink-to-dir.
+        // Removing such a symlink with `remove_dir` triggers ENOTDIR on macOS.
+        fs::remove_file(path)
+            .or_else(|_| fs::remove_dir(path))
+            .map_err(|err| SkillsError::io(path, err))
+    } else if metadata.is_dir() {
+        fs::remove_dir_all(path).map_err(|err| SkillsError::io(path, err))
+    } else {
+        fs::remove_file(path).map_err(|err| SkillsError::io(path, err))
+    }
+}
+
+fn is_symlink_to(path: &Path, target: &Path) -> bool {
+    match (resolve_canonical(path), resolve_canonical(target)) {
+        (Some(link_target), Some(expected_target)) => link_target == expected_target,
+        _ => false,
+    }
+}
+
+fn create_symlink_dir(target: &Path, link: &Path) -> SkillsResult<()> {
+    #[cfg(target_family = "unix")]
+    {
+        std::os::unix::fs::symlink(target, link).map_err(|err| SkillsError::io(link, err))
+    }
+    #[cfg(target_family = "windows")]
+    {
+        std::os::windows::fs::symlink_dir(target, link).map_err(|err| SkillsError::io(link, err))
+    }
+}
+
+pub(crate) fn validate_manager_skill_path(
+    target: &Path,
+    manager_root: &Path,
+) -> SkillsResult<PathBuf> {
+    let canonical = resolve_canonical(target).ok_or_else(|| SkillsError::not_found(target))?;
+    if !canonical.starts_with(manager_root) || canonical == manager_root {
+        return Err(SkillsError::outside_allowed_root(&canonical));
+    }
+    if !canonical.join("SKILL.md").exists() {
+        return Err(SkillsError::missing_skill_md(&canonical));
+    }
+    Ok(canonical)
+}
+
+fn ensure_export_path_is_safe(export_path: &Path, skill_paths: &[PathBuf]) -> SkillsResult<()> {
+    let file_name = export_path
+        .file_name()
+        .ok_or_else(|| SkillsError::invalid_request("Export path must include a file name"))?;
+    let export_parent = export_path.parent().ok_or_else(|| {
+        SkillsError::invalid_request("Export path must include a parent directory")
+    })?;
+    let normalized_export_parent =
+        resolve_canonical(export_parent).unwrap_or_else(|| normalize_path(export_parent));
+    let normalized_export = normalized_export_parent.join(file_name);
+    for skill_path in skill_paths {
+        if normalized_export.starts_with(skill_path) {
+            return Err(SkillsError::invalid_request(
+                "Export path cannot be inside a selected skill directory",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn zip_skill_directory(
+    zip: &mut ZipWriter<File>,
+    skill_path: &Path,
+    root_name: &str,
+) -> SkillsResult<()> {
+    let dir_options = || {
+        SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o755)
+    };
+    let file_options = || {
+        SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o644)
+    };
+
+    let root_dir = format!("{}/", root_name);
+    zip.add_directory(&root_dir, dir_options())?;
+
+    for entry in WalkDir::new(skill_path) {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type();
+
+        if file_type.is_symlink() {
+            return Err(SkillsError::symlink_rejected(path));
+        }
+        if path == skill_path {
+            continue;
+        }
+
+        let rel_path = path.strip_prefix(skill_path)?;
+        let zip_path = format!(
+            "{}/{}",
+            root_name,
+            rel_path.to_string_lossy().replace('\\', "/")
+        );
+
+        if file_type.is_dir() {
+            zip.add_directory(format!("{}/", zip_path), dir_options())?;
+            continue;
+        }
+
+        let mut file = File::open(path).map_err(|err| SkillsError::io(path, err))?;
+        zip.start_file(zip_path, file_options())?;
+        io::copy(&mut file, zip).map_err(|err| SkillsError::io(path, err))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(target_family = "windows")]
+fn create_junction_dir(target: &Path, link: &Path) -> SkillsResult<()> {
+    use std::process::Command;
+
+    fn to_cmd_path(path: &Path) -> String {
+        path.to_string_lossy().replace('/', "\\")
+    }
+
+    fn validate_path(path: &str) -> SkillsResult<()> {
+        let dangerous_chars = ['|', '^', '<', '>', '%', '!', '"', '&', '(', ')', ';'];
+        for ch in dangerous_chars {
+            if path.contains(ch) {
+                return Err(SkillsError::invalid_request(format!(
+                    "Path contains dangerous character: '{}'",
+                    ch
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    let target = to_cmd_path(target);
+    let link = to_cmd_path(link);
+
+    validate_path(&target)?;
+    validate_path(&link)?;
+
+    let output = Command::new("cmd")
+        .args(["/C", "mklink", "/J", &link, &target])
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let detail = if !stderr.is_empty() {
+            stderr
+        } else if !stdout.is_empty() {
+            stdout
+        } else {
+            "unknown error".to_string()
+        };
+        Err(SkillsError::internal(format!(
+            "mklink /J failed: {}",
+            detail
+        )))
+    }
+}
+
+/// Qoder on Windows does not follow links, so it gets copies unless the target says otherwise.
+fn default_link_strategy(_target_dir: &Path) -> LinkStrategy {
+    #[cfg(target_family = "windows")]
+    if _target_dir
+        .to_string_lossy()
+        .replace('\\', "/")
+        .to_ascii_lowercase()
+        .ends_with("/.qoder/skills")
+    {
+        return LinkStrategy::Copy;
+    }
+    LinkStrategy::Symlink
+}
+
+/// Makes the managed skill at `skill_path` available at `link_path` the way `strategy`
+/// asks. Copies and hard-link trees get a marker naming the skill and the strategy. Returns
+/// how the link was made, as a prefix for the result message, or why it could not be.
+fn link_skill_dir(
+    skill_path: &Path,
+    link_path: &Path,
+    strategy: LinkStrategy,
+) -> Result<&'static str, String> {
+    match strategy {
+        LinkStrategy::Symlink => {
+            let symlink_error = match create_symlink_dir(skill_path, link_path) {
+                Ok(()) => return Ok(""),
+                Err(err) => format!("symlink: {}", err),
+            };
+            #[cfg(target_family = "windows")]
+            let result = create_junction_dir(skill_path, link_path)
+                .map(|()| "junction ")
+                .map_err(|err| format!("{}; junction: {}", symlink_error, err));
+            #[cfg(not(target_family = "windows"))]
+            let result = Err(symlink_error);
+            result
+        }
+        LinkStrategy::RelativeSymlink => {
+            // Relative to where the link really is, in case the IDE directory is reached
+            // through a symlink itself.
+            let relative = link_path
+                .parent()
+                .and_then(resolve_canonical)
+                .and_then(|parent| relative_path(&parent, skill_path))
+                .ok_or_else(|| {
+                    "relative symlink: the skill and the target share no root".to_string()
+                })?;
+            create_symlink_dir(&relative, link_path)
+                .map(|()| "relative symlink ")
+                .map_err(|err| format!("relative symlink: {}", err))
+        }
+        LinkStrategy::Copy | LinkStrategy::HardlinkTree => {
+            let how = if strategy == LinkStrategy::Copy {
+                "synced "
+            } else {
+                "hard-linked "
+            };
+            match fill_skill_dir(skill_path, link_path, strategy) {
+                Ok(()) => Ok(how),
+                Err(err) => {
+                    let _ = fs::remove_dir_all(link_path);
+                    Err(format!("{}: {}", how.trim(), err))
+                }
+            }
+        }
+    }
+}
+
+/// Copies the managed skill at `skill_path` into the vendor directory of `project_dir` as
+/// `dir_name`. An earlier copy is replaced in place, so links to it keep working. Returns
+/// the copy.
+pub(crate) fn vendor_skill(
+    skill_path: &Path,
+    project_dir: &Path,
+    dir_name: &str,
+) -> SkillsResult<PathBuf> {
+    let vendor_dir = project_dir.join(PROJECT_VENDOR_DIR);
+    fs::create_dir_all(&vendor_dir).map_err(|err| SkillsError::io(&vendor_dir, err))?;
+    let vendored = vendor_dir.join(dir_name);
+    swap_in_skill_dir(skill_path, &vendored)?.finish(None)?;
+    Ok(resolve_canonical(&vendored).unwrap_or(vendored))
+}
+
+impl SkillsManager {
+    pub fn link_local_skill(&self, request: LinkRequest) -> SkillsResult<InstallResult> {
+        let normalized_home = normalize_path(self.home());
+        let mut allowed_roots = vec![normalized_home.clone()];
+        let project_root = request
+            .project_dir
+            .as_ref()
+            .map(|project_dir| normalize_path(Path::new(project_dir)));
+        if let Some(project_root) = &project_root {
+            allowed_roots.push(project_root.clone());
+        }
+        let manager_root = self.canonical_manager_root();
+        // Skills already vendored into the project can be linked from there.
+        let vendor_root = project_root
+            .as_ref()
+            .and_then(|root| resolve_canonical(&root.join(PROJECT_VENDOR_DIR)));
+
+        let skill_path = PathBuf::from(&request.skill_path);
+        let skill_canon =
+            resolve_canonical(&skill_path).ok_or_else(|| SkillsError::not_found(&skill_path))?;
+        let vendored = vendor_root
+            .as_ref()
+            .is_some_and(|root| skill_canon.starts_with(root) && skill_canon != *root);
+        if !skill_canon.starts_with(&manager_root) && !vendored {
+            return Err(SkillsError::outside_allowed_root(&skill_canon));
+        }
+
+        let safe_name = sanitize_skill_dir_name(&request.skill_name, &request.skill_path);
+        let (skill_path, vendored) = match &project_root {
+            Some(project_root) if request.vendor && !vendored => {
+                (vendor_skill(&skill_canon, project_root, &safe_name)?, true)
+            }
+            None if request.vendor => {
+                return Err(SkillsError::invalid_request(
+                    "Vendoring a skill needs a project directory",
+                ))
+            }
+            _ => (skill_canon, vendored),
+        };
+
+        let mut linked = Vec::new();
+        let mut skipped = Vec::new();
+
+        for target in request.link_targets {
+            let target_base = PathBuf::from(&target.path);
+            let normalized_target = normalize_path(&target_base);
+            if !allowed_roots
+                .iter()
+                .any(|root| normalized_target.starts_with(root))
+            {
+                return Err(SkillsError::outside_allowed_root(&target_base));
+            }
+
+            // Normalize resolved paths before comparison so Windows verbatim prefixes do not
+            // trigger false-positive symlink attack errors.
+            let target_canon =
+                resolve_canonical(&target_base).unwrap_or_else(|| normalized_target.clone());
+            if !allowed_roots
+                .iter()
+                .any(|root| target_canon.starts_with(root))
+            {
+                return Err(SkillsError::outside_allowed_root(&target_canon));
+            }
+
+            fs::create_dir_all(&target_base).map_err(|err| SkillsError::io(&target_base, err))?;
+            let link_path = target_base.join(&safe_name);
+
+            if fs::symlink_metadata(&link_path).is_ok() {
+                if is_symlink_to(&link_path, &skill_path) {
+                    skipped.push(format!("{}: already linked", target.name));
+                    continue;
+                }
+                if read_managed_copy_target(&link_path)
+                    .is_some_and(|managed_target| managed_target == skill_path)
+                {
+                    skipped.push(format!("{}: already synced", target.name));
+                    continue;
+                }
+                skipped.push(format!("{}: target already exists", target.name));
+                continue;
+            }
+
+            let strategy = target.strategy.unwrap_or_else(|| {
+                if vendored {
+                    LinkStrategy::RelativeSymlink
+                } else {
+                    default_link_strategy(&target_base)
+                }
+            });
+            let mut made = link_skill_dir(&skill_path, &link_path, strategy);
+            if target.strategy.is_none() && strategy == LinkStrategy::Copy {
+                // The copy Qoder gets by default falls back to a link when it cannot be made.
+                made = made.or_else(|copy_error| {
+                    link_skill_dir(&skill_path, &link_path, LinkStrategy::Symlink)
+                        .map_err(|err| format!("{}; {}", copy_error, err))
+                });
+            }
+            match made {
+                Ok(how) => linked.push(format!("{}: {}{}", target.name, how, link_path.display())),
+                Err(detail) => {
+                    return Err(SkillsError::LinkFailed {
+                        skill: request.skill_name.clone(),
+                        target: target.name.clone(),
+                        detail,
+                    })
+                }
+            }
+        }
+
+        Ok(InstallResult {
+            installed_path: skill_path.display().to_string(),
+            linked,
+            skipped,
+        })
+    }
+
+    /// Brings the copies and hard-link trees of the managed skill at `skill_dir` in the IDE
+    /// directories under home up to date after the skill was replaced, each the way it was
+    /// made. A copy that cannot be refreshed is left as it is and shows as stale in the
+    /// overview.
+    pub(crate) fn refresh_managed_copies(&self, skill_dir: &Path) {
+        let Some(skill_dir) = resolve_canonical(skill_dir) else {
+            return;
+        };
+        for (_, dir) in DEFAULT_IDE_DIRS {
+            let Ok(entries) = fs::read_dir(self.home().join(dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let copy = entry.path();
+                if fs::read_link(&copy).is_ok() {
+                    continue;
+                }
+                if let Some((target, strategy)) = read_managed_copy(&copy) {
+                    if target == skill_dir && is_stale_copy(&copy, &skill_dir) {
+                        let _ = refresh_managed_copy(&copy, &skill_dir, strategy);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Skills in the manager directory, without the IDE scan `scan_overview` performs.
+    pub(crate) fn manager_skills(&self) -> Vec<LocalSkill> {
+        collect_skills_from_dir(self.manager_root(), "manager", None)
+    }
+
+    pub fn scan_overview(&self, request: LocalScanRequest) -> SkillsResult<Overview> {
+        let home = self.home();
+
+        let mut manager_skills = collect_skills_from_dir(self.manager_root(), "manager", None);
+
+        // Resolve IDE directories: absolute paths are used directly, relative paths are joined with home
+        let ide_dirs: Vec<(String, PathBuf)> = if request.ide_dirs.is_empty() {
+            DEFAULT_IDE_DIRS
+                .iter()
+                .map(|(label, dir)| (label.to_string(), home.join(dir)))
+                .collect()
+        } else {
+            request
+                .ide_dirs
+                .iter()
+                .map(|item| {
+                    if !is_valid_ide_path(&item.relative_dir) {
+                        return Err(SkillsError::InvalidIdeDir {
+                            label: item.label.clone(),
+                        });
+                    }
+                    // Absolute path: use directly
+                    if is_absolute_ide_path(&item.relative_dir) {
+                        Ok((item.label.clone(), PathBuf::from(&item.relative_dir)))
+                    } else {
+                        // Relative path: join with home directory
+                        Ok((item.label.clone(), home.join(&item.relative_dir)))
+                    }
+                })
+                .collect::<SkillsResult<Vec<_>>>()?
+        };
+
+        let mut ide_skills: Vec<IdeSkill> = Vec::new();
+
+        let mut manager_map: Vec<(PathBuf, usize)> = Vec::new();
+        for (idx, skill) in manager_skills.iter().enumerate() {
+            if let Some(path) = resolve_canonical(Path::new(&skill.path)) {
+                manager_map.push((path, idx));
+            }
+        }
+
+        for (label, dir) in &ide_dirs {
+            ide_skills.extend(collect_ide_skills(
+                dir,
+                label,
+                &manager_map,
+                &mut manager_skills,
+            ));
+        }
+
+        if let Some(project) = request.project_dir {
+            let base = PathBuf::from(project);
+            for (label, dir) in &ide_dirs {
+                // For absolute paths, also check the same path under project
+                // For relative paths, join with project directory
+                let project_dir = if dir.is_absolute() {
+                    dir.clone()
+                } else {
+                    base.join(dir)
+                };
+                ide_skills.extend(collect_ide_skills(
+                    &project_dir,
+                    label,
+                    &manager_map,
+                    &mut manager_skills,
+                ));
+            }
+        }
+
+        Ok(Overview {
+            manager_skills,
+            ide_skills,
+        })
+    }
+
+    pub fn uninstall_skill(&self, request: UninstallRequest) -> SkillsResult<String> {
+        let home = self.home();
+        let mut allowed_roots = vec![self.manager_root().to_path_buf()];
+
+        let ide_dirs: Vec<String> = if request.ide_dirs.is_empty() {
+            DEFAULT_IDE_DIRS
+                .iter()
+                .map(|(_, dir)| dir.to_string())
+                .collect()
+        } else {
+            request
+                .ide_dirs
+                .iter()
+                .map(|item| item.relative_dir.clone())
+                .collect()
+        };
+
+        for dir in &ide_dirs {
+            if !is_valid_ide_path(dir) {
+                return Err(SkillsError::InvalidIdeDir { label: dir.clone() });
+            }
+            // Absolute path: add directly to allowed roots
+            if is_absolute_ide_path(dir) {
+                allowed_roots.push(PathBuf::from(dir));
+            } else {
+                // Relative path: join with home directory
+                allowed_roots.push(home.join(dir));
+            }
+        }
+        if let Some(project) = request.project_dir {
+            let base = PathBuf::from(project);
+            allowed_roots.push(base.join(".codex/skills"));
+            allowed_roots.push(base.join(".trae/skills"));
+            allowed_roots.push(base.join(".opencode/skills"));
+            allowed_roots.push(base.join(PROJECT_VENDOR_DIR));
+        }
+
+        let target = PathBuf::from(&request.target_path);
+        let parent = target.parent().unwrap_or(Path::new(&request.target_path));
+        let parent_canon = resolve_canonical(parent).unwrap_or_else(|| normalize_path(parent));
+        let allowed_roots_canon: Vec<PathBuf> = allowed_roots
+            .iter()
+            .map(|root| resolve_canonical(root).unwrap_or_else(|| normalize_path(root)))
+            .collect();
+        let allowed = allowed_roots_canon
+            .iter()
+            .any(|root| parent_canon.starts_with(root));
+        if !allowed {
+            return Err(SkillsError::outside_allowed_root(&target));
+        }
+
+        let metadata =
+            fs::symlink_metadata(&target).map_err(|err| SkillsError::io(&target, err))?;
+        if metadata.file_type().is_symlink() {
+            // `target.is_dir()` follows symlinks and may report true for a symlink-to-dir.
+            // Removing such a symlink with `remove_dir` triggers ENOTDIR/ENOTEMPTY on macOS.
+            fs::remove_file(&target)
+                .or_else(|_| fs::remove_dir(&target))
+                .map_err(|err| SkillsError::io(&target, err))?;
+            return Ok("Link removed".to_string());
+        }
+
+        fs::remove_dir_all(&target).map_err(|err| SkillsError::io(&target, err))?;
+        Ok("Directory removed".to_string())
+    }
+
+    pub fn import_local_skill(&self, request: ImportRequest) -> SkillsResult<String> {
+        let manager_dir = self.manager_root();
+
+        let source_path = PathBuf::from(&request.source_path);
+        if !source_path.exists() {
+            return Err(SkillsError::not_found(&source_path));
+        }
+
+        // Archives go through the download pipeline, which unpacks them safely and finds the
+        // skill inside.
+        if source_path.is_file() {
+            let source_path = resolve_canonical(&source_path).unwrap_or(source_path);
+            let file_name = source_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let installed = download_skill_to_dir(
+                &file_url(&source_path),
+                archive_stem(&file_name),
+                manager_dir,
+                manager_dir,
+                false,
+                &FetchContext::uncached(&DownloadJob::default()),
+            )?;
+            return Ok(format!(
+                "Imported skill: {}",
+                read_skill_metadata(&installed.dir).name
+            ));
+        }
+
+        if !source_path.join("SKILL.md").exists() {
+            return Err(SkillsError::missing_skill_md(&source_path));
+        }
+
+        let name = read_skill_metadata(&source_path).name;
+        let safe_name = sanitize_skill_dir_name(&name, &request.source_path);
+        let target_dir = manager_dir.join(&safe_name);
+
+        if target_dir.exists() {
+            return Err(SkillsError::already_exists(&target_dir));
+        }
+
+        fs::create_dir_all(&target_dir).map_err(|err| SkillsError::io(&target_dir, err))?;
+        copy_dir_recursive(&source_path, &target_dir)?;
+
+        Ok(format!("Imported skill: {}", name))
+    }
+
+    pub fn adopt_ide_skill(&self, request: AdoptIdeSkillRequest) -> SkillsResult<String> {
+        let normalized_home = normalize_path(self.home());
+        let manager_root = self.manager_root();
+        fs::create_dir_all(manager_root).map_err(|err| SkillsError::io(manager_root, err))?;
+
+        let target = PathBuf::from(&request.target_path);
+        let normalized_target = normalize_path(&target);
+        if !normalized_target.starts_with(&normalized_home) {
+            return Err(SkillsError::outside_allowed_root(&target));
+        }
+
+        fs::symlink_metadata(&target).map_err(|_| SkillsError::not_found(&target))?;
+        let target_canon = resolve_canonical(&target);
+
+        let (name, has_skill_file) = if let Some(path) = target_canon.as_ref() {
+            (
+                read_skill_metadata(path).name,
+                path.join("SKILL.md").exists(),
+            )
+        } else {
+            (
+                target
+                    .file_name()
+                    .and_then(|value| value.to_str())
+                    .unwrap_or("skill")
+                    .to_string(),
+                false,
+            )
+        };
+
+        let fallback_key = target.to_str().unwrap_or(request.target_path.as_str());
+        let safe_name = sanitize_skill_dir_name(&name, fallback_key);
+        let manager_target = manager_root.join(&safe_name);
+
+        if manager_target.exists() {
+            let manager_canon = resolve_canonical(&manager_target)
+                .ok_or_else(|| SkillsError::not_found(&manager_target))?;
+            if target_canon
+                .as_ref()
+                .is_some_and(|target_path| *target_path == manager_canon)
+            {
+                return Ok(format!("{} is already managed", name));
+            }
+        } else {
+            let source_dir = target_canon
+                .as_ref()
+                .ok_or_else(|| SkillsError::not_found(&target))?;
+            if !has_skill_file {
+                return Err(SkillsError::missing_skill_md(source_dir));
+            }
+            copy_dir_recursive(source_dir, &manager_target)?;
+        }
+
+        remove_path(&target)?;
+
+        let mut linked_done = false;
+        let mut link_errors = Vec::new();
+
+        match create_symlink_dir(&manager_target, &target) {
+            Ok(()) => linked_done = true,
+            Err(err) => link_errors.push(format!("symlink: {}", err)),
+        }
+
+        #[cfg(target_family = "windows")]
+        if !linked_done {
+            match create_junction_dir(&manager_target, &target) {
+                Ok(()) => linked_done = true,
+                Err(err) => link_errors.push(format!("junction: {}", err)),
+            }
+        }
+
+        if !linked_done {
+            copy_dir_recursive(&manager_target, &target)?;
+            let detail = if link_errors.is_empty() {
+                "unknown error".to_string()
+            } else {
+                link_errors.join("; ")
+            };
+            return Err(SkillsError::LinkFailed {
+                skill: name,
+                target: request.ide_label,
+                detail: format!(
+                    "the skill is now managed, but a local copy was restored instead of a link: {}",
+                    detail
+                ),
+            });
+        }
+
+        Ok(format!(
+            "Managed {} and re-linked it to {}",
+            name, request.ide_label
+        ))
+    }
+
+    pub fn read_local_skill_preview(&self, skill_path: &str) -> SkillsResult<LocalSkillPreview> {
+        let manager_root = self.canonical_manager_root();
+        let canonical = validate_manager_skill_path(&PathBuf::from(skill_path), &manager_root)?;
+        let skill_md_path = canonical.join("SKILL.md");
+        let skill_md_content = fs::read_to_string(&skill_md_path)
+            .map_err(|err| SkillsError::io(&skill_md_path, err))?;
+
+        Ok(LocalSkillPreview {
+            skill_md_path: skill_md_path.display().to_string(),
+            skill_md_content,
+        })
+    }
+
+    pub fn delete_local_skills(&self, request: DeleteLocalSkillRequest) -> SkillsResult<String> {
+        let manager_root = self.canonical_manager_root();
+
+        if request.target_paths.is_empty() {
+            return Err(SkillsError::invalid_request(
+                "No skills were provided for deletion",
+            ));
+        }
+
+        let mut deleted = 0usize;
+
+        for raw_path in request.target_paths {
+            let target = PathBuf::from(&raw_path);
+            let canonical =
+                resolve_canonical(&target).ok_or_else(|| SkillsError::not_found(&target))?;
+            if !canonical.starts_with(&manager_root) || canonical == manager_root {
+                return Err(SkillsError::outside_allowed_root(&canonical));
+            }
+            if !canonical.join("SKILL.md").exists() {
+                return Err(SkillsError::missing_skill_md(&canonical));
+            }
+
+            fs::remove_dir_all(&canonical).map_err(|err| SkillsError::io(&canonical, err))?;
+            deleted += 1;
+        }
+
+        Ok(format!("Deleted {} skills", deleted))
+    }
+
+    pub fn export_local_skills(&self, request: ExportSkillsRequest) -> SkillsResult<String> {
+        let manager_root = self.canonical_manager_root();
+
+        if request.target_paths.is_empty() {
+            return Err(SkillsError::invalid_request(
+                "No skills were provided for export",
+            ));
+        }
+        if request.export_path.trim().is_empty() {
+            return Err(SkillsError::invalid_request("Export path is required"));
+        }
+
+        let export_path = PathBuf::from(&request.export_path);
+        let export_parent = export_path.parent().ok_or_else(|| {
+            SkillsError::invalid_request("Export path must include a parent directory")
+        })?;
+        fs::create_dir_all(export_parent).map_err(|err| SkillsError::io(export_parent, err))?;
+
+        let mut skill_paths = Vec::new();
+        for raw_path in request.target_paths {
+            let canonical = validate_manager_skill_path(&PathBuf::from(raw_path), &manager_root)?;
+            skill_paths.push(canonical);
+        }
+
+        ensure_export_path_is_safe(&export_path, &skill_paths)?;
+
+        let file = File::create(&export_path).map_err(|err| SkillsError::io(&export_path, err))?;
+        let mut zip = ZipWriter::new(file);
+
+        for skill_path in &skill_paths {
+            let root_name = skill_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("skill");
+            if let Err(err) = zip_skill_directory(&mut zip, skill_path, root_name) {
+                let _ = zip.finish();
+                let _ = fs::remove_file(&export_path);
+                return Err(err);
+            }
+        }
+
+        zip.finish()?;
+        Ok(export_path.display().to_string())
+    }
+
+    pub fn scan_project_ide_dirs(
+        &self,
+        request: ProjectScanRequest,
+    ) -> SkillsResult<ProjectScanResult> {
+        let project_dir = PathBuf::from(&request.project_dir);
+
+        if !project_dir.exists() {
+            return Err(SkillsError::not_found(&project_dir));
+        }
+
+        let ide_dir_patterns = [
+            (".gemini/antigravity/skills", "Antigravity"),
+            (".claude/skills", "Claude Code"),
+            (".codebuddy/skills", "CodeBuddy"),
+            (".codex/skills", "Codex"),
+            (".cursor/skills", "Cursor"),
+            (".kiro/skills", "Kiro"),
+            (".openclaw/skills", "OpenClaw"),
+            (".opencode/skills", "OpenCode"),
+            (".qoder/skills", "Qoder"),
+            (".trae/skills", "Trae"),
+            (".github/skills", "VSCode"),
+            (".windsurf/skills", "Windsurf"),
+        ];
+
+        let mut detected_ide_dirs = Vec::new();
+
+        for (relative_path, label) in ide_dir_patterns.iter() {
+            let ide_path = project_dir.join(relative_path);
+            if ide_path.exists() && ide_path.is_dir() {
+                detected_ide_dirs.push(ProjectIdeDir {
+                    label: label.to_string(),
+                    relative_dir: relative_path.to_string(),
+                    absolute_path: ide_path.display().to_string(),
+                });
+            }
+        }
+
+        Ok(ProjectScanResult {
+            project_dir: request.project_dir,
+            detected_ide_dirs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ImportRequest, LinkRequest, LinkStrategy, LinkTarget, LocalScanRequest};
+    use crate::SkillsManager;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[cfg(unix)]
+    #[test]
+    fn imports_links_and_scans_inside_a_temp_home() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let home = std::env::temp_dir().join(format!("skills-manager-home-{timestamp}"));
+        let source = home.join("downloads/docx");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("SKILL.md"), "---\nname: docx\n---\n# Docx\n").unwrap();
+
+        let manager = SkillsManager::new(&home);
+        manager
+            .import_local_skill(ImportRequest {
+                source_path: source.display().to_string(),
+            })
+            .unwrap();
+        let managed = manager.manager_root().join("docx");
+        assert!(managed.join("SKILL.md").exists());
+
+        let claude_dir = home.join(".claude/skills");
+        let result = manager
+            .link_local_skill(LinkRequest {
+                skill_path: managed.display().to_string(),
+                skill_name: "docx".to_string(),
+                link_targets: vec![LinkTarget {
+                    name: "Claude".to_string(),
+                    path: claude_dir.display().to_string(),
+                    strategy: None,
+                }],
+                project_dir: None,
+                vendor: false,
+            })
+            .unwrap();
+        assert_eq!(result.linked.len(), 1);
+
+        let overview = manager
+            .scan_overview(LocalScanRequest {
+                project_dir: None,
+                ide_dirs: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(overview.manager_skills.len(), 1);
+        assert_eq!(
+            overview.manager_skills[0].used_by,
+            vec!["Claude".to_string()]
+        );
+        assert!(overview.ide_skills.iter().any(|skill| skill.managed));
+
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_with_the_strategy_each_target_asks_for() {
+        use std::os::unix::fs::MetadataExt;
+
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-link-strategies-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let manager = SkillsManager::new(&home);
+        let managed = manager.manager_root().join("docx");
+        fs::create_dir_all(managed.join("scripts")).unwrap();
+        fs::write(managed.join("SKILL.md"), "# docx").unwrap();
+        fs::write(managed.join("scripts/run.py"), "print('docx')").unwrap();
+
+        let strategies = [
+            ("symlink", LinkStrategy::Symlink),
+            ("relative", LinkStrategy::RelativeSymlink),
+            ("copy", LinkStrategy::Copy),
+            ("hardlink", LinkStrategy::HardlinkTree),
+        ];
+        let link_targets = strategies
+            .iter()
+            .map(|(name, strategy)| LinkTarget {
+                name: name.to_string(),
+                path: home.join(name).join("skills").display().to_string(),
+                strategy: Some(*strategy),
+            })
+            .collect();
+        let result = manager
+            .link_local_skill(LinkRequest {
+                skill_path: managed.display().to_string(),
+                skill_name: "docx".to_string(),
+                link_targets,
+                project_dir: None,
+                vendor: false,
+            })
+            .unwrap();
+        assert_eq!(result.linked.len(), 4);
+
+        let link = |name: &str| home.join(name).join("skills/docx");
+        assert!(fs::read_link(link("symlink")).unwrap().is_absolute());
+        assert_eq!(
+            fs::read_link(link("relative")).unwrap(),
+            std::path::Path::new("../../.skills-manager/skills/docx")
+        );
+        assert!(fs::symlink_metadata(link("copy"))
+            .unwrap()
+            .file_type()
+            .is_dir());
+        assert_eq!(
+            fs::metadata(link("hardlink").join("scripts/run.py"))
+                .unwrap()
+                .ino(),
+            fs::metadata(managed.join("scripts/run.py")).unwrap().ino()
+        );
+        let marker = fs::read_to_string(link("hardlink").join(super::MANAGED_COPY_MARKER)).unwrap();
+        assert!(marker.contains("hardlink-tree"));
+
+        // Every strategy is recognised as a link to the managed skill.
+        let overview = manager
+            .scan_overview(LocalScanRequest {
+                project_dir: None,
+                ide_dirs: strategies
+                    .iter()
+                    .map(|(name, _)| crate::types::IdeDir {
+                        label: name.to_string(),
+                        relative_dir: format!("{name}/skills"),
+                    })
+                    .collect(),
+            })
+            .unwrap();
+        assert_eq!(overview.manager_skills[0].used_by.len(), 4);
+        assert!(overview.ide_skills.iter().all(|skill| skill.managed));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refreshes_copies_and_hard_link_trees_when_the_skill_is_updated() {
+        use crate::jobs::DownloadJob;
+        use crate::types::DownloadRequest;
+        use crate::utils::path::file_url;
+        use std::os::unix::fs::MetadataExt;
+
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-refresh-copies-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        let source = home.join("source/docx");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("SKILL.md"), "# v1").unwrap();
+        let manager = SkillsManager::new(&home);
+        let request = || DownloadRequest {
+            source_url: file_url(&source),
+            skill_name: "docx".to_string(),
+            install_base_dir: manager.manager_root().display().to_string(),
+            job_id: None,
+        };
+        let job = DownloadJob::default();
+        manager.update_marketplace_skill(request(), &job).unwrap();
+        let managed = manager.manager_root().join("docx");
+
+        let targets = [
+            ("Claude", ".claude/skills", LinkStrategy::Copy),
+            ("Cursor", ".cursor/skills", LinkStrategy::HardlinkTree),
+        ];
+        manager
+            .link_local_skill(LinkRequest {
+                skill_path: managed.display().to_string(),
+                skill_name: "docx".to_string(),
+                link_targets: targets
+                    .iter()
+                    .map(|(name, dir, strategy)| LinkTarget {
+                        name: name.to_string(),
+                        path: home.join(dir).display().to_string(),
+                        strategy: Some(*strategy),
+                    })
+                    .collect(),
+                project_dir: None,
+                vendor: false,
+            })
+            .unwrap();
+        let copy = home.join(".claude/skills/docx");
+        let tree = home.join(".cursor/skills/docx");
+
+        // Copies that miss a file added to the managed skill show as stale.
+        fs::write(managed.join("notes.md"), "notes").unwrap();
+        let stale = |manager: &SkillsManager| {
+            manager
+                .scan_overview(LocalScanRequest {
+                    project_dir: None,
+                    ide_dirs: Vec::new(),
+                })
+                .unwrap()
+                .ide_skills
+                .into_iter()
+                .filter(|skill| skill.stale)
+                .map(|skill| skill.ide)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(stale(&manager), ["Claude", "Cursor"]);
+        fs::remove_file(managed.join("notes.md")).unwrap();
+
+        fs::write(source.join("SKILL.md"), "# v2").unwrap();
+        manager.update_marketplace_skill(request(), &job).unwrap();
+        assert_eq!(fs::read_to_string(copy.join("SKILL.md")).unwrap(), "# v2");
+        assert_eq!(
+            fs::metadata(tree.join("SKILL.md")).unwrap().ino(),
+            fs::metadata(managed.join("SKILL.md")).unwrap().ino()
+        );
+        assert!(fs::read_to_string(tree.join(super::MANAGED_COPY_MARKER))
+            .unwrap()
+            .contains("hardlink-tree"));
+        assert!(stale(&manager).is_empty());
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
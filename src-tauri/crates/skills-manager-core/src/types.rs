@@ -0,0 +1,490 @@
+use serde::{Deserialize, Serialize};
+
+pub use crate::manifest::SkillManifest;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSkillView {
+    pub id: String,
+    pub name: String,
+    pub namespace: String,
+    pub source_url: String,
+    pub description: String,
+    pub description_zh: String,
+    pub author: String,
+    pub installs: u64,
+    pub stars: u64,
+    pub market_id: String,
+    pub market_label: String,
+    /// Relevance to the search query; higher is better and `0` for an empty query.
+    pub score: f64,
+    /// Whether a skill with this source URL or name is already in the manager directory.
+    pub installed: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSkillsViewResponse {
+    pub skills: Vec<RemoteSkillView>,
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+    /// Enabled sources that could not be searched; results from the others are still returned.
+    pub source_errors: Vec<MarketSourceError>,
+    pub facets: MarketFacets,
+}
+
+/// Narrows and orders `search_marketplaces` results; every field is optional.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarketSearchFilters {
+    pub category: Option<String>,
+    pub author: Option<String>,
+    pub market_id: Option<String>,
+    /// `Some(true)` keeps only installed skills, `Some(false)` only the rest.
+    pub installed: Option<bool>,
+    pub sort: MarketSort,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MarketSort {
+    #[default]
+    Relevance,
+    Name,
+    /// Newest first, by `added_at` where the source provides it and index order otherwise.
+    Recent,
+    Stars,
+    Installs,
+}
+
+/// Result counts per category, author and market. Each facet ignores its own filter but
+/// applies the others, so chips show what selecting them would return.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketFacets {
+    pub categories: Vec<FacetCount>,
+    pub authors: Vec<FacetCount>,
+    pub markets: Vec<FacetCount>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub value: String,
+    pub label: String,
+    pub count: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSourceError {
+    pub market_id: String,
+    pub market_label: String,
+    pub error: crate::SkillsError,
+}
+
+/// How a managed skill is made available in an IDE skills directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkStrategy {
+    /// A symlink to the managed skill, or a junction where Windows refuses symlinks.
+    Symlink,
+    /// A symlink relative to the IDE directory, which still resolves when both are mounted
+    /// or moved together.
+    RelativeSymlink,
+    /// A copy of the managed skill; it keeps its content when the skill is updated.
+    Copy,
+    /// The managed skill's directories recreated with every file hard-linked into them.
+    HardlinkTree,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkTarget {
+    pub name: String,
+    pub path: String,
+    /// `None` makes relative symlinks to skills vendored into the project, copies into
+    /// Qoder's directory on Windows and symlinks everywhere else.
+    #[serde(default)]
+    pub strategy: Option<LinkStrategy>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallResult {
+    pub installed_path: String,
+    pub linked: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRequest {
+    pub source_url: String,
+    pub skill_name: String,
+    pub install_base_dir: String,
+    /// Ties progress events and `cancel_download` to this request.
+    #[serde(default)]
+    pub job_id: Option<String>,
+}
+
+/// Size of the archive cache, from `cache_stats` or what `clear_cache` removed.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveCacheStats {
+    pub path: String,
+    pub entries: u64,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+/// Bytes received so far for a download job; `total` is known when the server sends a
+/// `Content-Length`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub job_id: String,
+    pub received: u64,
+    pub total: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadResult {
+    pub installed_path: String,
+}
+
+/// A skill found inside a repository or archive by `list_skills_in_source`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSkill {
+    /// Directory of the skill relative to the repository or archive root; empty for the root.
+    pub subpath: String,
+    pub name: String,
+    pub description: String,
+    /// A source URL that installs only this skill.
+    pub source_url: String,
+}
+
+/// Installs the skills at `subpaths` from a single download of `source_url`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceInstallRequest {
+    pub source_url: String,
+    pub subpaths: Vec<String>,
+    pub install_base_dir: String,
+    /// Replace skills that are already installed instead of failing.
+    #[serde(default)]
+    pub overwrite: bool,
+    #[serde(default)]
+    pub job_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkRequest {
+    pub skill_path: String,
+    pub skill_name: String,
+    pub link_targets: Vec<LinkTarget>,
+    pub project_dir: Option<String>,
+    /// Copy the skill into the project's `.skills-manager/skills` and link that copy, so
+    /// the project can be committed with its skills. Needs `project_dir`.
+    #[serde(default)]
+    pub vendor: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSkill {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub manifest: Option<SkillManifest>,
+    pub path: String,
+    pub source: String,
+    pub source_url: Option<String>,
+    pub ide: Option<String>,
+    pub used_by: Vec<String>,
+    /// What was installed, for skills installed from a source.
+    pub provenance: Option<SkillProvenance>,
+}
+
+/// Where an installed skill came from, as recorded in its `.skills-manager.json`.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillProvenance {
+    pub source_url: String,
+    /// What a catalog `source_url` resolved to and was downloaded from.
+    pub resolved_url: Option<String>,
+    /// The commit the source's ref pointed at, for GitHub and git sources.
+    pub commit: Option<String>,
+    /// The skill's directory inside the repository or archive.
+    pub subpath: Option<String>,
+    /// RFC 3339, UTC.
+    pub installed_at: Option<String>,
+    /// SHA-256 of the downloaded archive, hex-encoded.
+    pub archive_sha256: Option<String>,
+    /// Validators of the archive response, for hosts without commits.
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub app_version: Option<String>,
+}
+
+/// A previous version of a managed skill, kept in `~/.skills-manager/versions/<skill>/<id>`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVersion {
+    /// The commit it was installed from, or when it was installed if that is unknown.
+    pub id: String,
+    pub path: String,
+    pub provenance: Option<SkillProvenance>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackSkillRequest {
+    pub skill_path: String,
+    /// A [`SkillVersion::id`] from `list_skill_versions`.
+    pub version: String,
+}
+
+/// How a managed skill installed from a source compares with the latest version of it.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateStatus {
+    pub name: String,
+    pub path: String,
+    pub source_url: String,
+    pub installed_commit: Option<String>,
+    /// The commit the source points at now, for GitHub and git sources.
+    pub latest_commit: Option<String>,
+    /// Whether updating would change any file of the skill.
+    pub update_available: bool,
+    pub changes: SkillChanges,
+    /// Why the source could not be checked; the other skills are still checked.
+    pub error: Option<crate::SkillsError>,
+}
+
+/// Files that updating a skill would add, change or remove, relative to the skill directory.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillChanges {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SkillChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSkillPreview {
+    pub skill_md_path: String,
+    pub skill_md_content: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalScanRequest {
+    pub project_dir: Option<String>,
+    pub ide_dirs: Vec<IdeDir>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeSkill {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub manifest: Option<SkillManifest>,
+    pub path: String,
+    pub ide: String,
+    pub source: String,
+    pub managed: bool,
+    /// A managed copy or hard-link tree that no longer matches the managed skill.
+    pub stale: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Overview {
+    pub manager_skills: Vec<LocalSkill>,
+    pub ide_skills: Vec<IdeSkill>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallRequest {
+    pub target_path: String,
+    pub project_dir: Option<String>,
+    pub ide_dirs: Vec<IdeDir>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeDir {
+    pub label: String,
+    pub relative_dir: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    pub source_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteLocalSkillRequest {
+    pub target_paths: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSkillsRequest {
+    pub target_paths: Vec<String>,
+    pub export_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptIdeSkillRequest {
+    pub target_path: String,
+    pub ide_label: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScanRequest {
+    pub project_dir: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIdeDir {
+    pub label: String,
+    pub relative_dir: String,
+    pub absolute_path: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScanResult {
+    pub project_dir: String,
+    pub detected_ide_dirs: Vec<ProjectIdeDir>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProjectRequest {
+    pub project_dir: String,
+    /// Report the drift without installing, linking or removing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDriftKind {
+    /// The skill is not in the manager directory.
+    NotInstalled,
+    /// The managed skill was installed from another source than the manifest names.
+    SourceChanged,
+    /// The managed skill is at another commit than the lockfile pins, or has no recorded
+    /// commit although its source has one.
+    CommitChanged,
+    /// The copy vendored into the project is missing or differs from the managed skill.
+    NotVendored,
+    NotLinked,
+    /// The link points at another managed skill.
+    LinkedElsewhere,
+    /// The copy or hard-link tree no longer matches the managed skill it was made from.
+    StaleCopy,
+    /// A skill of the IDE's own is where the link should go, or the managed skill of that
+    /// name was installed from another source by someone else; it is left alone.
+    Conflict,
+    /// A managed link the manifest does not declare.
+    Undeclared,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDrift {
+    pub skill: String,
+    pub kind: SyncDriftKind,
+    pub detail: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub project_dir: String,
+    pub lock_path: String,
+    /// Skills installed or reinstalled into the manager directory.
+    pub installed: Vec<String>,
+    pub linked: Vec<String>,
+    pub removed: Vec<String>,
+    /// How the project differed from its manifest before the sync.
+    pub drift: Vec<SyncDrift>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Stable rule identifier, e.g. `missing-description`.
+    pub code: String,
+    pub message: String,
+    /// Path relative to the skill directory.
+    pub file: String,
+    /// 1-based line, when the problem can be pinned to one.
+    pub line: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillValidationReport {
+    pub skill_path: String,
+    pub name: String,
+    /// `false` when at least one diagnostic is an error.
+    pub valid: bool,
+    pub diagnostics: Vec<SkillDiagnostic>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexSourceState {
+    Updated,
+    NotModified,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketIndexSourceStatus {
+    pub url: String,
+    pub state: IndexSourceState,
+    pub error: Option<crate::SkillsError>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketIndexRefresh {
+    pub updated_at: Option<String>,
+    pub total: u64,
+    pub added: u64,
+    pub removed: u64,
+    pub sources: Vec<MarketIndexSourceStatus>,
+}
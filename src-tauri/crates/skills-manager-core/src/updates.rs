@@ -0,0 +1,215 @@
+//! Update checks for skills installed from a source, against the provenance recorded in
+//! their `.skills-manager.json`.
+
+use crate::error::SkillsResult;
+use crate::jobs::DownloadJob;
+use crate::manager::SkillsManager;
+use crate::provenance::PROVENANCE_FILE;
+use crate::skills::MANAGED_COPY_MARKER;
+use crate::types::{LocalSkill, SkillChanges, SkillProvenance, SkillUpdateStatus};
+use crate::utils::download::{remote_commit, FetchContext, FetchedSource};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Every file under `dir` by its `/`-separated path relative to `dir`, except the
+/// provenance file, which is not part of the skill.
+fn skill_files(dir: &Path) -> SkillsResult<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(dir).min_depth(1) {
+        let entry = entry?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(dir)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if relative != PROVENANCE_FILE && relative != MANAGED_COPY_MARKER {
+            files.insert(relative, entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+/// What replacing `installed` with `latest` would change.
+pub(crate) fn diff_skill_dirs(installed: &Path, latest: &Path) -> SkillsResult<SkillChanges> {
+    let installed_files = skill_files(installed)?;
+    let latest_files = skill_files(latest)?;
+    let mut changes = SkillChanges::default();
+    for (relative, latest_path) in &latest_files {
+        match installed_files.get(relative) {
+            None => changes.added.push(relative.clone()),
+            Some(installed_path) => {
+                if fs::read(installed_path).ok() != fs::read(latest_path).ok() {
+                    changes.modified.push(relative.clone());
+                }
+            }
+        }
+    }
+    changes.removed = installed_files
+        .into_keys()
+        .filter(|relative| !latest_files.contains_key(relative))
+        .collect();
+    Ok(changes)
+}
+
+impl SkillsManager {
+    /// Checks every managed skill installed from a source against what the source holds
+    /// now. GitHub and git sources whose ref still points at the installed commit are not
+    /// downloaded; the others are fetched (through the archive cache) and compared file by
+    /// file. A source that moved on without touching a skill's files leaves it up to date.
+    pub fn check_skill_updates(&self) -> SkillsResult<Vec<SkillUpdateStatus>> {
+        let job = DownloadJob::default();
+        let ctx = self.fetch_context(&job)?;
+        Ok(self
+            .manager_skills()
+            .into_iter()
+            .filter_map(|skill| {
+                let provenance = skill.provenance.clone()?;
+                Some(self.check_skill_update(skill, provenance, &ctx))
+            })
+            .collect())
+    }
+
+    fn check_skill_update(
+        &self,
+        skill: LocalSkill,
+        provenance: SkillProvenance,
+        ctx: &FetchContext,
+    ) -> SkillUpdateStatus {
+        let mut status = SkillUpdateStatus {
+            name: skill.name,
+            path: skill.path,
+            source_url: provenance.source_url.clone(),
+            installed_commit: provenance.commit.clone(),
+            latest_commit: None,
+            update_available: false,
+            changes: SkillChanges::default(),
+            error: None,
+        };
+        match self.latest_changes(Path::new(&status.path), &provenance, ctx) {
+            Ok((latest_commit, changes)) => {
+                status.latest_commit = latest_commit;
+                status.update_available = !changes.is_empty();
+                status.changes = changes;
+            }
+            Err(err) => status.error = Some(err),
+        }
+        status
+    }
+
+    /// The commit the source is at now, if it has one, and how the skill differs from it.
+    fn latest_changes(
+        &self,
+        skill_dir: &Path,
+        provenance: &SkillProvenance,
+        ctx: &FetchContext,
+    ) -> SkillsResult<(Option<String>, SkillChanges)> {
+        let resolved_url = self.resolve_source_url(&provenance.source_url)?;
+        if let Some(commit) = &provenance.commit {
+            if remote_commit(&resolved_url, &ctx.net)?.as_ref() == Some(commit) {
+                return Ok((Some(commit.clone()), SkillChanges::default()));
+            }
+        }
+
+        let fetched = FetchedSource::open(&resolved_url, ctx)?;
+        let latest_commit = fetched.origin.commit.clone();
+        if provenance.archive_sha256.is_some()
+            && fetched.origin.archive_sha256 == provenance.archive_sha256
+        {
+            return Ok((latest_commit, SkillChanges::default()));
+        }
+        let dir_name = skill_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let latest_dir = fetched.installed_skill_dir(provenance.subpath.as_deref(), &dir_name)?;
+        Ok((latest_commit, diff_skill_dirs(skill_dir, &latest_dir)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jobs::DownloadJob;
+    use crate::types::{DownloadRequest, SkillChanges};
+    use crate::utils::path::file_url;
+    use crate::SkillsManager;
+    use std::fs;
+    use std::io::Write;
+    use std::path::Path;
+
+    fn write_tar_gz(path: &Path, entries: &[(&str, &str)]) {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        let mut file = fs::File::create(path).unwrap();
+        file.write_all(&builder.into_inner().unwrap().finish().unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn reports_changed_files_once_the_source_moves_on() {
+        let home = std::env::temp_dir().join(format!(
+            "skills-manager-update-check-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        let archive = home.join("docx.tar.gz");
+        write_tar_gz(
+            &archive,
+            &[
+                ("docx/SKILL.md", "# docx"),
+                ("docx/scripts/old.py", "print('old')"),
+            ],
+        );
+        let manager = SkillsManager::new(&home);
+        manager
+            .download_marketplace_skill(
+                DownloadRequest {
+                    source_url: file_url(&archive),
+                    skill_name: "docx".to_string(),
+                    install_base_dir: manager.manager_root().display().to_string(),
+                    job_id: None,
+                },
+                &DownloadJob::default(),
+            )
+            .unwrap();
+
+        let statuses = manager.check_skill_updates().unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses[0].error.is_none());
+        assert!(!statuses[0].update_available);
+
+        write_tar_gz(
+            &archive,
+            &[
+                ("docx/SKILL.md", "# docx, improved"),
+                ("docx/scripts/new.py", "print('new')"),
+            ],
+        );
+        let statuses = manager.check_skill_updates().unwrap();
+        assert!(statuses[0].update_available);
+        assert_eq!(
+            statuses[0].changes,
+            SkillChanges {
+                added: vec!["scripts/new.py".to_string()],
+                modified: vec!["SKILL.md".to_string()],
+                removed: vec!["scripts/old.py".to_string()],
+            }
+        );
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
@@ -0,0 +1,2575 @@
+use crate::archive_cache::ArchiveCache;
+use crate::error::{SkillsError, SkillsResult};
+use crate::jobs::DownloadJob;
+use crate::network::{rate_limit_error, NetworkConfig};
+use crate::utils::path::{file_url, normalize_path, path_from_file_url, sanitize_skill_dir_name};
+use crate::utils::security::is_within_directory;
+use crate::utils::staging::{swap_in_skill_dir, SkillSwap};
+use crate::versions::VersionHistory;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+const GITHUB_WEB_PREFIX: &str = "https://github.com/";
+pub(crate) const USER_AGENT: &str = "skills-manager-gui/0.1";
+const MAX_DOWNLOAD_SIZE: u64 = 50 * 1024 * 1024;
+const MAX_EXTRACTED_FILE_SIZE: u64 = 100 * 1024 * 1024;
+/// Minimum gap between two progress reports for the same download.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// Wait before the first retry; it doubles with every further one.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+/// Timeout for the primary host when mirrors can take over, so a stalled host is given up
+/// on sooner.
+const MIRROR_FAILOVER_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Detects the format from a file name or URL path by its extension.
+    fn from_name(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        if lower.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DownloadSource {
+    GitHubRepo {
+        owner: String,
+        repo: String,
+    },
+    GitHubTree {
+        owner: String,
+        repo: String,
+        git_ref: String,
+        subpath: PathBuf,
+    },
+    ZipUrl {
+        url: String,
+        subpath: Option<PathBuf>,
+    },
+    TarGzUrl {
+        url: String,
+        subpath: Option<PathBuf>,
+    },
+    LocalDir {
+        path: PathBuf,
+    },
+    LocalArchive {
+        path: PathBuf,
+        format: ArchiveFormat,
+        subpath: Option<PathBuf>,
+    },
+    /// gitlab.com or a self-hosted GitLab; `project` is the full, possibly nested, path.
+    GitLab {
+        base_url: String,
+        project: String,
+        git_ref: Option<String>,
+        subpath: Option<PathBuf>,
+    },
+    /// A Gitea or Forgejo instance such as codeberg.org.
+    Gitea {
+        base_url: String,
+        owner: String,
+        repo: String,
+        git_ref: Option<String>,
+        subpath: Option<PathBuf>,
+    },
+    Bitbucket {
+        owner: String,
+        repo: String,
+        git_ref: Option<String>,
+        subpath: Option<PathBuf>,
+    },
+    /// Any remote `git` can fetch, written as `<url>#<ref>:<subpath>` with both parts optional.
+    Git {
+        url: String,
+        git_ref: Option<String>,
+        subpath: Option<PathBuf>,
+    },
+}
+
+pub fn download_bytes(
+    url: &str,
+    headers: &[(&str, &str)],
+    net: &NetworkConfig,
+) -> SkillsResult<Vec<u8>> {
+    download_bytes_with_timeout(url, headers, 60, &DownloadJob::default(), net)
+}
+
+fn download_job_bytes(
+    url: &str,
+    headers: &[(&str, &str)],
+    ctx: &FetchContext,
+) -> SkillsResult<Vec<u8>> {
+    stream_with_mirrors(url, headers, ctx)?
+        .body
+        .ok_or_else(|| SkillsError::NetworkError {
+            url: url.to_string(),
+            status: Some(304),
+            detail: "Not modified, but nothing is cached".to_string(),
+        })
+}
+
+fn download_bytes_with_timeout(
+    url: &str,
+    headers: &[(&str, &str)],
+    timeout_secs: u64,
+    job: &DownloadJob,
+    net: &NetworkConfig,
+) -> SkillsResult<Vec<u8>> {
+    stream_response(url, headers, timeout_secs, job, net)
+        .map_err(|failure| failure.error)?
+        .body
+        .ok_or_else(|| SkillsError::NetworkError {
+            url: url.to_string(),
+            status: Some(304),
+            detail: "Not modified, but nothing is cached".to_string(),
+        })
+}
+
+struct StreamedResponse {
+    /// `None` when a conditional request came back `304 Not Modified`.
+    body: Option<Vec<u8>>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Downloads an archive from `url`, then from each configured mirror in turn when the
+/// primary host fails or stalls. Mirrors get no conditional headers, since their
+/// validators need not match the primary's.
+fn stream_with_mirrors(
+    url: &str,
+    headers: &[(&str, &str)],
+    ctx: &FetchContext,
+) -> SkillsResult<StreamedResponse> {
+    let mirrors = ctx.net.mirror_urls(url);
+    if mirrors.is_empty() {
+        return stream_response(url, headers, 60, ctx.job, &ctx.net)
+            .map_err(|failure| failure.error);
+    }
+    let primary_error = match stream_response(
+        url,
+        headers,
+        MIRROR_FAILOVER_TIMEOUT_SECS,
+        ctx.job,
+        &ctx.net,
+    ) {
+        Err(failure)
+            if failure.transient || matches!(failure.error, SkillsError::RateLimited { .. }) =>
+        {
+            failure.error
+        }
+        result => return result.map_err(|failure| failure.error),
+    };
+    let plain_headers: Vec<(&str, &str)> = headers
+        .iter()
+        .filter(|(name, _)| !name.starts_with("If-"))
+        .copied()
+        .collect();
+    for mirror in mirrors {
+        match stream_response(&mirror, &plain_headers, 60, ctx.job, &ctx.net) {
+            Ok(response) => return Ok(response),
+            Err(Failure {
+                error: error @ SkillsError::Cancelled { .. },
+                ..
+            }) => return Err(error),
+            Err(_) => continue,
+        }
+    }
+    Err(primary_error)
+}
+
+/// A failed GET, and whether sending it again may succeed: only connection failures,
+/// timeouts and read errors, `408`, `429` and server errors are worth another try.
+struct Failure {
+    error: SkillsError,
+    transient: bool,
+}
+
+impl From<SkillsError> for Failure {
+    fn from(error: SkillsError) -> Self {
+        Failure {
+            error,
+            transient: false,
+        }
+    }
+}
+
+/// Runs `attempt` again after transient failures, up to `net.retries` more times, waiting
+/// twice as long before each retry.
+fn with_retries<T>(
+    net: &NetworkConfig,
+    job: &DownloadJob,
+    mut attempt: impl FnMut() -> Result<T, Failure>,
+) -> Result<T, Failure> {
+    let mut delay = RETRY_BASE_DELAY;
+    for _ in 0..net.retries {
+        match attempt() {
+            Err(failure) if failure.transient => {
+                let until = Instant::now() + delay;
+                while Instant::now() < until {
+                    job.check()?;
+                    std::thread::sleep(PROGRESS_INTERVAL);
+                }
+                delay = (delay * 2).min(RETRY_MAX_DELAY);
+            }
+            result => return result,
+        }
+    }
+    attempt()
+}
+
+/// Streams the response body in chunks, reporting progress to `job` and stopping as soon as
+/// it is cancelled. The timeout applies to connecting and to each read, so a slow but
+/// steady download of a large archive is not cut off. Transient failures are retried.
+fn stream_response(
+    url: &str,
+    headers: &[(&str, &str)],
+    timeout_secs: u64,
+    job: &DownloadJob,
+    net: &NetworkConfig,
+) -> Result<StreamedResponse, Failure> {
+    with_retries(net, job, || {
+        stream_once(url, headers, timeout_secs, job, net)
+    })
+}
+
+fn stream_once(
+    url: &str,
+    headers: &[(&str, &str)],
+    timeout_secs: u64,
+    job: &DownloadJob,
+    net: &NetworkConfig,
+) -> Result<StreamedResponse, Failure> {
+    let agent = net.agent(url, Duration::from_secs(timeout_secs))?;
+    let mut request = agent.get(url);
+    for (key, value) in headers {
+        request = request.set(key, value);
+    }
+    if let Some(authorization) = net.authorization(url) {
+        request = request.set("Authorization", &authorization);
+    }
+
+    job.check()?;
+    let response = request.call().map_err(|err| request_failure(url, err))?;
+    let etag = response.header("ETag").map(str::to_string);
+    let last_modified = response.header("Last-Modified").map(str::to_string);
+    if response.status() == 304 {
+        return Ok(StreamedResponse {
+            body: None,
+            etag,
+            last_modified,
+        });
+    }
+    let total = response
+        .header("Content-Length")
+        .and_then(|length| length.parse::<u64>().ok());
+    let mut reader = response.into_reader().take(MAX_DOWNLOAD_SIZE);
+    let mut buf = Vec::new();
+    let mut chunk = vec![0; 64 * 1024];
+    let mut last_report: Option<Instant> = None;
+
+    loop {
+        job.check()?;
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(read_failure(url, err)),
+        };
+        buf.extend_from_slice(&chunk[..read]);
+        if last_report.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+            job.report(buf.len() as u64, total);
+            last_report = Some(Instant::now());
+        }
+    }
+    job.report(buf.len() as u64, total);
+    Ok(StreamedResponse {
+        body: Some(buf),
+        etag,
+        last_modified,
+    })
+}
+
+/// Outcome of a GET that revalidates a cached copy.
+pub enum ConditionalFetch {
+    NotModified,
+    Fetched {
+        body: Vec<u8>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Sends `If-None-Match` / `If-Modified-Since` when validators are known, so unchanged
+/// documents come back as `304 Not Modified` without a body.
+pub fn conditional_get(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+    net: &NetworkConfig,
+) -> SkillsResult<ConditionalFetch> {
+    with_retries(net, &DownloadJob::default(), || {
+        conditional_get_once(url, etag, last_modified, net)
+    })
+    .map_err(|failure| failure.error)
+}
+
+fn conditional_get_once(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+    net: &NetworkConfig,
+) -> Result<ConditionalFetch, Failure> {
+    let agent = net.agent(url, Duration::from_secs(30))?;
+    let mut request = agent.get(url).set("User-Agent", USER_AGENT);
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.set("If-Modified-Since", last_modified);
+    }
+    if let Some(authorization) = net.authorization(url) {
+        request = request.set("Authorization", &authorization);
+    }
+
+    let response = request.call().map_err(|err| request_failure(url, err))?;
+    if response.status() == 304 {
+        return Ok(ConditionalFetch::NotModified);
+    }
+    let etag = response.header("ETag").map(str::to_string);
+    let last_modified = response.header("Last-Modified").map(str::to_string);
+
+    let mut body = Vec::new();
+    const MAX_INDEX_SIZE: u64 = 20 * 1024 * 1024;
+    response
+        .into_reader()
+        .take(MAX_INDEX_SIZE)
+        .read_to_end(&mut body)
+        .map_err(|err| read_failure(url, err))?;
+    Ok(ConditionalFetch::Fetched {
+        body,
+        etag,
+        last_modified,
+    })
+}
+
+/// Maps a failed request to its error, marking refused or dropped connections and
+/// `408`, `429` and `5xx` responses as worth retrying.
+fn request_failure(url: &str, err: ureq::Error) -> Failure {
+    match err {
+        ureq::Error::Status(status, response) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            let error = rate_limit_error(url, status, &|name| response.header(name), now)
+                .unwrap_or_else(|| SkillsError::NetworkError {
+                    url: url.to_string(),
+                    status: Some(status),
+                    detail: response.status_text().to_string(),
+                });
+            Failure {
+                error,
+                transient: matches!(status, 408 | 429 | 500..=599),
+            }
+        }
+        ureq::Error::Transport(transport) => Failure {
+            transient: matches!(
+                transport.kind(),
+                ureq::ErrorKind::Dns
+                    | ureq::ErrorKind::ConnectionFailed
+                    | ureq::ErrorKind::ProxyConnect
+                    | ureq::ErrorKind::Io
+            ),
+            error: SkillsError::NetworkError {
+                url: url.to_string(),
+                status: None,
+                detail: transport.to_string(),
+            },
+        },
+    }
+}
+
+/// A connection that broke or timed out while the body was being read.
+fn read_failure(url: &str, err: std::io::Error) -> Failure {
+    Failure {
+        error: SkillsError::NetworkError {
+            url: url.to_string(),
+            status: None,
+            detail: err.to_string(),
+        },
+        transient: true,
+    }
+}
+
+/// What fetching a source needs besides the source itself: the job that follows and can
+/// cancel the transfer, the archive cache when it is turned on, and the connection settings.
+pub(crate) struct FetchContext<'a> {
+    pub(crate) job: &'a DownloadJob,
+    pub(crate) cache: Option<ArchiveCache>,
+    pub(crate) net: NetworkConfig,
+}
+
+impl<'a> FetchContext<'a> {
+    pub(crate) fn uncached(job: &'a DownloadJob) -> Self {
+        Self {
+            job,
+            cache: None,
+            net: NetworkConfig::default(),
+        }
+    }
+}
+
+/// What a fetch downloaded, recorded with the skills installed from it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FetchedOrigin {
+    pub(crate) commit: Option<String>,
+    pub(crate) archive_sha256: Option<String>,
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+}
+
+/// A skill installed by [`download_skill_to_dir`]. Any previous version is put back unless
+/// [`InstalledSkillDir::finish`] is called once the rest of the install has succeeded.
+#[derive(Debug)]
+pub(crate) struct InstalledSkillDir {
+    pub(crate) dir: PathBuf,
+    /// The skill's directory inside the source, unless it is the source's root.
+    pub(crate) subpath: Option<String>,
+    pub(crate) origin: FetchedOrigin,
+    swap: SkillSwap,
+}
+
+impl InstalledSkillDir {
+    /// Keeps the new version, moving the previous one into `history`, and returns where it is.
+    pub(crate) fn finish(self, history: Option<&VersionHistory>) -> SkillsResult<PathBuf> {
+        self.swap.finish(history)?;
+        Ok(self.dir)
+    }
+}
+
+pub(crate) fn download_skill_to_dir(
+    source_url: &str,
+    skill_name: &str,
+    install_base_dir: &Path,
+    allowed_base: &Path,
+    overwrite: bool,
+    ctx: &FetchContext,
+) -> SkillsResult<InstalledSkillDir> {
+    let target_dir = install_target_dir(
+        skill_name,
+        source_url,
+        install_base_dir,
+        allowed_base,
+        overwrite,
+    )?;
+
+    let parsed_source = parse_download_source(source_url)?;
+    if let DownloadSource::LocalDir { path } = &parsed_source {
+        if !path.join("SKILL.md").is_file() {
+            return Err(SkillsError::missing_skill_md(path));
+        }
+        let swap = swap_in_skill_dir(path, &target_dir)?;
+        return Ok(InstalledSkillDir {
+            dir: target_dir,
+            subpath: None,
+            origin: FetchedOrigin::default(),
+            swap,
+        });
+    }
+
+    let fetched = FetchedSource::fetch(source_url, parsed_source, ctx)?;
+    let safe_name = target_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let selected_root = fetched.installed_skill_dir(None, &safe_name)?;
+    ctx.job.check()?;
+    let swap = swap_in_skill_dir(&selected_root, &target_dir)?;
+
+    Ok(InstalledSkillDir {
+        dir: target_dir,
+        subpath: fetched.subpath_of(&selected_root),
+        origin: fetched.origin.clone(),
+        swap,
+    })
+}
+
+/// Where a skill named `skill_name` goes under `install_base_dir`. Fails for bases outside
+/// `allowed_base`, and for skills that are already installed unless `overwrite` is set.
+pub(crate) fn install_target_dir(
+    skill_name: &str,
+    fallback_key: &str,
+    install_base_dir: &Path,
+    allowed_base: &Path,
+    overwrite: bool,
+) -> SkillsResult<PathBuf> {
+    let allowed_base = normalize_path(allowed_base);
+    let requested_base = normalize_path(install_base_dir);
+    if !requested_base.starts_with(&allowed_base) {
+        return Err(SkillsError::outside_allowed_root(install_base_dir));
+    }
+
+    fs::create_dir_all(install_base_dir).map_err(|err| SkillsError::io(install_base_dir, err))?;
+
+    let target_dir = install_base_dir.join(sanitize_skill_dir_name(skill_name, fallback_key));
+    if target_dir.exists() && !overwrite {
+        return Err(SkillsError::already_exists(&target_dir));
+    }
+    Ok(target_dir)
+}
+
+/// A source fetched once, so several skills can be listed or installed from one download.
+/// Downloaded and cloned sources live in a temp dir that is removed on drop.
+pub(crate) struct FetchedSource {
+    /// The source URL without its `#<ref>:<subpath>` fragment.
+    location: String,
+    source: DownloadSource,
+    /// Where the archive was extracted or the repository cloned; a local directory as is.
+    extract_dir: PathBuf,
+    temp_dir: Option<PathBuf>,
+    pub(crate) origin: FetchedOrigin,
+}
+
+impl FetchedSource {
+    pub(crate) fn open(source_url: &str, ctx: &FetchContext) -> SkillsResult<Self> {
+        let source = parse_download_source(source_url)?;
+        Self::fetch(source_url, source, ctx)
+    }
+
+    /// Downloads or clones `source`. A cancelled job stops the transfer, and the temp dir
+    /// goes away with the partially built value.
+    fn fetch(source_url: &str, source: DownloadSource, ctx: &FetchContext) -> SkillsResult<Self> {
+        let trimmed = source_url.trim();
+        let location = match &source {
+            DownloadSource::Git { .. } => trimmed.split('#').next().unwrap_or_default(),
+            _ => split_ref_fragment(trimmed)?.0,
+        }
+        .to_string();
+        if let DownloadSource::LocalDir { path } = &source {
+            return Ok(Self {
+                location,
+                extract_dir: path.clone(),
+                source,
+                temp_dir: None,
+                origin: FetchedOrigin::default(),
+            });
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(SkillsError::internal)?
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!("skills-manager-{}", timestamp));
+        let extract_dir = temp_dir.join("extract");
+        fs::create_dir_all(&extract_dir).map_err(|err| SkillsError::io(&extract_dir, err))?;
+        let mut fetched = Self {
+            location,
+            source,
+            extract_dir,
+            temp_dir: Some(temp_dir),
+            origin: FetchedOrigin::default(),
+        };
+
+        if let DownloadSource::Git { url, git_ref, .. } = &fetched.source {
+            let commit = shallow_clone(
+                url,
+                git_ref.as_deref(),
+                &fetched.extract_dir.join("repo"),
+                ctx,
+            )?;
+            fetched.origin.commit = Some(commit);
+        } else {
+            let archive = download_archive_bytes(&fetched.source, ctx)?;
+            ctx.job.check()?;
+            match fetched.source.archive_format() {
+                ArchiveFormat::Zip => extract_zip(&archive.body, &fetched.extract_dir)?,
+                ArchiveFormat::TarGz => extract_tar_gz(&archive.body, &fetched.extract_dir)?,
+            }
+            fetched.origin = FetchedOrigin {
+                archive_sha256: Some(sha256_hex(&archive.body)),
+                commit: archive.commit,
+                etag: archive.etag,
+                last_modified: archive.last_modified,
+            };
+        }
+        Ok(fetched)
+    }
+
+    /// Where `skill_dir` sits inside the source, or `None` for the source's root.
+    pub(crate) fn subpath_of(&self, skill_dir: &Path) -> Option<String> {
+        let subpath = skill_dir.strip_prefix(self.content_root().ok()?).ok()?;
+        let subpath = subpath.to_string_lossy().replace('\\', "/");
+        (!subpath.is_empty()).then_some(subpath)
+    }
+
+    /// The repository or archive root that subpaths are relative to, skipping the single
+    /// wrapper directory forge archives put everything in.
+    fn content_root(&self) -> SkillsResult<PathBuf> {
+        if self.temp_dir.is_none() {
+            return Ok(self.extract_dir.clone());
+        }
+        let mut entries = fs::read_dir(&self.extract_dir)
+            .map_err(|err| SkillsError::io(&self.extract_dir, err))?
+            .collect::<Result<Vec<_>, _>>()?;
+        match entries.pop() {
+            Some(only) if entries.is_empty() && only.path().is_dir() => Ok(only.path()),
+            _ => Ok(self.extract_dir.clone()),
+        }
+    }
+
+    /// Every directory with a `SKILL.md`, as paths relative to the content root. Sources that
+    /// point at a subdirectory only list the skills inside it.
+    pub(crate) fn skill_subpaths(&self) -> SkillsResult<Vec<PathBuf>> {
+        let root = self.content_root()?;
+        let scope = match self.source.preferred_subpath() {
+            Some(subpath) if root.join(&subpath).is_dir() => root.join(subpath),
+            _ => root.clone(),
+        };
+        let mut subpaths = Vec::new();
+        for entry in WalkDir::new(&scope).max_depth(6).sort_by_file_name() {
+            let entry = entry?;
+            if entry.file_type().is_file() && entry.file_name() == "SKILL.md" {
+                if let Some(parent) = entry.path().parent() {
+                    subpaths.push(parent.strip_prefix(&root)?.to_path_buf());
+                }
+            }
+        }
+        Ok(subpaths)
+    }
+
+    /// The skill directory at `subpath`, which must stay inside the source and hold a
+    /// `SKILL.md`.
+    pub(crate) fn skill_dir(&self, subpath: &str) -> SkillsResult<PathBuf> {
+        let root = self.content_root()?;
+        let trimmed = subpath.trim().trim_matches('/');
+        let skill_dir = if trimmed.is_empty() {
+            root
+        } else {
+            root.join(sanitize_relative_subpath(trimmed).map_err(SkillsError::invalid_request)?)
+        };
+        if !skill_dir.join("SKILL.md").is_file() {
+            return Err(SkillsError::missing_skill_md(&skill_dir));
+        }
+        Ok(skill_dir)
+    }
+
+    /// The skill that was installed as `dir_name` from this source: the one at `subpath` when
+    /// that was recorded, otherwise the one [`download_skill_to_dir`] picks.
+    pub(crate) fn installed_skill_dir(
+        &self,
+        subpath: Option<&str>,
+        dir_name: &str,
+    ) -> SkillsResult<PathBuf> {
+        match subpath {
+            Some(subpath) => self.skill_dir(subpath),
+            None => find_skill_root(
+                &self.extract_dir,
+                dir_name,
+                self.source.preferred_subpath().as_deref(),
+            ),
+        }
+    }
+
+    /// A source URL that installs only the skill at `subpath`, so it can be updated later
+    /// on its own.
+    pub(crate) fn skill_source_url(&self, subpath: &Path) -> String {
+        let subpath = subpath.to_string_lossy().replace('\\', "/");
+        if subpath.is_empty() {
+            return self.location.clone();
+        }
+        match &self.source {
+            DownloadSource::GitHubRepo { owner, repo } => {
+                format!("{GITHUB_WEB_PREFIX}{owner}/{repo}/tree/HEAD/{subpath}")
+            }
+            DownloadSource::GitHubTree {
+                owner,
+                repo,
+                git_ref,
+                ..
+            } => format!("{GITHUB_WEB_PREFIX}{owner}/{repo}/tree/{git_ref}/{subpath}"),
+            DownloadSource::LocalDir { path } => file_url(&path.join(subpath)),
+            DownloadSource::GitLab { git_ref, .. }
+            | DownloadSource::Gitea { git_ref, .. }
+            | DownloadSource::Bitbucket { git_ref, .. }
+            | DownloadSource::Git { git_ref, .. } => format!(
+                "{}#{}:{subpath}",
+                self.location,
+                git_ref.as_deref().unwrap_or_default()
+            ),
+            DownloadSource::ZipUrl { .. }
+            | DownloadSource::TarGzUrl { .. }
+            | DownloadSource::LocalArchive { .. } => format!("{}#:{subpath}", self.location),
+        }
+    }
+}
+
+impl Drop for FetchedSource {
+    fn drop(&mut self) {
+        if let Some(temp_dir) = &self.temp_dir {
+            let _ = fs::remove_dir_all(temp_dir);
+        }
+    }
+}
+
+/// A downloaded archive and what identifies its content.
+struct Archive {
+    body: Vec<u8>,
+    commit: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Archive {
+    fn new(body: Vec<u8>) -> Self {
+        Self {
+            body,
+            commit: None,
+            etag: None,
+            last_modified: None,
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn download_archive_bytes(source: &DownloadSource, ctx: &FetchContext) -> SkillsResult<Archive> {
+    match source {
+        DownloadSource::GitHubRepo { owner, repo } => github_archive(owner, repo, "HEAD", ctx),
+        DownloadSource::GitHubTree {
+            owner,
+            repo,
+            git_ref,
+            ..
+        } => github_archive(owner, repo, git_ref, ctx),
+        DownloadSource::ZipUrl { url, .. } | DownloadSource::TarGzUrl { url, .. } => {
+            download_revalidated(url, &[("User-Agent", USER_AGENT)], ctx)
+        }
+        DownloadSource::LocalDir { path } => Err(SkillsError::internal(format!(
+            "{} is a directory, not an archive",
+            path.display()
+        ))),
+        DownloadSource::LocalArchive { path, .. } => {
+            let size = fs::metadata(path)
+                .map_err(|err| SkillsError::io(path, err))?
+                .len();
+            if size > MAX_DOWNLOAD_SIZE {
+                return Err(SkillsError::InvalidArchive {
+                    detail: format!(
+                        "{} is larger than {} MiB",
+                        path.display(),
+                        MAX_DOWNLOAD_SIZE / 1024 / 1024
+                    ),
+                });
+            }
+            fs::read(path)
+                .map(Archive::new)
+                .map_err(|err| SkillsError::io(path, err))
+        }
+        DownloadSource::GitLab {
+            base_url, project, ..
+        } => forge_archive(source, ctx, |sha| {
+            format!(
+                "{base_url}/api/v4/projects/{}/repository/archive.zip?sha={sha}",
+                urlencoding::encode(project)
+            )
+        }),
+        DownloadSource::Gitea {
+            base_url,
+            owner,
+            repo,
+            ..
+        } => forge_archive(source, ctx, |sha| {
+            format!("{base_url}/{owner}/{repo}/archive/{sha}.zip")
+        }),
+        DownloadSource::Bitbucket { owner, repo, .. } => forge_archive(source, ctx, |sha| {
+            format!("https://bitbucket.org/{owner}/{repo}/get/{sha}.zip")
+        }),
+        DownloadSource::Git { url, .. } => Err(SkillsError::internal(format!(
+            "{url} is cloned with git, not downloaded as an archive"
+        ))),
+    }
+}
+
+const GITHUB_API_HEADERS: [(&str, &str); 3] = [
+    ("Accept", "application/vnd.github+json"),
+    ("X-GitHub-Api-Version", "2022-11-28"),
+    ("User-Agent", USER_AGENT),
+];
+
+/// Downloads the zipball of `git_ref`, pinned to the commit it points at so the install
+/// records exactly what it got and the archive can be cached by content. When the commit
+/// cannot be resolved, for example under a rate limit, nothing is downloaded.
+fn github_archive(
+    owner: &str,
+    repo: &str,
+    git_ref: &str,
+    ctx: &FetchContext,
+) -> SkillsResult<Archive> {
+    let zipball_url =
+        |sha: &str| format!("https://api.github.com/repos/{owner}/{repo}/zipball/{sha}");
+    let sha = resolve_github_commit(owner, repo, git_ref, &ctx.net)?;
+    let key = format!("github:{owner}/{repo}@{sha}");
+    let cached = ctx.cache.as_ref().and_then(|cache| cache.get(&key));
+    let body = match cached {
+        Some(cached) => cached.body,
+        None => {
+            let body = download_job_bytes(&zipball_url(&sha), &GITHUB_API_HEADERS, ctx)?;
+            if let Some(cache) = &ctx.cache {
+                // Failing to cache only costs the next install a download.
+                let _ = cache.put(&key, &body, None, None);
+            }
+            body
+        }
+    };
+    Ok(Archive {
+        commit: Some(sha),
+        ..Archive::new(body)
+    })
+}
+
+fn is_commit_sha(value: &str) -> bool {
+    value.len() == 40 && value.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// The commit `git_ref` currently points at.
+fn resolve_github_commit(
+    owner: &str,
+    repo: &str,
+    git_ref: &str,
+    net: &NetworkConfig,
+) -> SkillsResult<String> {
+    if is_commit_sha(git_ref) {
+        return Ok(git_ref.to_ascii_lowercase());
+    }
+    let url = format!(
+        "https://api.github.com/repos/{owner}/{repo}/commits/{}",
+        urlencoding::encode(git_ref)
+    );
+    let body = download_bytes_with_timeout(
+        &url,
+        &[
+            ("Accept", "application/vnd.github.sha"),
+            ("X-GitHub-Api-Version", "2022-11-28"),
+            ("User-Agent", USER_AGENT),
+        ],
+        30,
+        &DownloadJob::default(),
+        net,
+    )?;
+    let sha = String::from_utf8_lossy(&body).trim().to_ascii_lowercase();
+    if !is_commit_sha(&sha) {
+        return Err(SkillsError::NetworkError {
+            url,
+            status: None,
+            detail: "Response is not a commit SHA".to_string(),
+        });
+    }
+    Ok(sha)
+}
+
+/// Downloads a GitLab, Gitea or Bitbucket archive at the commit its ref points at, so the
+/// install records exactly what it got.
+fn forge_archive(
+    source: &DownloadSource,
+    ctx: &FetchContext,
+    archive_url: impl FnOnce(&str) -> String,
+) -> SkillsResult<Archive> {
+    let sha = resolve_forge_commit(source, &ctx.net)?;
+    let archive = download_revalidated(&archive_url(&sha), &[("User-Agent", USER_AGENT)], ctx)?;
+    Ok(Archive {
+        commit: Some(sha),
+        ..archive
+    })
+}
+
+/// The commit the ref of a GitLab, Gitea or Bitbucket source points at, or its default
+/// branch when it names none, looked up through the forge's API.
+fn resolve_forge_commit(source: &DownloadSource, net: &NetworkConfig) -> SkillsResult<String> {
+    let (api_url, default_branch_pointer, commit_url, sha_pointer) = match source {
+        DownloadSource::GitLab {
+            base_url, project, ..
+        } => {
+            let api_url = format!(
+                "{base_url}/api/v4/projects/{}",
+                urlencoding::encode(project)
+            );
+            let commit_url = format!("{api_url}/repository/commits/");
+            (api_url, "/default_branch", commit_url, "/id")
+        }
+        DownloadSource::Gitea {
+            base_url,
+            owner,
+            repo,
+            ..
+        } => {
+            let api_url = format!("{base_url}/api/v1/repos/{owner}/{repo}");
+            let commit_url = format!("{api_url}/commits?limit=1&sha=");
+            (api_url, "/default_branch", commit_url, "/0/sha")
+        }
+        DownloadSource::Bitbucket { owner, repo, .. } => {
+            let api_url = format!("https://api.bitbucket.org/2.0/repositories/{owner}/{repo}");
+            let commit_url = format!("{api_url}/commit/");
+            (api_url, "/mainbranch/name", commit_url, "/hash")
+        }
+        _ => {
+            return Err(SkillsError::internal(
+                "Only GitLab, Gitea and Bitbucket refs are resolved through their API",
+            ))
+        }
+    };
+    let git_ref = match source {
+        DownloadSource::GitLab { git_ref, .. }
+        | DownloadSource::Gitea { git_ref, .. }
+        | DownloadSource::Bitbucket { git_ref, .. } => git_ref.clone(),
+        _ => None,
+    };
+    let git_ref = match git_ref {
+        Some(git_ref) if is_commit_sha(&git_ref) => return Ok(git_ref.to_ascii_lowercase()),
+        Some(git_ref) => git_ref,
+        None => api_string(
+            &api_url,
+            default_branch_pointer,
+            "Response does not name a default branch",
+            net,
+        )?,
+    };
+    let commit_url = format!("{commit_url}{}", urlencoding::encode(&git_ref));
+    let sha = api_string(
+        &commit_url,
+        sha_pointer,
+        "Response does not name a commit",
+        net,
+    )?
+    .to_ascii_lowercase();
+    if !is_commit_sha(&sha) {
+        return Err(SkillsError::NetworkError {
+            url: commit_url,
+            status: None,
+            detail: "Response is not a commit SHA".to_string(),
+        });
+    }
+    Ok(sha)
+}
+
+/// The commit a GitHub, GitLab, Gitea, Bitbucket or git source currently points at, without
+/// downloading it. Archives and local sources are not addressed by commit and give `None`.
+pub(crate) fn remote_commit(source_url: &str, net: &NetworkConfig) -> SkillsResult<Option<String>> {
+    match parse_download_source(source_url)? {
+        DownloadSource::GitHubRepo { owner, repo } => {
+            resolve_github_commit(&owner, &repo, "HEAD", net).map(Some)
+        }
+        DownloadSource::GitHubTree {
+            owner,
+            repo,
+            git_ref,
+            ..
+        } => resolve_github_commit(&owner, &repo, &git_ref, net).map(Some),
+        source @ (DownloadSource::GitLab { .. }
+        | DownloadSource::Gitea { .. }
+        | DownloadSource::Bitbucket { .. }) => resolve_forge_commit(&source, net).map(Some),
+        DownloadSource::Git { url, git_ref, .. } => {
+            let git_ref = git_ref.as_deref().unwrap_or("HEAD");
+            if is_commit_sha(git_ref) {
+                return Ok(Some(git_ref.to_ascii_lowercase()));
+            }
+            net.ensure_online(&url)?;
+            let mut args = net.git_config_args(&url);
+            args.extend(["ls-remote", "--quiet", &url, git_ref].map(str::to_string));
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let listed = run_git(&url, &std::env::temp_dir(), &args, &DownloadJob::default())?;
+            Ok(listed
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .find(|sha| is_commit_sha(sha))
+                .map(str::to_ascii_lowercase))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Whether installs from `source_url` record the commit they got, so it can be locked.
+pub(crate) fn is_commit_addressable(source_url: &str) -> bool {
+    matches!(
+        parse_download_source(source_url),
+        Ok(DownloadSource::GitHubRepo { .. }
+            | DownloadSource::GitHubTree { .. }
+            | DownloadSource::GitLab { .. }
+            | DownloadSource::Gitea { .. }
+            | DownloadSource::Bitbucket { .. }
+            | DownloadSource::Git { .. })
+    )
+}
+
+/// `source_url` with its ref replaced by `commit`, for the sources whose archives or clones
+/// can be addressed by commit. Archive URLs and local paths cannot be pinned.
+pub(crate) fn pinned_source_url(source_url: &str, commit: &str) -> SkillsResult<String> {
+    let trimmed = source_url.trim();
+    let subpath_of = |subpath: Option<PathBuf>| {
+        subpath
+            .map(|subpath| subpath.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default()
+    };
+    match parse_download_source(trimmed)? {
+        DownloadSource::GitHubRepo { owner, repo } => {
+            Ok(format!("{GITHUB_WEB_PREFIX}{owner}/{repo}/tree/{commit}"))
+        }
+        DownloadSource::GitHubTree {
+            owner,
+            repo,
+            subpath,
+            ..
+        } => Ok(format!(
+            "{GITHUB_WEB_PREFIX}{owner}/{repo}/tree/{commit}/{}",
+            subpath_of(Some(subpath))
+        )
+        .trim_end_matches('/')
+        .to_string()),
+        DownloadSource::Git { subpath, .. } => Ok(format!(
+            "{}#{commit}:{}",
+            trimmed.split('#').next().unwrap_or_default(),
+            subpath_of(subpath)
+        )),
+        DownloadSource::GitLab { subpath, .. }
+        | DownloadSource::Gitea { subpath, .. }
+        | DownloadSource::Bitbucket { subpath, .. } => Ok(format!(
+            "{}#{commit}:{}",
+            split_ref_fragment(trimmed)?.0,
+            subpath_of(subpath)
+        )),
+        DownloadSource::ZipUrl { .. }
+        | DownloadSource::TarGzUrl { .. }
+        | DownloadSource::LocalDir { .. }
+        | DownloadSource::LocalArchive { .. } => Err(SkillsError::unsupported_source(
+            trimmed,
+            "Only GitHub, GitLab, Gitea, Bitbucket and git sources can be pinned to a commit",
+        )),
+    }
+}
+
+/// Downloads `url`, reusing the cached copy when the server answers a conditional request
+/// with `304 Not Modified`. Responses without an `ETag` or `Last-Modified` are not cached,
+/// since there would be no way to tell when they go stale.
+fn download_revalidated(
+    url: &str,
+    headers: &[(&str, &str)],
+    ctx: &FetchContext,
+) -> SkillsResult<Archive> {
+    let Some(cache) = &ctx.cache else {
+        let response = stream_with_mirrors(url, headers, ctx)?;
+        return Ok(Archive {
+            etag: response.etag,
+            last_modified: response.last_modified,
+            ..Archive::new(response.body.unwrap_or_default())
+        });
+    };
+    let cached = cache.get(url);
+    let mut request_headers = headers.to_vec();
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request_headers.push(("If-None-Match", etag));
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request_headers.push(("If-Modified-Since", last_modified));
+        }
+    }
+
+    let response = stream_with_mirrors(url, &request_headers, ctx)?;
+    match (response.body, cached) {
+        (Some(body), _) => {
+            if response.etag.is_some() || response.last_modified.is_some() {
+                let _ = cache.put(
+                    url,
+                    &body,
+                    response.etag.as_deref(),
+                    response.last_modified.as_deref(),
+                );
+            }
+            Ok(Archive {
+                etag: response.etag,
+                last_modified: response.last_modified,
+                ..Archive::new(body)
+            })
+        }
+        (None, Some(cached)) => Ok(Archive {
+            etag: cached.etag,
+            last_modified: cached.last_modified,
+            ..Archive::new(cached.body)
+        }),
+        (None, None) => Err(SkillsError::NetworkError {
+            url: url.to_string(),
+            status: Some(304),
+            detail: "Not modified, but nothing is cached".to_string(),
+        }),
+    }
+}
+
+/// Reads the string at `pointer` from a forge's JSON API response, failing with `missing`
+/// when there is none.
+fn api_string(
+    api_url: &str,
+    pointer: &str,
+    missing: &str,
+    net: &NetworkConfig,
+) -> SkillsResult<String> {
+    let body = download_bytes_with_timeout(
+        api_url,
+        &[("Accept", "application/json"), ("User-Agent", USER_AGENT)],
+        30,
+        &DownloadJob::default(),
+        net,
+    )?;
+    let repository: serde_json::Value = serde_json::from_slice(&body)?;
+    repository
+        .pointer(pointer)
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| SkillsError::NetworkError {
+            url: api_url.to_string(),
+            status: None,
+            detail: missing.to_string(),
+        })
+}
+
+/// Fetches only the requested commit into `dest`, then drops `.git` so the checkout can be
+/// copied like an extracted archive. `fetch` rather than `clone --branch` also accepts tags
+/// and commit ids. Returns the commit that was checked out.
+fn shallow_clone(
+    url: &str,
+    git_ref: Option<&str>,
+    dest: &Path,
+    ctx: &FetchContext,
+) -> SkillsResult<String> {
+    ctx.net.ensure_online(url)?;
+    let job = ctx.job;
+    fs::create_dir_all(dest).map_err(|err| SkillsError::io(dest, err))?;
+    run_git(url, dest, &["init", "--quiet"], job)?;
+    run_git(url, dest, &["remote", "add", "origin", url], job)?;
+    let mut fetch_args = ctx.net.git_config_args(url);
+    fetch_args.extend(
+        ["fetch", "--quiet", "--depth", "1", "origin"]
+            .into_iter()
+            .chain([git_ref.unwrap_or("HEAD")])
+            .map(str::to_string),
+    );
+    let fetch_args: Vec<&str> = fetch_args.iter().map(String::as_str).collect();
+    run_git(url, dest, &fetch_args, job)?;
+    run_git(url, dest, &["checkout", "--quiet", "FETCH_HEAD"], job)?;
+    let commit = run_git(url, dest, &["rev-parse", "HEAD"], job)?;
+    let git_dir = dest.join(".git");
+    fs::remove_dir_all(&git_dir).map_err(|err| SkillsError::io(&git_dir, err))?;
+    Ok(commit)
+}
+
+/// Runs `git` in `dir`, killing it if `job` is cancelled while it runs, and returns what it
+/// printed, trimmed.
+fn run_git(url: &str, dir: &Path, args: &[&str], job: &DownloadJob) -> SkillsResult<String> {
+    job.check()?;
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        // Fail instead of waiting for credentials nobody can type in.
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                SkillsError::unsupported_source(url, "git must be installed to clone this source")
+            }
+            _ => SkillsError::io(dir, err),
+        })?;
+    while child
+        .try_wait()
+        .map_err(|err| SkillsError::io(dir, err))?
+        .is_none()
+    {
+        if let Err(cancelled) = job.check() {
+            // The process may already have exited; the cancellation is reported either way.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(cancelled);
+        }
+        std::thread::sleep(PROGRESS_INTERVAL);
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| SkillsError::io(dir, err))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+    Err(SkillsError::NetworkError {
+        url: url.to_string(),
+        status: None,
+        detail: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+fn parse_download_source(source_url: &str) -> SkillsResult<DownloadSource> {
+    let trimmed = source_url.trim();
+    if trimmed.is_empty() {
+        return Err(SkillsError::invalid_request("A source URL is required"));
+    }
+
+    if let Some(github) = parse_github_source(trimmed)? {
+        return Ok(github);
+    }
+
+    if let Some(git) = parse_git_source(trimmed)? {
+        return Ok(git);
+    }
+
+    // The remaining sources take an optional `#<ref>:<subpath>` fragment too.
+    let (location, git_ref, subpath) = split_ref_fragment(trimmed)?;
+
+    // `file://` URLs and absolute paths name a skill directory or an archive file.
+    if let Some(path) = path_from_file_url(location)
+        .or_else(|| Some(PathBuf::from(location)).filter(|path| path.is_absolute()))
+    {
+        if path.is_dir() {
+            let path = match subpath {
+                Some(subpath) => path.join(subpath),
+                None => path,
+            };
+            if !path.is_dir() {
+                return Err(SkillsError::not_found(&path));
+            }
+            return Ok(DownloadSource::LocalDir { path });
+        }
+        if !path.is_file() {
+            return Err(SkillsError::not_found(&path));
+        }
+        let format = ArchiveFormat::from_name(&path.to_string_lossy()).ok_or_else(|| {
+            SkillsError::unsupported_source(
+                trimmed,
+                "Local sources must be a directory or a .zip, .tar.gz or .tgz file",
+            )
+        })?;
+        return Ok(DownloadSource::LocalArchive {
+            path,
+            format,
+            subpath: None,
+        }
+        .with_fragment(git_ref, subpath));
+    }
+
+    let url = location.to_string();
+    match remote_archive_format(location) {
+        Some(ArchiveFormat::Zip) => return Ok(DownloadSource::ZipUrl { url, subpath }),
+        Some(ArchiveFormat::TarGz) => return Ok(DownloadSource::TarGzUrl { url, subpath }),
+        None => {}
+    }
+
+    if let Some(hosted) = parse_hosted_source(location)? {
+        return Ok(hosted.with_fragment(git_ref, subpath));
+    }
+
+    Err(SkillsError::unsupported_source(
+        trimmed,
+        "Only GitHub, GitLab, Gitea and Bitbucket URLs, git remotes, local paths or .zip / .tar.gz download URLs are supported",
+    ))
+}
+
+/// Splits off a `#<ref>:<subpath>` fragment. Fragments without `:` are left in place, since
+/// they may belong to the URL itself.
+fn split_ref_fragment(source_url: &str) -> SkillsResult<(&str, Option<String>, Option<PathBuf>)> {
+    match source_url.split_once('#') {
+        Some((location, fragment)) if fragment.contains(':') => {
+            let (git_ref, subpath) = parse_ref_fragment(source_url, fragment)?;
+            Ok((location, git_ref, subpath))
+        }
+        _ => Ok((source_url, None, None)),
+    }
+}
+
+fn parse_ref_fragment(
+    source_url: &str,
+    fragment: &str,
+) -> SkillsResult<(Option<String>, Option<PathBuf>)> {
+    let (git_ref, subpath) = fragment.split_once(':').unwrap_or((fragment, ""));
+    Ok((
+        git_ref_or_default(source_url, git_ref)?,
+        optional_subpath(source_url, subpath)?,
+    ))
+}
+
+/// Whether `source_url` parses as something the downloader knows how to fetch.
+pub(crate) fn is_downloadable_source_url(source_url: &str) -> bool {
+    parse_download_source(source_url).is_ok()
+}
+
+/// Generic git remotes: `git+<scheme>://`, `ssh://`, `git://`, scp-style `user@host:path`
+/// and http(s) URLs ending in `.git`, each optionally followed by `#<ref>:<subpath>`.
+fn parse_git_source(source_url: &str) -> SkillsResult<Option<DownloadSource>> {
+    let (url, fragment) = match source_url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (source_url, None),
+    };
+    let lower = url.to_ascii_lowercase();
+    let url = if let Some(stripped) = url.strip_prefix("git+") {
+        stripped
+    } else if lower.starts_with("ssh://")
+        || lower.starts_with("git://")
+        || is_scp_like_remote(url)
+        || ((lower.starts_with("https://") || lower.starts_with("http://"))
+            && lower
+                .split('?')
+                .next()
+                .unwrap_or_default()
+                .ends_with(".git"))
+    {
+        url
+    } else {
+        return Ok(None);
+    };
+    if url.is_empty() || url.starts_with('-') {
+        return Err(SkillsError::unsupported_source(
+            source_url,
+            "Git URL is missing the remote",
+        ));
+    }
+
+    let (git_ref, subpath) = match fragment {
+        Some(fragment) => parse_ref_fragment(source_url, fragment)?,
+        None => (None, None),
+    };
+    Ok(Some(DownloadSource::Git {
+        url: url.to_string(),
+        git_ref,
+        subpath,
+    }))
+}
+
+/// `git@host:owner/repo.git`, the form `git clone` accepts without a scheme.
+fn is_scp_like_remote(url: &str) -> bool {
+    if url.contains("://") || url.starts_with(['-', '/']) {
+        return false;
+    }
+    match (url.find('@'), url.find(':')) {
+        (Some(at), Some(colon)) => at > 0 && colon > at + 1,
+        _ => false,
+    }
+}
+
+fn git_ref_or_default(source_url: &str, git_ref: &str) -> SkillsResult<Option<String>> {
+    let git_ref = git_ref.trim();
+    if git_ref.starts_with('-') {
+        return Err(SkillsError::unsupported_source(
+            source_url,
+            "Git ref cannot start with '-'",
+        ));
+    }
+    Ok(Some(git_ref.to_string()).filter(|git_ref| !git_ref.is_empty()))
+}
+
+fn optional_subpath(source_url: &str, raw: &str) -> SkillsResult<Option<PathBuf>> {
+    let raw = raw.trim_matches('/');
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    sanitize_relative_subpath(raw)
+        .map(Some)
+        .map_err(|reason| SkillsError::unsupported_source(source_url, reason))
+}
+
+/// Web URLs of GitLab, Gitea/Forgejo and Bitbucket repositories and their tree pages.
+/// Self-hosted instances are recognised by a `gitlab.` / `gitea.` / `forgejo.` host or by
+/// the forge's URL layout (`/-/tree/`, `/-/blob/`, `/-/archive/` or `/src/branch/`).
+fn parse_hosted_source(source_url: &str) -> SkillsResult<Option<DownloadSource>> {
+    let Some((scheme, rest)) = source_url.split_once("://") else {
+        return Ok(None);
+    };
+    if !scheme.eq_ignore_ascii_case("https") && !scheme.eq_ignore_ascii_case("http") {
+        return Ok(None);
+    }
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = authority
+        .rsplit('@')
+        .next()
+        .unwrap_or_default()
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let base_url = format!("{}://{}", scheme.to_ascii_lowercase(), authority);
+    let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+
+    let is_gitlab = host == "gitlab.com"
+        || host.starts_with("gitlab.")
+        || parts
+            .windows(2)
+            .any(|pair| pair[0] == "-" && matches!(pair[1], "tree" | "blob" | "archive"));
+    let is_bitbucket = host == "bitbucket.org";
+    let is_gitea = host == "codeberg.org"
+        || host.starts_with("gitea.")
+        || host.starts_with("forgejo.")
+        || (parts.get(2) == Some(&"src")
+            && matches!(parts.get(3), Some(&"branch" | &"tag" | &"commit")));
+
+    if is_gitlab {
+        let (project, tree) = match parts.iter().position(|part| *part == "-") {
+            Some(index) => (&parts[..index], &parts[index + 1..]),
+            None => (&parts[..], &[][..]),
+        };
+        if project.len() < 2 {
+            return Err(SkillsError::unsupported_source(
+                source_url,
+                "GitLab URLs must include the group and project",
+            ));
+        }
+        let project = project.join("/");
+        let project = project.strip_suffix(".git").unwrap_or(&project).to_string();
+        let (git_ref, subpath) = match tree {
+            ["tree", git_ref, subpath @ ..] => (
+                git_ref_or_default(source_url, git_ref)?,
+                optional_subpath(source_url, &subpath.join("/"))?,
+            ),
+            ["blob", ..] => {
+                return Err(SkillsError::unsupported_source(
+                    source_url,
+                    "GitLab file URLs are not supported, use a project or directory URL",
+                ))
+            }
+            _ => (None, None),
+        };
+        return Ok(Some(DownloadSource::GitLab {
+            base_url,
+            project,
+            git_ref,
+            subpath,
+        }));
+    }
+
+    if !is_bitbucket && !is_gitea {
+        return Ok(None);
+    }
+    let (owner, repo) = match parts.as_slice() {
+        [owner, repo, ..] => (
+            owner.to_string(),
+            repo.strip_suffix(".git").unwrap_or(repo).to_string(),
+        ),
+        _ => {
+            return Err(SkillsError::unsupported_source(
+                source_url,
+                "Repository URLs must include owner/repo",
+            ))
+        }
+    };
+    // Bitbucket trees live at `src/<ref>/<path>`, Gitea ones at `src/<kind>/<ref>/<path>`.
+    let tree = match (parts.get(2), is_bitbucket) {
+        (Some(&"src"), true) => &parts[3..],
+        (Some(&"src"), false) if parts.len() > 3 => &parts[4..],
+        _ => &[][..],
+    };
+    let (git_ref, subpath) = match tree {
+        [git_ref, subpath @ ..] => (
+            git_ref_or_default(source_url, git_ref)?,
+            optional_subpath(source_url, &subpath.join("/"))?,
+        ),
+        [] => (None, None),
+    };
+    Ok(Some(if is_bitbucket {
+        DownloadSource::Bitbucket {
+            owner,
+            repo,
+            git_ref,
+            subpath,
+        }
+    } else {
+        DownloadSource::Gitea {
+            base_url,
+            owner,
+            repo,
+            git_ref,
+            subpath,
+        }
+    }))
+}
+
+fn parse_github_source(source_url: &str) -> SkillsResult<Option<DownloadSource>> {
+    let Some(stripped) = source_url.strip_prefix(GITHUB_WEB_PREFIX) else {
+        return Ok(None);
+    };
+
+    let path_without_query = stripped
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_matches('/');
+    let parts: Vec<&str> = path_without_query
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    if parts.len() < 2 {
+        return Err(SkillsError::unsupported_source(
+            source_url,
+            "GitHub URLs must include owner/repo",
+        ));
+    }
+
+    let owner = parts[0].to_string();
+    let repo = parts[1]
+        .strip_suffix(".git")
+        .unwrap_or(parts[1])
+        .to_string();
+    if owner.is_empty() || repo.is_empty() {
+        return Err(SkillsError::unsupported_source(
+            source_url,
+            "GitHub URL is missing the owner or repo",
+        ));
+    }
+
+    if parts.len() == 2 {
+        return Ok(Some(DownloadSource::GitHubRepo { owner, repo }));
+    }
+
+    match parts[2] {
+        "tree" => {
+            if parts.len() < 4 {
+                return Err(SkillsError::unsupported_source(
+                    source_url,
+                    "GitHub tree URL is missing the branch",
+                ));
+            }
+            let git_ref = parts[3].to_string();
+            // `tree/<ref>` alone is the whole repository at that ref.
+            let subpath = match parts.len() {
+                4 => PathBuf::new(),
+                _ => sanitize_relative_subpath(&parts[4..].join("/"))
+                    .map_err(|reason| SkillsError::unsupported_source(source_url, reason))?,
+            };
+            Ok(Some(DownloadSource::GitHubTree {
+                owner,
+                repo,
+                git_ref,
+                subpath,
+            }))
+        }
+        "blob" => Err(SkillsError::unsupported_source(
+            source_url,
+            "GitHub file URLs are not supported, use a repository, directory or ZIP URL",
+        )),
+        _ => Ok(Some(DownloadSource::GitHubRepo { owner, repo })),
+    }
+}
+
+fn sanitize_relative_subpath(raw: &str) -> Result<PathBuf, &'static str> {
+    let mut output = PathBuf::new();
+    for component in Path::new(raw).components() {
+        match component {
+            Component::Normal(value) => output.push(value),
+            Component::CurDir => {}
+            _ => return Err("Subdirectory path must be relative"),
+        }
+    }
+
+    if output.as_os_str().is_empty() {
+        return Err("Subdirectory path cannot be empty");
+    }
+
+    Ok(output)
+}
+
+/// `skill.tar.gz` -> `skill`, for naming a skill after the archive it came in.
+pub(crate) fn archive_stem(file_name: &str) -> &str {
+    let lower = file_name.to_ascii_lowercase();
+    [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .find(|extension| lower.ends_with(*extension))
+        .map_or(file_name, |extension| {
+            &file_name[..file_name.len() - extension.len()]
+        })
+}
+
+fn remote_archive_format(url: &str) -> Option<ArchiveFormat> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return None;
+    }
+    ArchiveFormat::from_name(url.split(['?', '#']).next().unwrap_or_default())
+}
+
+impl DownloadSource {
+    fn preferred_subpath(&self) -> Option<PathBuf> {
+        match self {
+            DownloadSource::GitHubTree { subpath, .. } => {
+                Some(subpath.clone()).filter(|subpath| !subpath.as_os_str().is_empty())
+            }
+            DownloadSource::GitLab { subpath, .. }
+            | DownloadSource::Gitea { subpath, .. }
+            | DownloadSource::Bitbucket { subpath, .. }
+            | DownloadSource::Git { subpath, .. }
+            | DownloadSource::ZipUrl { subpath, .. }
+            | DownloadSource::TarGzUrl { subpath, .. }
+            | DownloadSource::LocalArchive { subpath, .. } => subpath.clone(),
+            DownloadSource::GitHubRepo { .. } | DownloadSource::LocalDir { .. } => None,
+        }
+    }
+
+    /// Applies the ref and subpath of a `#<ref>:<subpath>` fragment; archives have no refs.
+    fn with_fragment(
+        mut self,
+        fragment_ref: Option<String>,
+        fragment_subpath: Option<PathBuf>,
+    ) -> Self {
+        match &mut self {
+            DownloadSource::GitLab {
+                git_ref, subpath, ..
+            }
+            | DownloadSource::Gitea {
+                git_ref, subpath, ..
+            }
+            | DownloadSource::Bitbucket {
+                git_ref, subpath, ..
+            } => {
+                if fragment_ref.is_some() {
+                    *git_ref = fragment_ref;
+                }
+                if fragment_subpath.is_some() {
+                    *subpath = fragment_subpath;
+                }
+            }
+            DownloadSource::ZipUrl { subpath, .. }
+            | DownloadSource::TarGzUrl { subpath, .. }
+            | DownloadSource::LocalArchive { subpath, .. }
+                if fragment_subpath.is_some() =>
+            {
+                *subpath = fragment_subpath;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    fn archive_format(&self) -> ArchiveFormat {
+        match self {
+            DownloadSource::TarGzUrl { .. } => ArchiveFormat::TarGz,
+            DownloadSource::LocalArchive { format, .. } => *format,
+            _ => ArchiveFormat::Zip,
+        }
+    }
+}
+
+pub fn extract_zip(buf: &[u8], extract_dir: &Path) -> SkillsResult<()> {
+    let cursor = Cursor::new(buf);
+    let mut zip = ZipArchive::new(cursor)?;
+
+    let canonical_extract = extract_dir
+        .canonicalize()
+        .unwrap_or_else(|_| extract_dir.to_path_buf());
+
+    for i in 0..zip.len() {
+        let file = zip.by_index(i)?;
+        let Some(enclosed) = file.enclosed_name() else {
+            continue;
+        };
+        let out_path = canonical_extract.join(&enclosed);
+
+        if !is_within_directory(&canonical_extract, &out_path) {
+            return Err(SkillsError::ZipSlip {
+                entry: enclosed.display().to_string(),
+            });
+        }
+
+        if file.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|err| SkillsError::io(&out_path, err))?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| SkillsError::io(parent, err))?;
+        }
+        let mut outfile =
+            fs::File::create(&out_path).map_err(|err| SkillsError::io(&out_path, err))?;
+
+        std::io::copy(&mut file.take(MAX_EXTRACTED_FILE_SIZE), &mut outfile)
+            .map_err(|err| SkillsError::io(&out_path, err))?;
+    }
+
+    Ok(())
+}
+
+/// Extracts a gzip-compressed tarball with the same guarantees as [`extract_zip`]: entries
+/// that would land outside `extract_dir` are rejected, and so are links, which
+/// [`copy_dir_recursive`] would refuse to install anyway.
+pub fn extract_tar_gz(buf: &[u8], extract_dir: &Path) -> SkillsResult<()> {
+    let invalid = |err: std::io::Error| SkillsError::InvalidArchive {
+        detail: err.to_string(),
+    };
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(buf));
+
+    let canonical_extract = extract_dir
+        .canonicalize()
+        .unwrap_or_else(|_| extract_dir.to_path_buf());
+
+    for entry in archive.entries().map_err(invalid)? {
+        let entry = entry.map_err(invalid)?;
+        let entry_path = entry.path().map_err(invalid)?.into_owned();
+        let mut enclosed = PathBuf::new();
+        for component in entry_path.components() {
+            match component {
+                Component::Normal(part) => enclosed.push(part),
+                Component::CurDir => {}
+                _ => {
+                    return Err(SkillsError::ZipSlip {
+                        entry: entry_path.display().to_string(),
+                    })
+                }
+            }
+        }
+        if enclosed.as_os_str().is_empty() {
+            continue;
+        }
+        let out_path = canonical_extract.join(&enclosed);
+        if !is_within_directory(&canonical_extract, &out_path) {
+            return Err(SkillsError::ZipSlip {
+                entry: enclosed.display().to_string(),
+            });
+        }
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|err| SkillsError::io(&out_path, err))?;
+            continue;
+        }
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(SkillsError::symlink_rejected(&enclosed));
+        }
+        // PAX headers, devices and FIFOs carry nothing a skill needs.
+        if !entry_type.is_file() {
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| SkillsError::io(parent, err))?;
+        }
+        let mut outfile =
+            fs::File::create(&out_path).map_err(|err| SkillsError::io(&out_path, err))?;
+        std::io::copy(&mut entry.take(MAX_EXTRACTED_FILE_SIZE), &mut outfile)
+            .map_err(|err| SkillsError::io(&out_path, err))?;
+    }
+
+    Ok(())
+}
+
+pub fn copy_dir_recursive(src: &Path, dst: &Path) -> SkillsResult<()> {
+    for entry in WalkDir::new(src) {
+        let entry = entry?;
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            return Err(SkillsError::symlink_rejected(entry.path()));
+        }
+        let rel_path = entry.path().strip_prefix(src)?;
+        let target = dst.join(rel_path);
+        if file_type.is_dir() {
+            fs::create_dir_all(&target).map_err(|err| SkillsError::io(&target, err))?;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|err| SkillsError::io(parent, err))?;
+            }
+            fs::copy(entry.path(), &target).map_err(|err| SkillsError::io(&target, err))?;
+        }
+    }
+    Ok(())
+}
+
+/// Recreates the directories of `src` under `dst` and hard-links every file into them, so the
+/// tree takes no extra space. Both must be on the same filesystem.
+pub fn hardlink_dir_recursive(src: &Path, dst: &Path) -> SkillsResult<()> {
+    for entry in WalkDir::new(src) {
+        let entry = entry?;
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            return Err(SkillsError::symlink_rejected(entry.path()));
+        }
+        let target = dst.join(entry.path().strip_prefix(src)?);
+        if file_type.is_dir() {
+            fs::create_dir_all(&target).map_err(|err| SkillsError::io(&target, err))?;
+        } else {
+            fs::hard_link(entry.path(), &target).map_err(|err| SkillsError::io(&target, err))?;
+        }
+    }
+    Ok(())
+}
+
+fn find_skill_root(
+    extract_dir: &Path,
+    expected: &str,
+    preferred_subpath: Option<&Path>,
+) -> SkillsResult<PathBuf> {
+    if let Some(preferred) = preferred_subpath {
+        if let Some(found) = find_preferred_root(extract_dir, preferred)? {
+            return Ok(found);
+        }
+    }
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for entry in WalkDir::new(extract_dir).max_depth(5) {
+        let entry = entry?;
+        if entry.file_type().is_file() && entry.file_name() == "SKILL.md" {
+            if let Some(parent) = entry.path().parent() {
+                candidates.push(parent.to_path_buf());
+            }
+        }
+    }
+
+    if candidates.is_empty() {
+        return Ok(extract_dir.to_path_buf());
+    }
+
+    let expected_lower = expected.to_ascii_lowercase();
+    if let Some(best) = candidates.iter().find(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_ascii_lowercase() == expected_lower)
+            .unwrap_or(false)
+    }) {
+        return Ok(best.clone());
+    }
+
+    Ok(candidates[0].clone())
+}
+
+fn find_preferred_root(
+    extract_dir: &Path,
+    preferred_subpath: &Path,
+) -> SkillsResult<Option<PathBuf>> {
+    let direct = extract_dir.join(preferred_subpath);
+    if direct.exists() && direct.is_dir() {
+        return Ok(Some(direct));
+    }
+
+    let entries = fs::read_dir(extract_dir).map_err(|err| SkillsError::io(extract_dir, err))?;
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let candidate = path.join(preferred_subpath);
+        if candidate.exists() && candidate.is_dir() {
+            return Ok(Some(candidate));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        download_bytes_with_timeout, download_skill_to_dir, extract_tar_gz, find_skill_root,
+        parse_download_source, parse_hosted_source, pinned_source_url, remote_commit,
+        ArchiveFormat, DownloadSource, FetchContext, FetchedSource,
+    };
+    use crate::archive_cache::ArchiveCache;
+    use crate::error::SkillsError;
+    use crate::jobs::DownloadJob;
+    use crate::network::NetworkConfig;
+    use crate::utils::path::file_url;
+    use std::fs;
+    use std::io::{Cursor, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn parses_github_repo_url() {
+        let parsed = parse_download_source("https://github.com/owner/repo").unwrap();
+        assert_eq!(
+            parsed,
+            DownloadSource::GitHubRepo {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_github_tree_url() {
+        let parsed =
+            parse_download_source("https://github.com/anthropics/skills/tree/main/skills/docx")
+                .unwrap();
+        assert_eq!(
+            parsed,
+            DownloadSource::GitHubTree {
+                owner: "anthropics".to_string(),
+                repo: "skills".to_string(),
+                git_ref: "main".to_string(),
+                subpath: PathBuf::from("skills/docx"),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_zip_url() {
+        let parsed =
+            parse_download_source("https://example.com/files/skill-pack.zip?download=1").unwrap();
+        assert_eq!(
+            parsed,
+            DownloadSource::ZipUrl {
+                url: "https://example.com/files/skill-pack.zip?download=1".to_string(),
+                subpath: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_gitlab_gitea_and_bitbucket_urls() {
+        assert_eq!(
+            parse_download_source("https://gitlab.example.com/team/ai/skills/-/tree/v2/docx")
+                .unwrap(),
+            DownloadSource::GitLab {
+                base_url: "https://gitlab.example.com".to_string(),
+                project: "team/ai/skills".to_string(),
+                git_ref: Some("v2".to_string()),
+                subpath: Some(PathBuf::from("docx")),
+            }
+        );
+        assert_eq!(
+            parse_download_source("https://codeberg.org/owner/skills").unwrap(),
+            DownloadSource::Gitea {
+                base_url: "https://codeberg.org".to_string(),
+                owner: "owner".to_string(),
+                repo: "skills".to_string(),
+                git_ref: None,
+                subpath: None,
+            }
+        );
+        assert_eq!(
+            parse_download_source("https://git.corp.dev/owner/skills/src/branch/main/pdf").unwrap(),
+            DownloadSource::Gitea {
+                base_url: "https://git.corp.dev".to_string(),
+                owner: "owner".to_string(),
+                repo: "skills".to_string(),
+                git_ref: Some("main".to_string()),
+                subpath: Some(PathBuf::from("pdf")),
+            }
+        );
+        assert_eq!(
+            parse_download_source("https://bitbucket.org/owner/skills/src/main/skills/xlsx/")
+                .unwrap(),
+            DownloadSource::Bitbucket {
+                owner: "owner".to_string(),
+                repo: "skills".to_string(),
+                git_ref: Some("main".to_string()),
+                subpath: Some(PathBuf::from("skills/xlsx")),
+            }
+        );
+        // A `-` segment alone does not make a host GitLab.
+        assert!(
+            parse_hosted_source("https://files.example.com/-/skills/docx")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn parses_git_remotes_with_ref_and_subpath() {
+        assert_eq!(
+            parse_download_source("git@gitea.internal:team/skills.git#v1.2:skills/docx").unwrap(),
+            DownloadSource::Git {
+                url: "git@gitea.internal:team/skills.git".to_string(),
+                git_ref: Some("v1.2".to_string()),
+                subpath: Some(PathBuf::from("skills/docx")),
+            }
+        );
+        assert_eq!(
+            parse_download_source("git+https://example.com/skills#:docx").unwrap(),
+            DownloadSource::Git {
+                url: "https://example.com/skills".to_string(),
+                git_ref: None,
+                subpath: Some(PathBuf::from("docx")),
+            }
+        );
+        assert!(parse_download_source("ssh://git@example.com/skills.git#--upload-pack=x").is_err());
+        assert!(parse_download_source("git+https://example.com/skills#main:../etc").is_err());
+    }
+
+    #[test]
+    fn pins_commit_addressable_sources_to_a_commit() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let pinned = |url: &str| pinned_source_url(url, sha).unwrap().replace(sha, "<sha>");
+        assert_eq!(
+            pinned("https://github.com/anthropics/skills/tree/main/skills/docx"),
+            "https://github.com/anthropics/skills/tree/<sha>/skills/docx"
+        );
+        assert_eq!(
+            pinned("https://github.com/anthropics/skills"),
+            "https://github.com/anthropics/skills/tree/<sha>"
+        );
+        assert_eq!(
+            pinned("git+https://example.com/skills#main:docx"),
+            "git+https://example.com/skills#<sha>:docx"
+        );
+        assert_eq!(
+            pinned("https://gitlab.com/group/skills/-/tree/main/docx"),
+            "https://gitlab.com/group/skills/-/tree/main/docx#<sha>:docx"
+        );
+        assert_eq!(
+            pinned("https://codeberg.org/owner/skills"),
+            "https://codeberg.org/owner/skills#<sha>:"
+        );
+        assert_eq!(
+            pinned("https://bitbucket.org/owner/skills/src/main/docx"),
+            "https://bitbucket.org/owner/skills/src/main/docx#<sha>:docx"
+        );
+        assert_eq!(
+            parse_download_source(&pinned_source_url("https://github.com/o/r", sha).unwrap())
+                .unwrap(),
+            DownloadSource::GitHubTree {
+                owner: "o".to_string(),
+                repo: "r".to_string(),
+                git_ref: sha.to_string(),
+                subpath: PathBuf::new(),
+            }
+        );
+        assert_eq!(
+            parse_download_source(
+                &pinned_source_url("https://bitbucket.org/owner/skills/src/main/docx", sha)
+                    .unwrap()
+            )
+            .unwrap(),
+            DownloadSource::Bitbucket {
+                owner: "owner".to_string(),
+                repo: "skills".to_string(),
+                git_ref: Some(sha.to_string()),
+                subpath: Some(PathBuf::from("docx")),
+            }
+        );
+        assert!(matches!(
+            pinned_source_url("https://example.com/docx.zip", sha),
+            Err(SkillsError::UnsupportedSource { .. })
+        ));
+    }
+
+    #[test]
+    fn clones_git_sources_shallowly() {
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false)
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-git-{timestamp}"));
+        let remote = temp_root.join("remote");
+        fs::create_dir_all(remote.join("skills/docx")).unwrap();
+        fs::write(remote.join("skills/docx/SKILL.md"), "# docx").unwrap();
+        if !git(&remote, &["init", "--quiet", "--initial-branch=main"]) {
+            // git is not available on this machine.
+            let _ = fs::remove_dir_all(temp_root);
+            return;
+        }
+        assert!(git(&remote, &["add", "."]));
+        assert!(git(&remote, &["commit", "--quiet", "-m", "add docx"]));
+
+        let install_base = temp_root.join("installed");
+        let installed = download_skill_to_dir(
+            &format!("git+file://{}#main:skills/docx", remote.display()),
+            "docx",
+            &install_base,
+            &temp_root,
+            false,
+            &FetchContext::uncached(&DownloadJob::default()),
+        )
+        .unwrap();
+        assert_eq!(installed.dir, install_base.join("docx"));
+        assert_eq!(installed.subpath.as_deref(), Some("skills/docx"));
+        let commit = installed.origin.commit.clone().unwrap();
+        assert_eq!(commit.len(), 40);
+        assert!(commit.chars().all(|c| c.is_ascii_hexdigit()));
+        let remote_url = format!("git+file://{}#main", remote.display());
+        assert_eq!(
+            remote_commit(&remote_url, &NetworkConfig::default()).unwrap(),
+            Some(commit)
+        );
+        let installed = installed.finish(None).unwrap();
+        assert!(installed.join("SKILL.md").is_file());
+        assert!(!installed.join(".git").exists());
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    fn tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            // Written directly so tests can produce paths `set_path` would refuse.
+            header.as_gnu_mut().unwrap().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn installs_local_tarballs_through_the_pipeline() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-tgz-{timestamp}"));
+        fs::create_dir_all(&temp_root).unwrap();
+        let archive = temp_root.join("Team Skill.tgz");
+        fs::write(
+            &archive,
+            tar_gz(&[
+                ("pack/README.md", b"readme"),
+                ("pack/skills/docx/SKILL.md", b"# docx"),
+                ("pack/skills/docx/ref.md", b"ref"),
+            ]),
+        )
+        .unwrap();
+
+        let url = file_url(&archive);
+        assert_eq!(
+            parse_download_source(&url).unwrap(),
+            DownloadSource::LocalArchive {
+                path: archive.clone(),
+                format: ArchiveFormat::TarGz,
+                subpath: None,
+            }
+        );
+        let install_base = temp_root.join("installed");
+        let installed = download_skill_to_dir(
+            &url,
+            "docx",
+            &install_base,
+            &temp_root,
+            false,
+            &FetchContext::uncached(&DownloadJob::default()),
+        )
+        .unwrap()
+        .finish(None)
+        .unwrap();
+        assert!(installed.join("SKILL.md").is_file());
+        assert!(installed.join("ref.md").is_file());
+        assert!(!installed.join("README.md").exists());
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    #[test]
+    fn lists_skills_in_an_archive_and_pins_each_one() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-list-{timestamp}"));
+        fs::create_dir_all(&temp_root).unwrap();
+        let archive = temp_root.join("skills.tar.gz");
+        fs::write(
+            &archive,
+            tar_gz(&[
+                ("skills-main/skills/docx/SKILL.md", b"# docx"),
+                ("skills-main/skills/pdf/SKILL.md", b"# pdf"),
+            ]),
+        )
+        .unwrap();
+
+        let fetched = FetchedSource::open(
+            &file_url(&archive),
+            &FetchContext::uncached(&DownloadJob::default()),
+        )
+        .unwrap();
+        let subpaths = fetched.skill_subpaths().unwrap();
+        assert_eq!(
+            subpaths,
+            [PathBuf::from("skills/docx"), PathBuf::from("skills/pdf")]
+        );
+        let pinned = fetched.skill_source_url(&subpaths[1]);
+        assert_eq!(pinned, format!("{}#:skills/pdf", file_url(&archive)));
+        assert!(fetched.skill_dir("../outside").is_err());
+        drop(fetched);
+
+        // The pinned URL installs that skill even though another one sorts first.
+        let installed = download_skill_to_dir(
+            &pinned,
+            "other",
+            &temp_root.join("out"),
+            &temp_root,
+            false,
+            &FetchContext::uncached(&DownloadJob::default()),
+        )
+        .unwrap()
+        .finish(None)
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+            "# pdf"
+        );
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    #[test]
+    fn streams_downloads_with_progress() {
+        let body = vec![b'x'; 200 * 1024];
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/skill.zip", listener.local_addr().unwrap());
+        let served = body.clone();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                served.len()
+            )
+            .unwrap();
+            stream.write_all(&served).unwrap();
+        });
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let job = DownloadJob::new("zip").with_progress(move |progress| {
+            sink.lock()
+                .unwrap()
+                .push((progress.received, progress.total))
+        });
+        let downloaded =
+            download_bytes_with_timeout(&url, &[], 10, &job, &NetworkConfig::default()).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(downloaded, body);
+        let reports = reports.lock().unwrap();
+        let total = body.len() as u64;
+        assert_eq!(reports.last(), Some(&(total, Some(total))));
+        assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+
+    #[test]
+    fn retries_transient_failures_but_not_client_errors() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            for status in ["502 Bad Gateway", "200 OK", "404 Not Found"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+                )
+                .unwrap();
+            }
+        });
+
+        let net = NetworkConfig {
+            retries: 2,
+            ..NetworkConfig::default()
+        };
+        let job = DownloadJob::default();
+        let url = format!("{base}/skill.zip");
+        assert_eq!(
+            download_bytes_with_timeout(&url, &[], 10, &job, &net).unwrap(),
+            b"ok"
+        );
+        let missing =
+            download_bytes_with_timeout(&format!("{base}/missing.zip"), &[], 10, &job, &net);
+        assert!(matches!(
+            missing,
+            Err(SkillsError::NetworkError {
+                status: Some(404),
+                ..
+            })
+        ));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn does_not_retry_a_malformed_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/skill.zip", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            write!(stream, "HTTP/1.1 abc\r\nConnection: close\r\n\r\n").unwrap();
+            listener
+        });
+
+        let net = NetworkConfig {
+            retries: 2,
+            ..NetworkConfig::default()
+        };
+        let result = download_bytes_with_timeout(&url, &[], 2, &DownloadJob::default(), &net);
+        assert!(matches!(
+            result,
+            Err(SkillsError::NetworkError { status: None, .. })
+        ));
+        let listener = server.join().unwrap();
+        listener.set_nonblocking(true).unwrap();
+        assert!(listener.accept().is_err());
+    }
+
+    #[test]
+    fn pins_forge_archives_to_the_commit_their_branch_points_at() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-forge-{timestamp}"));
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("skills/docx/SKILL.md", SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(b"# docx").unwrap();
+        let archive = archive.finish().unwrap().into_inner();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/owner/skills/src/branch/main/docx",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for body in [format!("[{{\"sha\":\"{sha}\"}}]").into_bytes(), archive] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 2048];
+                let read = stream.read(&mut request).unwrap();
+                requests.push(
+                    String::from_utf8_lossy(&request[..read])
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+            requests
+        });
+
+        let install_base = temp_root.join("installed");
+        let installed = download_skill_to_dir(
+            &url,
+            "docx",
+            &install_base,
+            &temp_root,
+            false,
+            &FetchContext::uncached(&DownloadJob::default()),
+        )
+        .unwrap();
+        assert_eq!(installed.origin.commit.as_deref(), Some(sha));
+        assert!(installed.finish(None).unwrap().join("SKILL.md").is_file());
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests,
+            [
+                "GET /api/v1/repos/owner/skills/commits?limit=1&sha=main HTTP/1.1".to_string(),
+                format!("GET /owner/skills/archive/{sha}.zip HTTP/1.1"),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    #[test]
+    fn revalidates_cached_archives_instead_of_downloading_again() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-cached-{timestamp}"));
+        let archive = tar_gz(&[("docx/SKILL.md", b"# docx")]);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/docx.tgz", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in [Some(archive), None] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 2048];
+                let read = stream.read(&mut request).unwrap();
+                requests.push(String::from_utf8_lossy(&request[..read]).to_lowercase());
+                match response {
+                    Some(body) => {
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\
+                             Connection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .unwrap();
+                        stream.write_all(&body).unwrap();
+                    }
+                    None => stream
+                        .write_all(b"HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n")
+                        .unwrap(),
+                }
+            }
+            requests
+        });
+
+        let job = DownloadJob::default();
+        let ctx = FetchContext {
+            job: &job,
+            cache: Some(ArchiveCache::new(temp_root.join("cache"), 1024 * 1024)),
+            net: NetworkConfig::default(),
+        };
+        let install_base = temp_root.join("installed");
+        for overwrite in [false, true] {
+            let installed =
+                download_skill_to_dir(&url, "docx", &install_base, &temp_root, overwrite, &ctx)
+                    .unwrap();
+            assert!(installed.dir.join("SKILL.md").is_file());
+            assert_eq!(installed.origin.etag.as_deref(), Some("\"v1\""));
+            assert_eq!(
+                installed
+                    .origin
+                    .archive_sha256
+                    .as_ref()
+                    .map(|sha| sha.len()),
+                Some(64)
+            );
+            installed.finish(None).unwrap();
+        }
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    #[test]
+    fn cancelled_jobs_install_nothing_and_clean_up() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-cancel-{timestamp}"));
+        fs::create_dir_all(&temp_root).unwrap();
+        let archive = temp_root.join("docx.tgz");
+        fs::write(&archive, tar_gz(&[("docx/SKILL.md", b"# docx")])).unwrap();
+
+        let job = DownloadJob::new("docx");
+        job.cancel();
+        let install_base = temp_root.join("installed");
+        let error = download_skill_to_dir(
+            &file_url(&archive),
+            "docx",
+            &install_base,
+            &temp_root,
+            false,
+            &FetchContext::uncached(&job),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            SkillsError::Cancelled {
+                job_id: "docx".to_string()
+            }
+        );
+        assert!(!install_base.join("docx").exists());
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+
+    #[test]
+    fn parses_remote_tarball_urls() {
+        assert_eq!(
+            parse_download_source("https://example.com/skill.tar.gz?dl=1").unwrap(),
+            DownloadSource::TarGzUrl {
+                url: "https://example.com/skill.tar.gz?dl=1".to_string(),
+                subpath: None,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_tarball_entries_outside_the_extract_dir() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let extract_dir = std::env::temp_dir().join(format!("skills-manager-slip-{timestamp}"));
+        fs::create_dir_all(&extract_dir).unwrap();
+
+        let error = extract_tar_gz(&tar_gz(&[("../evil.md", b"x")]), &extract_dir).unwrap_err();
+        assert!(matches!(error, SkillsError::ZipSlip { .. }));
+        assert!(!extract_dir.parent().unwrap().join("evil.md").exists());
+
+        let _ = fs::remove_dir_all(extract_dir);
+    }
+
+    #[test]
+    fn rejects_unsupported_url() {
+        let error = parse_download_source("https://example.com/skill-page").unwrap_err();
+        assert!(matches!(error, SkillsError::UnsupportedSource { .. }));
+    }
+
+    #[test]
+    fn prioritizes_preferred_subpath() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_root = std::env::temp_dir().join(format!("skills-manager-test-{timestamp}"));
+        let extract_dir = temp_root.join("extract");
+        let repo_root = extract_dir.join("repo-main");
+        let preferred = repo_root.join("skills/docx");
+        let fallback = repo_root.join("skills/other-skill");
+
+        fs::create_dir_all(&preferred).unwrap();
+        fs::create_dir_all(&fallback).unwrap();
+        fs::write(preferred.join("SKILL.md"), "# docx").unwrap();
+        fs::write(fallback.join("SKILL.md"), "# other").unwrap();
+
+        let selected = find_skill_root(
+            &extract_dir,
+            "other-skill",
+            Some(PathBuf::from("skills/docx").as_path()),
+        )
+        .unwrap();
+        assert_eq!(selected, preferred);
+
+        let _ = fs::remove_dir_all(temp_root);
+    }
+}
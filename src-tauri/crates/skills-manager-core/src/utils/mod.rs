@@ -1,3 +1,5 @@
 pub mod download;
 pub mod path;
 pub mod security;
+pub mod staging;
+pub mod time;
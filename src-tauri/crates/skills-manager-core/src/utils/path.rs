@@ -1,3 +1,4 @@
+use crate::error::{SkillsError, SkillsResult};
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 
@@ -35,6 +36,7 @@ pub fn normalize_path(path: &Path) -> PathBuf {
 
 /// Windows reserved names that cannot be used as file/directory names
 #[cfg(target_os = "windows")]
+#[rustfmt::skip]
 const WINDOWS_RESERVED_NAMES: &[&str] = &[
     "CON", "PRN", "AUX", "NUL",
     "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
@@ -67,6 +69,7 @@ pub fn sanitize_dir_name(name: &str) -> String {
             out.push('-');
         }
     }
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
     let mut result = if out.is_empty() {
         "skill".to_string()
     } else {
@@ -82,7 +85,14 @@ pub fn sanitize_dir_name(name: &str) -> String {
     result
 }
 
-fn short_stable_hash(input: &str) -> String {
+/// Writes through a temporary file so a crash never leaves a truncated file behind.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> SkillsResult<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(|err| SkillsError::io(&tmp, err))?;
+    fs::rename(&tmp, path).map_err(|err| SkillsError::io(path, err))
+}
+
+pub fn short_stable_hash(input: &str) -> String {
     let mut hash: u64 = 0xcbf29ce484222325;
     for byte in input.as_bytes() {
         hash ^= u64::from(*byte);
@@ -114,3 +124,58 @@ pub fn resolve_canonical(path: &Path) -> Option<PathBuf> {
         .ok()
         .map(|canon| normalize_path(&canon))
 }
+
+/// `target` as seen from the directory `base`, e.g. `../../.skills-manager/skills/docx`. Both
+/// must be absolute and normalized; `None` when they share no root, such as two Windows drives.
+pub fn relative_path(base: &Path, target: &Path) -> Option<PathBuf> {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(&target)
+        .take_while(|(base, target)| base == target)
+        .count();
+    if common == 0 {
+        return None;
+    }
+    let mut relative: PathBuf = base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .collect();
+    relative.extend(&target[common..]);
+    Some(relative)
+}
+
+/// `file://` URL for a local path, with each segment percent-encoded.
+pub fn file_url(path: &Path) -> String {
+    let raw = path.to_string_lossy().replace('\\', "/");
+    let encoded = raw
+        .split('/')
+        .map(|segment| {
+            // Keep Windows drive letters (`C:`) readable.
+            if segment.len() == 2 && segment.ends_with(':') {
+                segment.to_string()
+            } else {
+                urlencoding::encode(segment).into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if encoded.starts_with('/') {
+        format!("file://{}", encoded)
+    } else {
+        format!("file:///{}", encoded)
+    }
+}
+
+/// Inverse of [`file_url`]; `None` for anything that is not a `file://` URL.
+pub fn path_from_file_url(url: &str) -> Option<PathBuf> {
+    let rest = url.strip_prefix("file://")?;
+    let decoded = urlencoding::decode(rest).ok()?;
+    let bytes = decoded.as_bytes();
+    // `file:///C:/skills` decodes to `/C:/skills` on Windows.
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[2] == b':' {
+        return Some(PathBuf::from(&decoded[1..]));
+    }
+    Some(PathBuf::from(decoded.as_ref()))
+}
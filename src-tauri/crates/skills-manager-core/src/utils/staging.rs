@@ -0,0 +1,217 @@
+//! Replacing an installed skill without a window in which it is missing or half-copied.
+//!
+//! The new version is copied next to the target and checked, the previous version is renamed
+//! aside, and the copy is renamed into place. The previous version is kept until the caller
+//! has finished writing everything else the install needs, and is put back if it does not.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::utils::download::copy_dir_recursive;
+use crate::versions::VersionHistory;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Prefix of the staging and backup directories created next to installed skills, which
+/// scans skip.
+pub(crate) const STAGING_PREFIX: &str = ".skills-manager-";
+
+/// A hidden directory next to `target_dir`, e.g. `.skills-manager-backup-docx-<nanos>`.
+pub(crate) fn sibling_dir(target_dir: &Path, kind: &str) -> SkillsResult<PathBuf> {
+    let name = target_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(SkillsError::internal)?
+        .as_nanos();
+    Ok(target_dir.with_file_name(format!("{STAGING_PREFIX}{kind}-{name}-{timestamp}")))
+}
+
+/// A skill swapped into `target_dir`. Until [`SkillSwap::finish`] is called the previous
+/// version is kept aside, and dropping the swap puts it back.
+#[derive(Debug)]
+pub(crate) struct SkillSwap {
+    target_dir: PathBuf,
+    backup_dir: Option<PathBuf>,
+    finished: bool,
+}
+
+impl SkillSwap {
+    /// Keeps the new version and moves the previous one into `history`, or removes it when
+    /// there is no history. When it cannot be archived, the previous version is left in its
+    /// backup directory and the error is returned; the new version stays in place either way.
+    pub(crate) fn finish(mut self, history: Option<&VersionHistory>) -> SkillsResult<()> {
+        self.finished = true;
+        let Some(backup_dir) = &self.backup_dir else {
+            return Ok(());
+        };
+        let Some(history) = history else {
+            // A leftover backup only costs disk space.
+            let _ = fs::remove_dir_all(backup_dir);
+            return Ok(());
+        };
+        let skill_name = self
+            .target_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        history.archive(&skill_name, backup_dir)
+    }
+}
+
+impl Drop for SkillSwap {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let _ = fs::remove_dir_all(&self.target_dir);
+        if let Some(backup_dir) = &self.backup_dir {
+            let _ = fs::rename(backup_dir, &self.target_dir);
+        }
+    }
+}
+
+/// Copies `skill_dir` into a staging directory next to `target_dir`, checks that the copy
+/// has a `SKILL.md`, and renames it over `target_dir`. Any failure leaves `target_dir` as
+/// it was.
+pub(crate) fn swap_in_skill_dir(skill_dir: &Path, target_dir: &Path) -> SkillsResult<SkillSwap> {
+    swap_in_dir_with(target_dir, |staging_dir| {
+        copy_dir_recursive(skill_dir, staging_dir)?;
+        if staging_dir.join("SKILL.md").is_file() {
+            Ok(())
+        } else {
+            Err(SkillsError::missing_skill_md(skill_dir))
+        }
+    })
+}
+
+/// Like [`swap_in_skill_dir`], with `fill` creating the new version in the staging
+/// directory it is given.
+pub(crate) fn swap_in_dir_with(
+    target_dir: &Path,
+    fill: impl FnOnce(&Path) -> SkillsResult<()>,
+) -> SkillsResult<SkillSwap> {
+    let staging_dir = sibling_dir(target_dir, "staging")?;
+    if let Err(err) = fill(&staging_dir) {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(err);
+    }
+
+    let backup_dir = match fs::symlink_metadata(target_dir) {
+        Ok(_) => {
+            let backup_dir = sibling_dir(target_dir, "backup")?;
+            if let Err(err) = fs::rename(target_dir, &backup_dir) {
+                let _ = fs::remove_dir_all(&staging_dir);
+                return Err(SkillsError::io(target_dir, err));
+            }
+            Some(backup_dir)
+        }
+        Err(_) => None,
+    };
+    if let Err(err) = fs::rename(&staging_dir, target_dir) {
+        let _ = fs::remove_dir_all(&staging_dir);
+        if let Some(backup_dir) = &backup_dir {
+            let _ = fs::rename(backup_dir, target_dir);
+        }
+        return Err(SkillsError::io(target_dir, err));
+    }
+    Ok(SkillSwap {
+        target_dir: target_dir.to_path_buf(),
+        backup_dir,
+        finished: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{swap_in_skill_dir, STAGING_PREFIX};
+    use crate::error::SkillsError;
+    use crate::versions::VersionHistory;
+    use std::fs;
+    use std::path::Path;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn restores_the_previous_version_unless_finished() {
+        let root =
+            std::env::temp_dir().join(format!("skills-manager-staging-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let installed = root.join("skills");
+        let target = installed.join("docx");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("SKILL.md"), "# v1").unwrap();
+        let v2 = root.join("v2");
+        fs::create_dir_all(&v2).unwrap();
+        fs::write(v2.join("SKILL.md"), "# v2").unwrap();
+
+        let swap = swap_in_skill_dir(&v2, &target).unwrap();
+        assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "# v2");
+        drop(swap);
+        assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "# v1");
+        assert_eq!(entries(&installed), ["docx"]);
+
+        let broken = root.join("broken");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join("README.md"), "no skill here").unwrap();
+        let error = swap_in_skill_dir(&broken, &target).unwrap_err();
+        assert!(matches!(error, SkillsError::MissingSkillMd { .. }));
+        assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "# v1");
+        assert_eq!(entries(&installed), ["docx"]);
+
+        swap_in_skill_dir(&v2, &target)
+            .unwrap()
+            .finish(None)
+            .unwrap();
+        assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "# v2");
+        assert!(entries(&installed)
+            .iter()
+            .all(|name| !name.starts_with(STAGING_PREFIX)));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn keeps_the_backup_when_it_cannot_be_archived() {
+        let root = std::env::temp_dir().join(format!(
+            "skills-manager-staging-archive-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let installed = root.join("skills");
+        let target = installed.join("docx");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("SKILL.md"), "# v1").unwrap();
+        let v2 = root.join("v2");
+        fs::create_dir_all(&v2).unwrap();
+        fs::write(v2.join("SKILL.md"), "# v2").unwrap();
+        // A file where the history directory should be makes archiving fail.
+        let versions = root.join("versions");
+        fs::write(&versions, "").unwrap();
+
+        let swap = swap_in_skill_dir(&v2, &target).unwrap();
+        assert!(swap
+            .finish(Some(&VersionHistory::new(versions, 3)))
+            .is_err());
+        assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "# v2");
+        let backups: Vec<_> = entries(&installed)
+            .into_iter()
+            .filter(|name| name.starts_with(STAGING_PREFIX))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(
+            fs::read_to_string(installed.join(&backups[0]).join("SKILL.md")).unwrap(),
+            "# v1"
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::manifest::{split_frontmatter, SkillDocument, SkillManifest};
+use crate::skills::read_skill_metadata;
+use crate::types::{DiagnosticSeverity, SkillDiagnostic, SkillValidationReport};
+use crate::utils::path::{normalize_path, resolve_canonical, sanitize_skill_dir_name};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const MAX_NAME_LEN: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 1024;
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const SKILL_MD: &str = "SKILL.md";
+
+struct Diagnostics(Vec<SkillDiagnostic>);
+
+impl Diagnostics {
+    fn push(
+        &mut self,
+        severity: DiagnosticSeverity,
+        code: &str,
+        file: &str,
+        line: Option<usize>,
+        message: impl Into<String>,
+    ) {
+        self.0.push(SkillDiagnostic {
+            severity,
+            code: code.to_string(),
+            message: message.into(),
+            file: file.to_string(),
+            line,
+        });
+    }
+
+    fn error(&mut self, code: &str, file: &str, line: Option<usize>, message: impl Into<String>) {
+        self.push(DiagnosticSeverity::Error, code, file, line, message);
+    }
+
+    fn warning(&mut self, code: &str, file: &str, line: Option<usize>, message: impl Into<String>) {
+        self.push(DiagnosticSeverity::Warning, code, file, line, message);
+    }
+}
+
+impl SkillsManager {
+    /// Lints a skill directory without modifying it.
+    ///
+    /// Problems with the skill itself are reported as diagnostics; `Err` is only returned
+    /// when `skill_path` is not a readable directory.
+    pub fn validate_skill(&self, skill_path: &str) -> SkillsResult<SkillValidationReport> {
+        let raw = PathBuf::from(skill_path);
+        let skill_dir = resolve_canonical(&raw).ok_or_else(|| SkillsError::not_found(&raw))?;
+        if !skill_dir.is_dir() {
+            return Err(SkillsError::invalid_request(format!(
+                "Not a skill directory: {}",
+                skill_dir.display()
+            )));
+        }
+
+        let metadata = read_skill_metadata(&skill_dir);
+        let mut diagnostics = Diagnostics(Vec::new());
+
+        let skill_md = skill_dir.join(SKILL_MD);
+        match fs::read_to_string(&skill_md) {
+            Ok(content) => check_frontmatter(&skill_dir, &content, &mut diagnostics),
+            Err(err) if skill_md.exists() => {
+                diagnostics.error("unreadable-file", SKILL_MD, None, err.to_string())
+            }
+            Err(_) => diagnostics.error(
+                "missing-skill-md",
+                SKILL_MD,
+                None,
+                "The skill directory does not contain SKILL.md",
+            ),
+        }
+        check_files(&skill_dir, &mut diagnostics);
+
+        let diagnostics = diagnostics.0;
+        Ok(SkillValidationReport {
+            skill_path: skill_dir.display().to_string(),
+            name: metadata.name,
+            valid: diagnostics
+                .iter()
+                .all(|item| item.severity != DiagnosticSeverity::Error),
+            diagnostics,
+        })
+    }
+}
+
+fn check_frontmatter(skill_dir: &Path, content: &str, diagnostics: &mut Diagnostics) {
+    let document = split_frontmatter(content);
+    let Some(frontmatter) = document.frontmatter else {
+        diagnostics.error(
+            "missing-frontmatter",
+            SKILL_MD,
+            Some(1),
+            "SKILL.md must start with a `---` YAML frontmatter block",
+        );
+        return;
+    };
+
+    let manifest: SkillManifest = if frontmatter.trim().is_empty() {
+        SkillManifest::default()
+    } else {
+        match serde_yaml::from_str(frontmatter) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                let line = err
+                    .location()
+                    .map(|location| document.frontmatter_line + location.line() - 1);
+                diagnostics.error(
+                    "invalid-frontmatter",
+                    SKILL_MD,
+                    line,
+                    format!("Frontmatter is not valid YAML: {}", err),
+                );
+                return;
+            }
+        }
+    };
+
+    let name_line = key_line(&document, "name");
+    match manifest.name.as_deref().map(str::trim) {
+        None | Some("") => diagnostics.error(
+            "missing-name",
+            SKILL_MD,
+            Some(document.frontmatter_line),
+            "Frontmatter is missing `name`",
+        ),
+        Some(name) => {
+            if name.chars().count() > MAX_NAME_LEN {
+                diagnostics.error(
+                    "name-too-long",
+                    SKILL_MD,
+                    name_line,
+                    format!("`name` is longer than {} characters", MAX_NAME_LEN),
+                );
+            }
+            let expected = sanitize_skill_dir_name(name, name);
+            if expected != name {
+                diagnostics.warning(
+                    "name-not-normalized",
+                    SKILL_MD,
+                    name_line,
+                    format!(
+                        "`name` should be lowercase letters, digits and hyphens; it installs as `{}`",
+                        expected
+                    ),
+                );
+            }
+            let dir_name = skill_dir
+                .file_name()
+                .and_then(|value| value.to_str())
+                .unwrap_or_default();
+            if dir_name != expected {
+                diagnostics.warning(
+                    "name-dir-mismatch",
+                    SKILL_MD,
+                    name_line,
+                    format!(
+                        "`name` installs as `{}` but the directory is named `{}`",
+                        expected, dir_name
+                    ),
+                );
+            }
+        }
+    }
+
+    let description_line = key_line(&document, "description");
+    match manifest.description.as_deref().map(str::trim) {
+        None | Some("") => diagnostics.error(
+            "missing-description",
+            SKILL_MD,
+            Some(document.frontmatter_line),
+            "Frontmatter is missing `description`; agents use it to decide when to load the skill",
+        ),
+        Some(description) if description.chars().count() > MAX_DESCRIPTION_LEN => diagnostics
+            .error(
+                "description-too-long",
+                SKILL_MD,
+                description_line,
+                format!(
+                    "`description` is longer than {} characters",
+                    MAX_DESCRIPTION_LEN
+                ),
+            ),
+        Some(_) => {}
+    }
+}
+
+/// Line of a top-level frontmatter key, for pointing diagnostics at it.
+fn key_line(document: &SkillDocument<'_>, key: &str) -> Option<usize> {
+    let prefix = format!("{}:", key);
+    document
+        .frontmatter?
+        .lines()
+        .position(|line| line.starts_with(&prefix))
+        .map(|index| document.frontmatter_line + index)
+}
+
+fn check_files(skill_dir: &Path, diagnostics: &mut Diagnostics) {
+    let walker = WalkDir::new(skill_dir)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                let file = err
+                    .path()
+                    .map(|path| relative_display(skill_dir, path))
+                    .unwrap_or_default();
+                diagnostics.error("unreadable-file", &file, None, err.to_string());
+                continue;
+            }
+        };
+        let path = entry.path();
+        let file = relative_display(skill_dir, path);
+
+        if entry.path_is_symlink() {
+            diagnostics.error(
+                "symlink-not-allowed",
+                &file,
+                None,
+                "Symlinks are rejected when the skill is installed",
+            );
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.len() > MAX_FILE_BYTES {
+                diagnostics.warning(
+                    "file-too-large",
+                    &file,
+                    None,
+                    format!(
+                        "File is {} KiB; keep skill files under {} KiB",
+                        metadata.len() / 1024,
+                        MAX_FILE_BYTES / 1024
+                    ),
+                );
+            }
+        }
+
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        if is_markdown {
+            if let Ok(content) = fs::read_to_string(path) {
+                check_links(skill_dir, path, &file, &content, diagnostics);
+            }
+        }
+    }
+}
+
+fn check_links(
+    skill_dir: &Path,
+    markdown_path: &Path,
+    file: &str,
+    content: &str,
+    diagnostics: &mut Diagnostics,
+) {
+    let base = markdown_path.parent().unwrap_or(skill_dir);
+    let mut in_code_block = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for target in link_targets(line) {
+            let Some(relative) = local_link_path(target) else {
+                continue;
+            };
+            let resolved = normalize_path(&base.join(relative));
+            if !resolved.starts_with(skill_dir) {
+                diagnostics.error(
+                    "link-outside-skill",
+                    file,
+                    Some(index + 1),
+                    format!("Link `{}` points outside the skill directory", target),
+                );
+            } else if !resolved.exists() {
+                diagnostics.error(
+                    "broken-link",
+                    file,
+                    Some(index + 1),
+                    format!("Link `{}` does not resolve to a file in the skill", target),
+                );
+            }
+        }
+    }
+}
+
+/// Targets of inline markdown links and images (`[text](target)`) on one line.
+fn link_targets(line: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        let raw = after[..end].trim();
+        let raw = match raw.strip_prefix('<') {
+            Some(inner) => inner.split('>').next().unwrap_or_default(),
+            // Drop an optional title: `(path "Title")`.
+            None => raw.split_whitespace().next().unwrap_or_default(),
+        };
+        if !raw.is_empty() {
+            targets.push(raw);
+        }
+        rest = &after[end + 1..];
+    }
+    targets
+}
+
+/// Strips anchors and queries from a link, returning `None` for URLs and in-page anchors.
+fn local_link_path(target: &str) -> Option<&str> {
+    if target.starts_with('#') || target.starts_with('/') || target.contains("://") {
+        return None;
+    }
+    if let Some((scheme, _)) = target.split_once(':') {
+        if scheme
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.')
+        {
+            return None;
+        }
+    }
+    let path = target.split(['#', '?']).next().unwrap_or_default();
+    (!path.is_empty()).then_some(path)
+}
+
+fn relative_display(skill_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(skill_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{link_targets, local_link_path};
+    use crate::types::DiagnosticSeverity;
+    use crate::SkillsManager;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "skills-manager-validate-{}-{}",
+            label,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extracts_local_link_targets() {
+        let line = "See [ref](docs/ref.md#usage), ![img](<assets/a b.png>) and [web](https://x.dev \"t\").";
+        let targets = link_targets(line);
+        assert_eq!(
+            targets,
+            vec!["docs/ref.md#usage", "assets/a b.png", "https://x.dev"]
+        );
+        assert_eq!(local_link_path(targets[0]), Some("docs/ref.md"));
+        assert_eq!(local_link_path("mailto:a@b.c"), None);
+        assert_eq!(local_link_path("#section"), None);
+    }
+
+    #[test]
+    fn reports_frontmatter_and_link_problems_with_locations() {
+        let root = temp_dir("lint");
+        let skill_dir = root.join("pdf-tools");
+        fs::create_dir_all(skill_dir.join("docs")).unwrap();
+        fs::write(skill_dir.join("docs/ref.md"), "reference").unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: PDF Tools\n---\n# PDF\n\n[ok](docs/ref.md)\n[missing](docs/nope.md)\n[escape](../other/SKILL.md)\n",
+        )
+        .unwrap();
+
+        let report = SkillsManager::new(&root)
+            .validate_skill(&skill_dir.display().to_string())
+            .unwrap();
+        let found: Vec<(&str, Option<usize>)> = report
+            .diagnostics
+            .iter()
+            .map(|item| (item.code.as_str(), item.line))
+            .collect();
+
+        assert!(!report.valid);
+        assert!(found.contains(&("missing-description", Some(2))));
+        assert!(found.contains(&("name-not-normalized", Some(2))));
+        assert!(found.contains(&("broken-link", Some(7))));
+        assert!(found.contains(&("link-outside-skill", Some(8))));
+        assert!(!found.iter().any(|(code, _)| *code == "name-dir-mismatch"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn accepts_a_well_formed_skill() {
+        let root = temp_dir("clean");
+        let skill_dir = root.join("pdf-tools");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: Extract text from PDF files.\n---\n# PDF\n",
+        )
+        .unwrap();
+
+        let report = SkillsManager::new(&root)
+            .validate_skill(&skill_dir.display().to_string())
+            .unwrap();
+        assert!(report.valid);
+        assert!(report
+            .diagnostics
+            .iter()
+            .all(|item| item.severity != DiagnosticSeverity::Error));
+        assert_eq!(report.name, "pdf-tools");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
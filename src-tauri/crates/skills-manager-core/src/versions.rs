@@ -0,0 +1,302 @@
+//! Previous versions of managed skills, kept in `~/.skills-manager/versions/<skill>/<id>`
+//! when an update replaces them so they can be rolled back to.
+
+use crate::error::{SkillsError, SkillsResult};
+use crate::manager::SkillsManager;
+use crate::provenance::read_provenance;
+use crate::skills::validate_manager_skill_path;
+use crate::types::{RollbackSkillRequest, SkillVersion};
+use crate::utils::download::copy_dir_recursive;
+use crate::utils::staging::{sibling_dir, swap_in_skill_dir};
+use crate::utils::time::{format_utc, now_utc};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// The version history of every managed skill, trimmed to `keep` versions per skill.
+pub(crate) struct VersionHistory {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl VersionHistory {
+    pub(crate) fn new(dir: PathBuf, keep: usize) -> Self {
+        VersionHistory { dir, keep }
+    }
+
+    /// Moves `previous`, the version of `skill_name` an update replaced, into the history
+    /// and drops the oldest versions beyond `keep`. Versions are named after the commit they
+    /// were installed from, so reinstalling a commit replaces its entry; the others are named
+    /// after when they were installed.
+    pub(crate) fn archive(&self, skill_name: &str, previous: &Path) -> SkillsResult<()> {
+        let provenance = read_provenance(previous);
+        let skill_history = self.dir.join(skill_name);
+        fs::create_dir_all(&skill_history).map_err(|err| SkillsError::io(&skill_history, err))?;
+
+        let dest = match provenance.as_ref().and_then(|p| p.commit.clone()) {
+            Some(commit) => {
+                let dest = skill_history.join(commit);
+                if dest.exists() {
+                    fs::remove_dir_all(&dest).map_err(|err| SkillsError::io(&dest, err))?;
+                }
+                dest
+            }
+            None => {
+                let installed_at = provenance
+                    .and_then(|p| p.installed_at)
+                    .unwrap_or_else(now_utc)
+                    .replace(['-', ':'], "");
+                let mut dest = skill_history.join(&installed_at);
+                let mut n = 2;
+                while dest.exists() {
+                    dest = skill_history.join(format!("{installed_at}-{n}"));
+                    n += 1;
+                }
+                dest
+            }
+        };
+        if fs::rename(previous, &dest).is_err() {
+            // The history may be on another filesystem than the skills.
+            copy_dir_recursive(previous, &dest)?;
+            fs::remove_dir_all(previous).map_err(|err| SkillsError::io(previous, err))?;
+        }
+
+        for stale in versions_in(&skill_history)?.into_iter().skip(self.keep) {
+            let stale = Path::new(&stale.path);
+            fs::remove_dir_all(stale).map_err(|err| SkillsError::io(stale, err))?;
+        }
+        Ok(())
+    }
+}
+
+/// The versions in `skill_history`, newest install first. Versions without a recorded
+/// install time are dated by when their directory was last modified.
+fn versions_in(skill_history: &Path) -> SkillsResult<Vec<SkillVersion>> {
+    let entries = match fs::read_dir(skill_history) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(SkillsError::io(skill_history, err)),
+    };
+    let mut versions = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let provenance = read_provenance(&path);
+        let installed_at = provenance
+            .as_ref()
+            .and_then(|p| p.installed_at.clone())
+            .or_else(|| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+                let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+                Some(format_utc(secs))
+            });
+        let version = SkillVersion {
+            id: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            provenance,
+            path: path.display().to_string(),
+        };
+        versions.push((installed_at, version));
+    }
+    versions.sort_by(|(a_at, a), (b_at, b)| b_at.cmp(a_at).then_with(|| b.id.cmp(&a.id)));
+    Ok(versions.into_iter().map(|(_, version)| version).collect())
+}
+
+impl SkillsManager {
+    fn versions_dir(&self) -> PathBuf {
+        self.data_dir().join("versions")
+    }
+
+    /// Where updates keep the versions they replace, or `None` when the settings turn the
+    /// history off.
+    pub(crate) fn version_history(&self) -> SkillsResult<Option<VersionHistory>> {
+        let keep = self.load_settings()?.skill_versions_kept as usize;
+        Ok((keep > 0).then(|| VersionHistory::new(self.versions_dir(), keep)))
+    }
+
+    /// The managed skill at `skill_path` and the name its history is kept under.
+    fn managed_skill(&self, skill_path: &str) -> SkillsResult<(PathBuf, String)> {
+        let manager_root = self.canonical_manager_root();
+        let skill_dir = validate_manager_skill_path(Path::new(skill_path), &manager_root)?;
+        if skill_dir.parent() != Some(manager_root.as_path()) {
+            return Err(SkillsError::outside_allowed_root(&skill_dir));
+        }
+        let name = skill_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok((skill_dir, name))
+    }
+
+    /// Previous versions of the managed skill at `skill_path`, newest first.
+    pub fn list_skill_versions(&self, skill_path: &str) -> SkillsResult<Vec<SkillVersion>> {
+        let (_, name) = self.managed_skill(skill_path)?;
+        versions_in(&self.versions_dir().join(name))
+    }
+
+    /// Puts a previous version back in place of the managed skill, which joins the history
+    /// in its stead. The skill stays at the same path, so symlinks to it see the restored
+    /// version, and its copies and hard-link trees in the IDE directories are refreshed.
+    pub fn rollback_skill(&self, request: RollbackSkillRequest) -> SkillsResult<String> {
+        let (skill_dir, name) = self.managed_skill(&request.skill_path)?;
+        let mut components = Path::new(request.version.trim()).components();
+        let version = match (components.next(), components.next()) {
+            (Some(Component::Normal(version)), None) => version.to_owned(),
+            _ => {
+                return Err(SkillsError::invalid_request(format!(
+                    "Invalid version: {}",
+                    request.version
+                )))
+            }
+        };
+        let version_dir = self.versions_dir().join(&name).join(version);
+        if !version_dir.is_dir() {
+            return Err(SkillsError::not_found(&version_dir));
+        }
+
+        let history = self.version_history()?;
+        let swap = swap_in_skill_dir(&version_dir, &skill_dir)?;
+        // The restored version leaves the history by a rename, which either happens or
+        // leaves both versions where they were, and before the replaced one is archived so
+        // that trimming does not count it.
+        let restored = sibling_dir(&self.versions_dir().join(&name), "restored")?;
+        fs::rename(&version_dir, &restored).map_err(|err| SkillsError::io(&version_dir, err))?;
+        let archived = swap.finish(history.as_ref());
+        let _ = fs::remove_dir_all(&restored);
+        archived?;
+        self.refresh_managed_copies(&skill_dir);
+        Ok(format!(
+            "Rolled back {} to {}",
+            name,
+            request.version.trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{versions_in, VersionHistory};
+    use crate::jobs::DownloadJob;
+    use crate::provenance::write_provenance;
+    use crate::types::{DownloadRequest, RollbackSkillRequest, SkillProvenance};
+    use crate::utils::path::file_url;
+    use crate::SkillsManager;
+    use std::fs;
+
+    #[test]
+    fn trims_the_oldest_version_even_without_provenance() {
+        let root = std::env::temp_dir().join(format!(
+            "skills-manager-versions-trim-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let skill_history = root.join("versions/docx");
+        let oldest = skill_history.join("20010101T000000Z");
+        fs::create_dir_all(&oldest).unwrap();
+        let provenance = SkillProvenance {
+            installed_at: Some("2001-01-01T00:00:00Z".to_string()),
+            ..SkillProvenance::default()
+        };
+        write_provenance(&oldest, &provenance).unwrap();
+
+        let history = VersionHistory::new(root.join("versions"), 2);
+        for version in ["v1", "v2"] {
+            let previous = root.join(version);
+            fs::create_dir_all(&previous).unwrap();
+            fs::write(previous.join("SKILL.md"), format!("# {version}")).unwrap();
+            history.archive("docx", &previous).unwrap();
+        }
+
+        let versions = versions_in(&skill_history).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(!oldest.exists());
+        assert!(versions.iter().all(|version| version.provenance.is_none()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn keeps_replaced_versions_and_rolls_back_in_place() {
+        let home =
+            std::env::temp_dir().join(format!("skills-manager-versions-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let source = home.join("source/docx");
+        fs::create_dir_all(&source).unwrap();
+        let manager = SkillsManager::new(&home);
+        let mut settings = manager.load_settings().unwrap();
+        settings.skill_versions_kept = 2;
+        manager.save_settings(&settings).unwrap();
+        let request = || DownloadRequest {
+            source_url: file_url(&source),
+            skill_name: "docx".to_string(),
+            install_base_dir: manager.manager_root().display().to_string(),
+            job_id: None,
+        };
+        let job = DownloadJob::default();
+        for version in ["v1", "v2", "v3", "v4"] {
+            fs::write(source.join("SKILL.md"), format!("# {version}")).unwrap();
+            manager.update_marketplace_skill(request(), &job).unwrap();
+        }
+        let live = manager.manager_root().join("docx");
+        let live_path = live.display().to_string();
+        #[cfg(unix)]
+        let link = {
+            let link = home.join("ide-skills/docx");
+            fs::create_dir_all(link.parent().unwrap()).unwrap();
+            std::os::unix::fs::symlink(&live, &link).unwrap();
+            link
+        };
+
+        let versions = manager.list_skill_versions(&live_path).unwrap();
+        let contents: Vec<_> = versions
+            .iter()
+            .map(|version| fs::read_to_string(format!("{}/SKILL.md", version.path)).unwrap())
+            .collect();
+        assert_eq!(contents, ["# v3", "# v2"]);
+
+        let message = manager
+            .rollback_skill(RollbackSkillRequest {
+                skill_path: live_path.clone(),
+                version: versions[1].id.clone(),
+            })
+            .unwrap();
+        assert!(message.contains("docx"));
+        assert_eq!(fs::read_to_string(live.join("SKILL.md")).unwrap(), "# v2");
+        #[cfg(unix)]
+        assert_eq!(fs::read_to_string(link.join("SKILL.md")).unwrap(), "# v2");
+        let versions = manager.list_skill_versions(&live_path).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions.iter().all(|version| fs::read_to_string(format!(
+            "{}/SKILL.md",
+            version.path
+        ))
+        .unwrap()
+            != "# v2"));
+
+        // Rolling back to the newest version keeps as many as before.
+        manager
+            .rollback_skill(RollbackSkillRequest {
+                skill_path: live_path.clone(),
+                version: versions[0].id.clone(),
+            })
+            .unwrap();
+        assert_eq!(manager.list_skill_versions(&live_path).unwrap().len(), 2);
+        assert!(fs::read_dir(manager.versions_dir())
+            .unwrap()
+            .all(|entry| entry.unwrap().file_name() == "docx"));
+
+        let error = manager
+            .rollback_skill(RollbackSkillRequest {
+                skill_path: live_path,
+                version: "../../skills/docx".to_string(),
+            })
+            .unwrap_err();
+        assert!(matches!(error, crate::SkillsError::InvalidRequest { .. }));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
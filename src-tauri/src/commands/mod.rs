@@ -1,2 +1,10 @@
 pub mod market;
+pub mod settings;
 pub mod skills;
+
+use skills_manager_core::{SkillsManager, SkillsResult};
+
+fn manager() -> SkillsResult<SkillsManager> {
+    SkillsManager::from_home_dir()
+        .map(|manager| manager.with_app_version(env!("CARGO_PKG_VERSION")))
+}
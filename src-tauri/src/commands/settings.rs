@@ -0,0 +1,33 @@
+use super::manager;
+use skills_manager_core::settings::{Settings, SettingsView};
+use skills_manager_core::types::ArchiveCacheStats;
+use skills_manager_core::SkillsResult;
+
+#[tauri::command]
+pub fn get_settings() -> SkillsResult<SettingsView> {
+    Ok(manager()?.load_settings()?.into())
+}
+
+/// Saves everything but the GitHub token, which the webview never sees; it is changed
+/// through `set_github_token` only.
+#[tauri::command]
+pub fn save_settings(mut settings: Settings) -> SkillsResult<()> {
+    let manager = manager()?;
+    settings.github_token = manager.load_settings()?.github_token;
+    manager.save_settings(&settings)
+}
+
+#[tauri::command]
+pub fn set_github_token(token: Option<String>) -> SkillsResult<()> {
+    manager()?.set_github_token(token)
+}
+
+#[tauri::command]
+pub fn cache_stats() -> SkillsResult<ArchiveCacheStats> {
+    manager()?.cache_stats()
+}
+
+#[tauri::command]
+pub fn clear_cache() -> SkillsResult<ArchiveCacheStats> {
+    manager()?.clear_cache()
+}
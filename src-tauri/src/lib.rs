@@ -1,21 +1,32 @@
 mod commands;
-mod types;
-mod utils;
 
-use commands::market::{download_marketplace_skill, search_marketplaces, update_marketplace_skill};
+use commands::market::{
+    cancel_download, check_skill_updates, download_marketplace_skill, install_skills_from_source,
+    list_skills_in_source, refresh_market_index, search_marketplaces, update_marketplace_skill,
+};
+use commands::settings::{cache_stats, clear_cache, get_settings, save_settings, set_github_token};
 use commands::skills::{
     adopt_ide_skill, delete_local_skills, export_local_skills, import_local_skill,
-    link_local_skill, read_local_skill_preview, scan_overview, scan_project_ide_dirs,
-    uninstall_skill,
+    link_local_skill, list_skill_versions, read_local_skill_preview, rollback_skill, scan_overview,
+    scan_project_ide_dirs, sync_project, uninstall_skill, validate_skill,
 };
+use skills_manager_core::jobs::DownloadJobs;
 use tauri::Manager;
 
-pub use crate::types::{
-    AdoptIdeSkillRequest, DeleteLocalSkillRequest, ExportSkillsRequest, IdeDir, IdeSkill,
-    ImportRequest, InstallResult, LinkRequest, LinkTarget, LocalScanRequest, LocalSkill,
-    LocalSkillPreview, Overview, ProjectIdeDir, ProjectScanRequest, ProjectScanResult,
-    RemoteSkillView, RemoteSkillsViewResponse, UninstallRequest,
+pub use skills_manager_core::settings::{
+    MarketSourceConfig, MarketSourceKind, Settings, SettingsView,
+};
+pub use skills_manager_core::types::{
+    AdoptIdeSkillRequest, ArchiveCacheStats, DeleteLocalSkillRequest, DownloadProgress,
+    ExportSkillsRequest, IdeDir, IdeSkill, ImportRequest, InstallResult, LinkRequest, LinkStrategy,
+    LinkTarget, LocalScanRequest, LocalSkill, LocalSkillPreview, MarketFacets, MarketIndexRefresh,
+    MarketSearchFilters, MarketSort, Overview, ProjectIdeDir, ProjectScanRequest,
+    ProjectScanResult, RemoteSkillView, RemoteSkillsViewResponse, RollbackSkillRequest,
+    SkillChanges, SkillDiagnostic, SkillProvenance, SkillUpdateStatus, SkillValidationReport,
+    SkillVersion, SourceInstallRequest, SourceSkill, SyncDrift, SyncDriftKind, SyncProjectRequest,
+    SyncReport, UninstallRequest,
 };
+pub use skills_manager_core::SkillsManager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -24,19 +35,34 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(DownloadJobs::default())
         .invoke_handler(tauri::generate_handler![
             search_marketplaces,
+            refresh_market_index,
             download_marketplace_skill,
             update_marketplace_skill,
+            check_skill_updates,
+            cancel_download,
+            list_skills_in_source,
+            install_skills_from_source,
             link_local_skill,
             read_local_skill_preview,
+            validate_skill,
             scan_overview,
             uninstall_skill,
             import_local_skill,
             delete_local_skills,
             export_local_skills,
             adopt_ide_skill,
-            scan_project_ide_dirs
+            list_skill_versions,
+            rollback_skill,
+            scan_project_ide_dirs,
+            sync_project,
+            get_settings,
+            save_settings,
+            set_github_token,
+            cache_stats,
+            clear_cache
         ]);
 
     #[cfg(desktop)]
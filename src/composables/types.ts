@@ -13,6 +13,117 @@ export type RemoteSkill = {
   stars: number;
   marketId: string;
   marketLabel: string;
+  score?: number;
+  installed?: boolean;
+};
+
+export type MarketSort = "relevance" | "name" | "recent" | "stars" | "installs";
+
+/**
+ * Optional filters and sort order for search_marketplaces
+ */
+export type MarketSearchFilters = {
+  category?: string | null;
+  author?: string | null;
+  marketId?: string | null;
+  installed?: boolean | null;
+  sort: MarketSort;
+};
+
+export type FacetCount = {
+  value: string;
+  label: string;
+  count: number;
+};
+
+/**
+ * Result counts per category, author and marketplace for the current search
+ */
+export type MarketFacets = {
+  categories: FacetCount[];
+  authors: FacetCount[];
+  markets: FacetCount[];
+};
+
+/**
+ * Skill found inside a repository or archive by list_skills_in_source
+ */
+export type SourceSkill = {
+  subpath: string;
+  name: string;
+  description: string;
+  sourceUrl: string;
+};
+
+/**
+ * Marketplace configured in settings.json
+ */
+export type MarketSourceConfig = {
+  id: string;
+  label: string;
+  enabled: boolean;
+} & (
+  | { kind: "skillsIndex" }
+  | { kind: "indexUrl"; url: string }
+  | { kind: "localDir"; path: string }
+  | { kind: "github"; org?: string | null; topic?: string | null }
+);
+
+/**
+ * Backend settings stored in ~/.skills-manager/settings.json
+ */
+export type Settings = {
+  marketIndexUrls: string[];
+  marketSources: MarketSourceConfig[];
+  archiveCacheMaxMb: number;
+  proxyUrl?: string | null;
+  noProxy?: string[];
+  caBundlePath?: string | null;
+  offline: boolean;
+  downloadRetries: number;
+  githubMirrors?: string[];
+  skillVersionsKept: number;
+};
+
+/**
+ * Settings as get_settings returns them: the GitHub token stays in the backend
+ */
+export type SettingsView = Settings & {
+  hasGithubToken: boolean;
+};
+
+/**
+ * Size of the downloaded archive cache
+ */
+export type ArchiveCacheStats = {
+  path: string;
+  entries: number;
+  totalBytes: number;
+  maxBytes: number;
+};
+
+/**
+ * Marketplace that failed during a search
+ */
+export type MarketSourceError = {
+  marketId: string;
+  marketLabel: string;
+  error: unknown;
+};
+
+/**
+ * Result of refresh_market_index
+ */
+export type MarketIndexRefresh = {
+  updatedAt?: string | null;
+  total: number;
+  added: number;
+  removed: number;
+  sources: {
+    url: string;
+    state: "updated" | "notModified" | "failed";
+    error?: unknown;
+  }[];
 };
 
 /**
@@ -24,6 +135,19 @@ export type InstallResult = {
   skipped: string[];
 };
 
+/**
+ * Parsed SKILL.md frontmatter
+ */
+export type SkillManifest = {
+  name?: string;
+  description?: string;
+  license?: string;
+  compatibility?: string;
+  allowedTools: string[];
+  metadata: Record<string, unknown>;
+  [key: string]: unknown;
+};
+
 /**
  * Local skill managed by skills-manager
  */
@@ -31,13 +155,92 @@ export type LocalSkill = {
   id: string;
   name: string;
   description: string;
+  manifest?: SkillManifest | null;
   path: string;
   source: string;
   sourceUrl?: string;
+  provenance?: SkillProvenance | null;
   ide?: string;
   usedBy: string[];
 };
 
+/**
+ * What was installed for a skill from a source, read from its .skills-manager.json
+ */
+export type SkillProvenance = {
+  sourceUrl: string;
+  /** What a catalog sourceUrl resolved to and was downloaded from */
+  resolvedUrl?: string | null;
+  commit?: string | null;
+  subpath?: string | null;
+  installedAt?: string | null;
+  archiveSha256?: string | null;
+  etag?: string | null;
+  lastModified?: string | null;
+  appVersion?: string | null;
+};
+
+/**
+ * A previous version of a managed skill kept for rollback
+ */
+export type SkillVersion = {
+  id: string;
+  path: string;
+  provenance?: SkillProvenance | null;
+};
+
+/**
+ * Files an update would add, change or remove, relative to the skill directory
+ */
+export type SkillChanges = {
+  added: string[];
+  modified: string[];
+  removed: string[];
+};
+
+/**
+ * Result of check_skill_updates for one managed skill installed from a source
+ */
+export type SyncDriftKind =
+  | "notInstalled"
+  | "sourceChanged"
+  | "commitChanged"
+  | "notVendored"
+  | "notLinked"
+  | "linkedElsewhere"
+  | "staleCopy"
+  | "conflict"
+  | "undeclared";
+
+export type SyncDrift = {
+  skill: string;
+  kind: SyncDriftKind;
+  detail: string;
+};
+
+/**
+ * What syncing a project against its skills.toml installed, linked and removed
+ */
+export type SyncReport = {
+  projectDir: string;
+  lockPath: string;
+  installed: string[];
+  linked: string[];
+  removed: string[];
+  drift: SyncDrift[];
+};
+
+export type SkillUpdateStatus = {
+  name: string;
+  path: string;
+  sourceUrl: string;
+  installedCommit?: string | null;
+  latestCommit?: string | null;
+  updateAvailable: boolean;
+  changes: SkillChanges;
+  error?: unknown;
+};
+
 export type LocalSkillPreview = {
   skillMdPath: string;
   skillMdContent: string;
@@ -49,10 +252,32 @@ export type LocalSkillPreview = {
 export type IdeSkill = {
   id: string;
   name: string;
+  description: string;
+  manifest?: SkillManifest | null;
   path: string;
   ide: string;
   source: string;
   managed: boolean;
+  /** A managed copy or hard-link tree that no longer matches the managed skill */
+  stale: boolean;
+};
+
+/**
+ * Single finding reported by validate_skill
+ */
+export type SkillDiagnostic = {
+  severity: "error" | "warning";
+  code: string;
+  message: string;
+  file: string;
+  line?: number | null;
+};
+
+export type SkillValidationReport = {
+  skillPath: string;
+  name: string;
+  valid: boolean;
+  diagnostics: SkillDiagnostic[];
 };
 
 /**
@@ -75,9 +300,15 @@ export type IdeOption = {
 /**
  * Link target for skill installation
  */
+/**
+ * How a managed skill is placed in an IDE directory; omitted means the platform default
+ */
+export type LinkStrategy = "symlink" | "relative-symlink" | "copy" | "hardlink-tree";
+
 export type LinkTarget = {
   name: string;
   path: string;
+  strategy?: LinkStrategy;
 };
 
 /**
@@ -90,6 +321,18 @@ export type DownloadTask = {
   action: "download" | "update";
   status: "pending" | "downloading" | "done" | "error";
   error?: string;
+  // Bytes received so far and the expected size, when the server reports it
+  received?: number;
+  total?: number | null;
+};
+
+/**
+ * Progress event emitted while a download job streams in
+ */
+export type DownloadProgress = {
+  jobId: string;
+  received: number;
+  total: number | null;
 };
 
 /**
@@ -0,0 +1,32 @@
+import { ref } from "vue";
+import { invoke } from "@tauri-apps/api/core";
+import type { ArchiveCacheStats } from "./types";
+
+// Shared so the numbers survive switching tabs
+const stats = ref<ArchiveCacheStats | null>(null);
+const clearing = ref(false);
+
+export function useArchiveCache() {
+  const loadStats = async () => {
+    stats.value = (await invoke("cache_stats")) as ArchiveCacheStats;
+  };
+
+  // Returns what was removed
+  const clearCache = async () => {
+    clearing.value = true;
+    try {
+      const removed = (await invoke("clear_cache")) as ArchiveCacheStats;
+      await loadStats();
+      return removed;
+    } finally {
+      clearing.value = false;
+    }
+  };
+
+  return {
+    stats,
+    clearing,
+    loadStats,
+    clearCache
+  };
+}
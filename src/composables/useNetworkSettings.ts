@@ -0,0 +1,80 @@
+import { ref } from "vue";
+import { invoke } from "@tauri-apps/api/core";
+import type { Settings, SettingsView } from "./types";
+
+export type NetworkSettings = Pick<
+  Settings,
+  "proxyUrl" | "noProxy" | "caBundlePath" | "offline" | "downloadRetries" | "githubMirrors"
+>;
+
+// Shared so the form keeps its values when switching tabs
+const hasToken = ref(false);
+const network = ref<NetworkSettings>({
+  proxyUrl: null,
+  noProxy: [],
+  caBundlePath: null,
+  offline: false,
+  downloadRetries: 2,
+  githubMirrors: []
+});
+const saving = ref(false);
+
+export function useNetworkSettings() {
+  const loadNetwork = async () => {
+    const settings = (await invoke("get_settings")) as SettingsView;
+    hasToken.value = settings.hasGithubToken;
+    network.value = {
+      proxyUrl: settings.proxyUrl ?? null,
+      noProxy: settings.noProxy ?? [],
+      caBundlePath: settings.caBundlePath ?? null,
+      offline: settings.offline ?? false,
+      downloadRetries: settings.downloadRetries ?? 2,
+      githubMirrors: settings.githubMirrors ?? []
+    };
+  };
+
+  // Merges into the stored settings so fields edited elsewhere are kept
+  const update = async (changes: Partial<Settings>) => {
+    saving.value = true;
+    try {
+      const { hasGithubToken: _, ...settings } = (await invoke("get_settings")) as SettingsView;
+      await invoke("save_settings", { settings: { ...settings, ...changes } });
+    } finally {
+      saving.value = false;
+    }
+  };
+
+  // An empty token removes the stored one
+  const saveToken = async (token: string) => {
+    const githubToken = token.trim() || null;
+    saving.value = true;
+    try {
+      await invoke("set_github_token", { token: githubToken });
+    } finally {
+      saving.value = false;
+    }
+    hasToken.value = Boolean(githubToken);
+  };
+
+  const saveNetwork = async (changes: NetworkSettings) => {
+    const normalized: NetworkSettings = {
+      proxyUrl: changes.proxyUrl?.trim() || null,
+      noProxy: (changes.noProxy ?? []).map((host) => host.trim()).filter(Boolean),
+      caBundlePath: changes.caBundlePath?.trim() || null,
+      offline: changes.offline,
+      downloadRetries: Math.max(0, Math.floor(changes.downloadRetries || 0)),
+      githubMirrors: (changes.githubMirrors ?? []).map((mirror) => mirror.trim()).filter(Boolean)
+    };
+    await update(normalized);
+    network.value = normalized;
+  };
+
+  return {
+    hasToken,
+    network,
+    saving,
+    loadNetwork,
+    saveToken,
+    saveNetwork
+  };
+}
@@ -1,17 +1,21 @@
 import { computed, onMounted, onUnmounted, ref } from "vue";
 import { useI18n } from "vue-i18n";
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { dirname, homeDir, join } from "@tauri-apps/api/path";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
 import { useToast } from "./useToast";
 import type {
   RemoteSkill, InstallResult, LocalSkill,
-  IdeSkill, Overview, LinkTarget, DownloadTask, ProjectConfig
+  IdeSkill, Overview, LinkTarget, DownloadTask, ProjectConfig, MarketIndexRefresh,
+  MarketSourceError, MarketSearchFilters, MarketFacets, SourceSkill, DownloadProgress,
+  SkillUpdateStatus, LinkStrategy
 } from "./types";
 import { buildProjectLinkTargets } from "./projectTargets";
 import { useIdeConfig } from "./useIdeConfig";
 import {
   isSafeRelativePath,
+  getErrorCode,
   getErrorMessage,
   isSafeAbsolutePath,
   parseManualSkillSource,
@@ -19,18 +23,37 @@ import {
 } from "./utils";
 
 export function useSkillsManager() {
-  const { t } = useI18n();
+  const { t, te } = useI18n();
   const toast = useToast();
+
+  // Prefer a localized message for structured backend errors, then the backend text.
+  function describeError(err: unknown, fallback: string): string {
+    const code = getErrorCode(err);
+    const resetAt = (err as { resetAt?: unknown } | null)?.resetAt;
+    if (code === "RateLimited" && typeof resetAt === "string") {
+      return t("backendErrors.RateLimitedUntil", { resetAt: new Date(resetAt).toLocaleString() });
+    }
+    const key = `backendErrors.${code}`;
+    if (code && te(key)) {
+      return t(key, err as Record<string, unknown>);
+    }
+    return getErrorMessage(err, fallback);
+  }
   const cacheTtlMs = 10 * 60 * 1000;
   const searchCache = new Map<
     string,
-    { timestamp: number; data: { skills: RemoteSkill[]; total: number; limit: number; offset: number } }
+    {
+      timestamp: number;
+      data: { skills: RemoteSkill[]; total: number; limit: number; offset: number; facets: MarketFacets };
+    }
   >();
   const activeTab = ref<"local" | "market" | "ide" | "projects" | "settings">("local");
 
   const query = ref("");
   const results = ref<RemoteSkill[]>([]);
   const total = ref(0);
+  const marketFilters = ref<MarketSearchFilters>({ sort: "relevance" });
+  const marketFacets = ref<MarketFacets | null>(null);
   const limit = ref(20);
   const offset = ref(0);
   const loading = ref(false);
@@ -41,6 +64,9 @@ export function useSkillsManager() {
   const localSkills = ref<LocalSkill[]>([]);
   const ideSkills = ref<IdeSkill[]>([]);
   const localLoading = ref(false);
+  // Update checks by skill path
+  const skillUpdates = ref<Record<string, SkillUpdateStatus>>({});
+  const checkingUpdates = ref(false);
 
   // Download Queue
   const downloadQueue = ref<DownloadTask[]>([]);
@@ -49,9 +75,22 @@ export function useSkillsManager() {
   // Timer tracking for cleanup
   const timers: number[] = [];
 
+  // Progress events for queued downloads, matched to tasks by job id
+  let unlistenProgress: UnlistenFn | null = null;
+  void listen<DownloadProgress>("download-progress", (event) => {
+    const task = downloadQueue.value.find(t => t.id === event.payload.jobId);
+    if (task && task.status === "downloading") {
+      task.received = event.payload.received;
+      task.total = event.payload.total;
+    }
+  }).then((unlisten) => {
+    unlistenProgress = unlisten;
+  });
+
   // Cleanup on unmount
   onUnmounted(() => {
     timers.forEach((id) => clearTimeout(id));
+    unlistenProgress?.();
   });
 
   const showInstallModal = ref(false);
@@ -125,6 +164,10 @@ export function useSkillsManager() {
     return `skills-export-${timestamp}.zip`;
   }
 
+  function withStrategy(targets: LinkTarget[], strategy: LinkStrategy | null): LinkTarget[] {
+    return strategy ? targets.map((target) => ({ ...target, strategy })) : targets;
+  }
+
   async function buildLinkTargets(targetLabel: string): Promise<LinkTarget[]> {
     const target = ideOptions.value.find((option) => option.label === targetLabel);
     if (!target) return [];
@@ -153,13 +196,14 @@ export function useSkillsManager() {
     loading.value = true;
 
     const nextOffset = reset ? 0 : offset.value + limit.value;
-    const cacheKey = `${query.value.trim().toLowerCase()}|${limit.value}`;
+    const cacheKey = `${query.value.trim().toLowerCase()}|${limit.value}|${JSON.stringify(marketFilters.value)}`;
 
     if (reset && !force) {
       const cached = searchCache.get(cacheKey);
       if (cached && Date.now() - cached.timestamp < cacheTtlMs) {
         results.value = cached.data.skills;
         total.value = cached.data.total;
+        marketFacets.value = cached.data.facets;
         offset.value = cached.data.offset;
         loading.value = false;
         return;
@@ -170,20 +214,34 @@ export function useSkillsManager() {
       const response = await invoke("search_marketplaces", {
         query: query.value,
         limit: limit.value,
-        offset: nextOffset
+        offset: nextOffset,
+        filters: marketFilters.value
       });
       const data = response as {
         skills: RemoteSkill[];
         total: number;
         limit: number;
         offset: number;
+        sourceErrors: MarketSourceError[];
+        facets: MarketFacets;
       };
+      if (reset) {
+        for (const failed of data.sourceErrors ?? []) {
+          toast.error(
+            t("errors.marketSourceFailed", {
+              market: failed.marketLabel,
+              message: describeError(failed.error, t("errors.searchFailed"))
+            })
+          );
+        }
+      }
 
       const deduped = dedupeSkills(reset ? data.skills : [...results.value, ...data.skills]);
       results.value = deduped;
 
       total.value = data.total;
       offset.value = data.offset;
+      marketFacets.value = data.facets;
 
       if (reset) {
         searchCache.set(cacheKey, {
@@ -192,12 +250,36 @@ export function useSkillsManager() {
         });
       }
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.searchFailed")));
+      toast.error(describeError(err, t("errors.searchFailed")));
     } finally {
       loading.value = false;
     }
   }
 
+  // Fetch the latest skills index; `silent` is used for the background refresh on startup.
+  async function refreshMarketIndex(silent = false) {
+    try {
+      const result = (await invoke("refresh_market_index")) as MarketIndexRefresh;
+      if (!silent) {
+        toast.success(
+          t("messages.indexRefreshed", {
+            date: result.updatedAt ?? "-",
+            added: result.added,
+            removed: result.removed
+          })
+        );
+      }
+      if (!silent || result.added > 0 || result.removed > 0) {
+        searchCache.clear();
+        await searchMarketplace(true, true);
+      }
+    } catch (err) {
+      if (!silent) {
+        toast.error(describeError(err, t("errors.indexRefreshFailed")));
+      }
+    }
+  }
+
   function dedupeSkills(skills: RemoteSkill[]) {
     const map = new Map<string, RemoteSkill>();
     for (const skill of skills) {
@@ -245,10 +327,16 @@ export function useSkillsManager() {
           request: {
             sourceUrl: task.sourceUrl,
             skillName: task.name,
-            installBaseDir
+            installBaseDir,
+            jobId: task.id
           }
         });
         task.status = 'done';
+        if (task.id.startsWith("local:")) {
+          const nextUpdates = { ...skillUpdates.value };
+          delete nextUpdates[task.id.slice("local:".length)];
+          skillUpdates.value = nextUpdates;
+        }
         recentTaskStatus.value = {
           ...recentTaskStatus.value,
           [task.id]: task.action
@@ -271,14 +359,32 @@ export function useSkillsManager() {
         }, 2500);
         timers.push(timerId);
       } catch (err) {
+        if (getErrorCode(err) === "Cancelled") {
+          downloadQueue.value = downloadQueue.value.filter(t => t.id !== task.id);
+          continue;
+        }
         task.status = 'error';
-        task.error = err instanceof Error ? err.message : String(err);
+        task.error = describeError(err, t("errors.downloadFailed"));
       }
     }
 
     isProcessingQueue = false;
   }
 
+  async function cancelDownload(taskId: string) {
+    const task = downloadQueue.value.find(t => t.id === taskId);
+    if (!task) return;
+    if (task.status !== "downloading") {
+      removeFromQueue(taskId);
+      return;
+    }
+    // The queue drops the task once the backend reports the cancellation.
+    const cancelled = (await invoke("cancel_download", { jobId: taskId })) as boolean;
+    if (!cancelled) {
+      toast.error(t("download.cancelFailed"));
+    }
+  }
+
   function removeFromQueue(taskId: string) {
     downloadQueue.value = downloadQueue.value.filter(t => t.id !== taskId);
   }
@@ -288,6 +394,8 @@ export function useSkillsManager() {
     if (task && task.status === 'error') {
       task.status = 'pending';
       task.error = undefined;
+      task.received = undefined;
+      task.total = undefined;
       processQueue();
     }
   }
@@ -334,6 +442,57 @@ export function useSkillsManager() {
     }
   }
 
+  // Compares managed skills with their sources; `silent` skips the toasts for the startup check.
+  async function checkSkillUpdates(silent = false) {
+    if (checkingUpdates.value) return;
+    checkingUpdates.value = true;
+    try {
+      const statuses = (await invoke("check_skill_updates")) as SkillUpdateStatus[];
+      skillUpdates.value = Object.fromEntries(statuses.map((status) => [status.path, status]));
+      if (!silent) {
+        const available = statuses.filter((status) => status.updateAvailable).length;
+        toast.success(
+          available > 0 ? t("messages.updatesAvailable", { count: available }) : t("messages.upToDate")
+        );
+      }
+    } catch (err) {
+      if (!silent) {
+        toast.error(describeError(err, t("errors.updateCheckFailed")));
+      }
+    } finally {
+      checkingUpdates.value = false;
+    }
+  }
+
+  // Lists every skill in a repository or archive so the user can pick which to install.
+  async function listSkillsInSource(sourceUrl: string) {
+    try {
+      return (await invoke("list_skills_in_source", { sourceUrl })) as SourceSkill[];
+    } catch (err) {
+      toast.error(describeError(err, t("errors.listSourceFailed")));
+      return null;
+    }
+  }
+
+  async function installSkillsFromSource(sourceUrl: string, subpaths: string[]) {
+    if (subpaths.length === 0) return;
+    busy.value = true;
+    busyText.value = t("messages.installing");
+    try {
+      const installBaseDir = await buildInstallBaseDir();
+      const results = (await invoke("install_skills_from_source", {
+        request: { sourceUrl, subpaths, installBaseDir }
+      })) as { installedPath: string }[];
+      toast.success(t("messages.installedFromSource", { count: results.length }));
+      await scanLocalSkills();
+    } catch (err) {
+      toast.error(describeError(err, t("errors.downloadFailed")));
+    } finally {
+      busy.value = false;
+      busyText.value = "";
+    }
+  }
+
   async function addManualSkill(sourceUrl: string, customName?: string) {
     const parsed = parseManualSkillSource(sourceUrl);
     if (!parsed) {
@@ -354,7 +513,7 @@ export function useSkillsManager() {
       sourceUrl: parsed.normalizedUrl,
       description: t("market.manualDescription"),
       descriptionZh: "",
-      author: parsed.kind === "zip" ? t("market.manualSourceLabel") : "",
+      author: parsed.kind === "archive" ? t("market.manualSourceLabel") : "",
       installs: 0,
       stars: 0,
       marketId: "manual",
@@ -387,14 +546,20 @@ export function useSkillsManager() {
       localSkills.value = response.managerSkills;
       ideSkills.value = response.ideSkills;
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.scanFailed")));
+      toast.error(describeError(err, t("errors.scanFailed")));
     } finally {
       localLoading.value = false;
     }
   }
 
-  async function linkSkillInternal(skill: LocalSkill, ideLabel: string, skipScan = false, suppressToast = false) {
-    const linkTargets = await buildLinkTargets(ideLabel);
+  async function linkSkillInternal(
+    skill: LocalSkill,
+    ideLabel: string,
+    skipScan = false,
+    suppressToast = false,
+    strategy: LinkStrategy | null = null
+  ) {
+    const linkTargets = withStrategy(await buildLinkTargets(ideLabel), strategy);
     if (linkTargets.length === 0) {
       throw new Error(t("errors.selectValidIde"));
     }
@@ -431,7 +596,13 @@ export function useSkillsManager() {
     saveLastInstallTargets(next);
   }
 
-  async function confirmInstallToIde(installTarget: "ide" | "project", targetIds: string[], projects?: ProjectConfig[]) {
+  async function confirmInstallToIde(
+    installTarget: "ide" | "project",
+    targetIds: string[],
+    projects?: ProjectConfig[],
+    strategy: LinkStrategy | null = null,
+    vendor = false
+  ) {
     if (installTarget === "project") {
       // Project installation
       if (!projects || projects.length === 0) {
@@ -461,7 +632,15 @@ export function useSkillsManager() {
         for (const skill of installTargetSkills.value) {
           for (const project of selectedProjects) {
             for (const ideLabel of project.ideTargets) {
-              const result = await linkSkillToProjectInternal(skill, project, ideLabel, true, true);
+              const result = await linkSkillToProjectInternal(
+                skill,
+                project,
+                ideLabel,
+                true,
+                true,
+                strategy,
+                vendor
+              );
               totalLinked += result.linked.length;
               totalSkipped += result.skipped.length;
             }
@@ -473,7 +652,7 @@ export function useSkillsManager() {
         showInstallModal.value = false;
         installTargetSkills.value = [];
       } catch (err) {
-        toast.error(getErrorMessage(err, t("errors.installFailed")));
+        toast.error(describeError(err, t("errors.installFailed")));
       } finally {
         installingId.value = null;
         busy.value = false;
@@ -499,7 +678,7 @@ export function useSkillsManager() {
       // Install to global IDE directories
       for (const skill of installTargetSkills.value) {
         for (const label of targetIds) {
-          const result = await linkSkillInternal(skill, label, true, true);
+          const result = await linkSkillInternal(skill, label, true, true, strategy);
           totalLinked += result.linked.length;
           totalSkipped += result.skipped.length;
         }
@@ -510,7 +689,7 @@ export function useSkillsManager() {
       showInstallModal.value = false;
       installTargetSkills.value = [];
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.installFailed")));
+      toast.error(describeError(err, t("errors.installFailed")));
     } finally {
       installingId.value = null;
       busy.value = false;
@@ -523,9 +702,11 @@ export function useSkillsManager() {
     project: ProjectConfig,
     ideLabel: string,
     skipScan = false,
-    suppressToast = false
+    suppressToast = false,
+    strategy: LinkStrategy | null = null,
+    vendor = false
   ) {
-    const linkTargets = buildProjectLinkTargets(project, ideLabel);
+    const linkTargets = withStrategy(buildProjectLinkTargets(project, ideLabel), strategy);
     if (linkTargets.length === 0) {
       throw new Error(`${t("errors.selectValidIde")} (${project.name}: ${ideLabel})`);
     }
@@ -534,7 +715,8 @@ export function useSkillsManager() {
         skillPath: skill.path,
         skillName: skill.name,
         linkTargets,
-        projectDir: project.path
+        projectDir: project.path,
+        vendor
       }
     })) as InstallResult;
 
@@ -623,7 +805,7 @@ export function useSkillsManager() {
       await scanLocalSkills();
     } catch (err) {
       toast.error(
-        getErrorMessage(
+        describeError(
           err,
           uninstallMode.value === "local" ? t("errors.deleteFailed") : t("errors.uninstallFailed")
         )
@@ -645,14 +827,19 @@ export function useSkillsManager() {
     uninstallTargetPaths.value = [];
   }
 
-  async function importLocalSkill() {
+  // Archives are unpacked by the backend download pipeline, directories are copied as-is.
+  async function importLocalSkill(source: "directory" | "archive" = "directory") {
     try {
       const { open } = await import("@tauri-apps/plugin-dialog");
-      const selected = await open({
-        directory: true,
-        multiple: true,
-        title: t("local.selectSkillDir")
-      });
+      const selected = await open(
+        source === "directory"
+          ? { directory: true, multiple: true, title: t("local.selectSkillDir") }
+          : {
+              multiple: true,
+              title: t("local.selectSkillArchive"),
+              filters: [{ name: "Skill Archive", extensions: ["zip", "tar.gz", "tgz"] }]
+            }
+      );
 
       if (!selected) return;
 
@@ -676,7 +863,7 @@ export function useSkillsManager() {
           successCount++;
         } catch (err) {
           failCount++;
-          lastError = err instanceof Error ? err.message : String(err);
+          lastError = describeError(err, t("errors.importFailed"));
         }
       }
 
@@ -691,7 +878,7 @@ export function useSkillsManager() {
 
       await scanLocalSkills();
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.importFailed")));
+      toast.error(describeError(err, t("errors.importFailed")));
     } finally {
       busy.value = false;
       busyText.value = "";
@@ -728,7 +915,7 @@ export function useSkillsManager() {
 
       toast.success(t("messages.exported", { path: result }));
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.exportFailed")));
+      toast.error(describeError(err, t("errors.exportFailed")));
     } finally {
       busy.value = false;
       busyText.value = "";
@@ -739,7 +926,7 @@ export function useSkillsManager() {
     try {
       await revealItemInDir(path);
     } catch (err) {
-      const message = getErrorMessage(err, t("errors.openDirFailed"));
+      const message = describeError(err, t("errors.openDirFailed"));
       if (message.includes("os error 2") || message.toLowerCase().includes("cannot find the file")) {
         try {
           await revealItemInDir(await dirname(path));
@@ -766,7 +953,7 @@ export function useSkillsManager() {
       toast.success(message);
       await scanLocalSkills();
     } catch (err) {
-      toast.error(getErrorMessage(err, t("errors.adoptFailed")));
+      toast.error(describeError(err, t("errors.adoptFailed")));
     } finally {
       busy.value = false;
       busyText.value = "";
@@ -809,8 +996,8 @@ export function useSkillsManager() {
 
   onMounted(() => {
     refreshIdeOptions();
-    void searchMarketplace(true);
-    void scanLocalSkills();
+    void searchMarketplace(true).then(() => refreshMarketIndex(true));
+    void scanLocalSkills().then(() => checkSkillUpdates(true));
   });
 
   return {
@@ -827,6 +1014,8 @@ export function useSkillsManager() {
     localSkills,
     ideSkills,
     localLoading,
+    skillUpdates,
+    checkingUpdates,
     ideOptions,
     selectedIdeFilter,
     customIdeName,
@@ -840,6 +1029,8 @@ export function useSkillsManager() {
     hasMore,
     sortedResults,
     localSkillNameSet,
+    marketFilters,
+    marketFacets,
     filteredIdeSkills,
     customIdeOptions,
     downloadQueue,
@@ -851,10 +1042,12 @@ export function useSkillsManager() {
     addCustomIde,
     removeCustomIde,
     searchMarketplace,
+    refreshMarketIndex,
     downloadSkill,
     updateSkill,
     updateLocalSkill,
     updateLocalSkills,
+    checkSkillUpdates,
     addManualSkill,
     scanLocalSkills,
     openInstallModal,
@@ -867,12 +1060,15 @@ export function useSkillsManager() {
     confirmUninstall,
     cancelUninstall,
     importLocalSkill,
+    listSkillsInSource,
+    installSkillsFromSource,
     exportLocalSkills,
     openSkillDirectory,
     adoptIdeSkill,
     adoptManyIdeSkills,
     addToDownloadQueue,
     removeFromQueue,
-    retryDownload
+    retryDownload,
+    cancelDownload
   };
 }
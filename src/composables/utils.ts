@@ -112,6 +112,32 @@ export function getErrorMessage(err: unknown, fallback: string): string {
   return fallback;
 }
 
+/**
+ * Extracts the stable error code from a structured backend error
+ */
+export function getErrorCode(err: unknown): string | undefined {
+  if (err && typeof err === "object") {
+    const code = (err as { code?: unknown }).code;
+    if (typeof code === "string" && code) return code;
+  }
+  return undefined;
+}
+
+/**
+ * Formats a byte count for display, e.g. 1.5 MB
+ */
+export function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  const units = ["KB", "MB", "GB"];
+  let value = bytes / 1024;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit += 1;
+  }
+  return `${value.toFixed(1)} ${units[unit]}`;
+}
+
 /**
  * Normalizes a skill name for stable matching across sources and local paths
  */
@@ -125,7 +151,7 @@ export function normalizeSkillName(input: string): string {
     .replace(/^-+|-+$/g, "");
 }
 
-export type ManualSkillSourceKind = "github_repo" | "github_tree" | "zip";
+export type ManualSkillSourceKind = "github_repo" | "github_tree" | "archive" | "catalog" | "git";
 
 export type ManualSkillSource = {
   kind: ManualSkillSourceKind;
@@ -137,7 +163,7 @@ function normalizeManualSkillName(name: string): string {
   return decodeURIComponent(name)
     .trim()
     .replace(/\.git$/i, "")
-    .replace(/\.zip$/i, "");
+    .replace(/\.(zip|tar\.gz|tgz)$/i, "");
 }
 
 function sanitizeUrl(url: string): URL | null {
@@ -148,7 +174,41 @@ function sanitizeUrl(url: string): URL | null {
   }
 }
 
+// Generic git remotes, optionally followed by `#<ref>:<subpath>`; the backend clones them.
+const gitRemotePattern = /^(git\+[a-z]+:\/\/|ssh:\/\/|git:\/\/|[\w.-]+@[\w.-]+:)/i;
+
+// GitLab, Gitea/Forgejo and Bitbucket web URLs the backend downloads as archives.
+function isHostedGitUrl(url: URL, segments: string[]): boolean {
+  const host = url.hostname.toLowerCase();
+  return (
+    host === "gitlab.com" ||
+    host === "bitbucket.org" ||
+    host === "codeberg.org" ||
+    /^(gitlab|gitea|forgejo)\./.test(host) ||
+    segments.some(
+      (segment, i) => segment === "-" && ["tree", "blob", "archive"].includes(segments[i + 1]),
+    ) ||
+    (segments[2] === "src" && ["branch", "tag", "commit"].includes(segments[3]))
+  );
+}
+
+function parseGitSkillSource(input: string): ManualSkillSource | null {
+  const trimmed = input.trim();
+  const [remote, fragment = ""] = trimmed.split("#", 2);
+  const isHttpGit = /^https?:\/\//i.test(remote) && /\.git$/i.test(remote);
+  if (!gitRemotePattern.test(remote) && !isHttpGit) return null;
+
+  const subpath = fragment.includes(":") ? fragment.slice(fragment.indexOf(":") + 1) : "";
+  const nameSource = subpath.split("/").filter(Boolean).pop() ?? remote.split(/[/:]/).filter(Boolean).pop() ?? "";
+  const inferredName = normalizeManualSkillName(nameSource);
+  if (!inferredName) return null;
+  return { kind: "git", normalizedUrl: trimmed, inferredName };
+}
+
 export function parseManualSkillSource(input: string): ManualSkillSource | null {
+  const git = parseGitSkillSource(input);
+  if (git) return git;
+
   const url = sanitizeUrl(input);
   if (!url || !/^https?:$/.test(url.protocol)) return null;
 
@@ -189,12 +249,38 @@ export function parseManualSkillSource(input: string): ManualSkillSource | null
     };
   }
 
+  // Catalog pages are resolved to their GitHub directory by the backend.
+  if (url.hostname === "officialskills.sh" && segments.length >= 3) {
+    const skillName = normalizeManualSkillName(segments[segments.length - 1]);
+    if (!skillName) return null;
+    return {
+      kind: "catalog",
+      normalizedUrl: `${url.origin}/${segments.join("/")}`,
+      inferredName: skillName
+    };
+  }
+
+  if (url.hostname !== "github.com" && segments.length >= 2 && isHostedGitUrl(url, segments)) {
+    const treeStart = segments.indexOf("-");
+    const projectSegments = treeStart >= 0 ? segments.slice(0, treeStart) : segments.slice(0, 2);
+    // Segments before the subdirectory: `-/tree/<ref>` on GitLab, `src/<kind>/<ref>` on Gitea, `src/<ref>` on Bitbucket.
+    const treeLength = treeStart >= 0 ? 3 : segments[2] === "src" ? (url.hostname === "bitbucket.org" ? 2 : 3) : 0;
+    const subpath = treeLength > 0 ? segments.slice(projectSegments.length + treeLength) : [];
+    const name = normalizeManualSkillName(subpath.pop() ?? projectSegments[projectSegments.length - 1]);
+    if (!name) return null;
+    return {
+      kind: "git",
+      normalizedUrl: `${url.origin}/${segments.join("/")}`,
+      inferredName: name
+    };
+  }
+
   const lowerPath = rawPath.toLowerCase();
-  if (lowerPath.endsWith(".zip")) {
+  if (/\.(zip|tar\.gz|tgz)$/.test(lowerPath)) {
     const fileName = normalizeManualSkillName(segments[segments.length - 1] ?? "");
     if (!fileName) return null;
     return {
-      kind: "zip",
+      kind: "archive",
       normalizedUrl: url.toString(),
       inferredName: fileName
     };
@@ -49,6 +49,37 @@ export default {
       light: "Light",
       dark: "Dark",
       system: "System"
+    },
+    cache: {
+      title: "Download Cache",
+      usage: "{count} archives, {size} of {max}",
+      clear: "Clear Cache",
+      cleared: "Freed {size}",
+      clearFailed: "Failed to clear the cache",
+      hint: "Downloaded repository archives are kept so installing more skills from the same source or updating an unchanged skill does not download it again."
+    },
+    network: {
+      title: "Network",
+      proxy: "Proxy URL",
+      noProxy: "Hosts without proxy (comma-separated)",
+      caBundle: "Extra CA certificates (PEM file)",
+      retries: "Retries after a connection or server error",
+      mirrors: "GitHub mirrors, one per line, tried when GitHub fails",
+      offline: "Offline mode",
+      save: "Save",
+      saved: "Network settings saved",
+      saveFailed: "Failed to save network settings",
+      hint: "Without a proxy URL, HTTPS_PROXY / HTTP_PROXY and NO_PROXY from the environment are used. Offline mode stops all downloads and searches only cached indexes."
+    },
+    github: {
+      title: "GitHub Token",
+      placeholder: "ghp_... or github_pat_...",
+      save: "Save",
+      clear: "Remove",
+      saved: "GitHub token saved",
+      cleared: "GitHub token removed",
+      saveFailed: "Failed to save the GitHub token",
+      hint: "Used for private repositories and a higher API rate limit. GITHUB_TOKEN or GH_TOKEN take precedence; without any, the token from `gh auth login` is used. It is only sent to GitHub."
     }
   },
   market: {
@@ -62,7 +93,7 @@ export default {
     manualAddTitle: "Add Skill Manually",
     manualUrlLabel: "Source URL",
     manualUrlPlaceholder: "e.g. https://github.com/owner/repo/tree/main/skills/foo",
-    manualUrlHint: "Supports GitHub repos, GitHub subdirectories, and ZIP download links.",
+    manualUrlHint: "Supports GitHub, GitLab, Gitea and Bitbucket repos or subdirectories, git remotes (append #ref:path to pick a subdirectory), officialskills.sh pages, and .zip / .tar.gz download links.",
     manualNameLabel: "Skill Name (Optional)",
     manualNamePlaceholder: "Auto-detect when left blank",
     manualNameHint: "If the URL cannot be inferred, enter the skill name manually.",
@@ -84,7 +115,20 @@ export default {
     source: "Source: {source}",
     viewSource: "View",
     loadMore: "Load More",
-    unavailable: "Unavailable"
+    unavailable: "Unavailable",
+    sortLabel: "Sort results",
+    sort: {
+      relevance: "Best match",
+      name: "Name",
+      recent: "Recently added",
+      stars: "Most stars",
+      installs: "Most installs"
+    },
+    facet: "{label} ({count})",
+    browseSkills: "Browse Skills",
+    browsing: "Loading...",
+    browseEmpty: "No SKILL.md found in this source",
+    installSelected: "Install {count} Selected"
   },
   local: {
     title: "Local Skills",
@@ -100,9 +144,14 @@ export default {
     installSelected: "Batch install to IDE ({count})",
     updateOne: "Update",
     updateSelected: "Update Selected ({count})",
+    checkUpdates: "Check for Updates",
+    checkingUpdates: "Checking...",
+    updateAll: "Update All ({count})",
+    updateAvailable: "Update: +{added} ~{modified} -{removed}",
     exportOne: "Export",
     exportSelected: "Export selected ({count})",
     import: "Import Local Skill",
+    importArchive: "Import Archive",
     preview: "Preview",
     previewTitle: "Skill Preview",
     previewEmptyDescription: "No description",
@@ -116,9 +165,16 @@ export default {
     deleteAll: "Delete all",
     selectExportPath: "Choose Export Path",
     selectSkillDir: "Select Skill Directory",
+    selectSkillArchive: "Select Skill Archive (.zip / .tar.gz)",
     processing: "Processing...",
     linked: "Linked",
-    unused: "Not linked"
+    unused: "Not linked",
+    pinnedCommit: "Commit {commit}",
+    installedAt: "Installed {time}",
+    versions: "Versions",
+    versionsTitle: "Previous versions of {name}",
+    versionsEmpty: "No previous versions yet. They are kept when the skill is updated.",
+    restoreVersion: "Restore"
   },
   ide: {
     title: "IDE Browser",
@@ -135,6 +191,7 @@ export default {
     sourceLink: "Linked",
     sourceLocal: "Local",
     unmanaged: "Unmanaged",
+    stale: "Outdated copy",
     openDir: "Open Folder",
     adopt: "Manage Centrally",
     uninstall: "Uninstall",
@@ -151,7 +208,17 @@ export default {
     noProjects: "No projects",
     installToIde: "Install to IDE",
     installToProject: "Install to Project",
-    cancel: "Cancel"
+    cancel: "Cancel",
+    strategy: "Link as",
+    strategyDefault: "Default",
+    strategies: {
+      symlink: "Symlink",
+      "relative-symlink": "Relative symlink",
+      copy: "Copy",
+      "hardlink-tree": "Hard-link tree"
+    },
+    vendor: "Vendor into project",
+    vendorHint: "Copy the skill into the project's .skills-manager/skills and link it with relative symlinks, so it can be committed with the project"
   },
   uninstallModal: {
     title: "Confirm uninstall",
@@ -182,6 +249,13 @@ export default {
     handled: "Handled {linked} targets, skipped {skipped} targets.",
     imported: "Successfully imported {success} skills, failed {failed}.",
     exported: "Exported to {path}",
+    installedFromSource: "Installed {count} skills",
+    updatesAvailable: "{count} skills have updates",
+    upToDate: "All skills are up to date",
+    rolledBack: "Restored a previous version of {name}",
+    projectSynced: "Synced {name}: {installed} installed, {linked} linked, {removed} removed",
+    projectInSync: "{name} already matches its skills.toml",
+    indexRefreshed: "Skills index updated ({date}): {added} added, {removed} removed",
     selectSkillsForProject: "Select skills to install for project {name}"
   },
   errors: {
@@ -190,8 +264,15 @@ export default {
     selectValidIde: "Select a valid IDE",
     selectAtLeastOne: "Select at least one IDE",
     searchFailed: "Search failed. Please try again.",
+    indexRefreshFailed: "Failed to refresh the skills index.",
+    marketSourceFailed: "{market} is unavailable: {message}",
     downloadFailed: "Download failed.",
+    listSourceFailed: "Failed to list skills in this source.",
     updateFailed: "Update failed.",
+    updateCheckFailed: "Failed to check for updates.",
+    versionsFailed: "Failed to list previous versions.",
+    rollbackFailed: "Failed to restore the version.",
+    projectSyncFailed: "Failed to sync the project.",
     scanFailed: "Failed to scan local skills.",
     installFailed: "Installation failed.",
     uninstallFailed: "Uninstallation failed.",
@@ -206,6 +287,24 @@ export default {
     invalidPath: "Path must be a relative path or a valid absolute path.",
     projectNoIdeTargets: "Project has no IDE targets configured. Please configure first."
   },
+  backendErrors: {
+    Cancelled: "The download was cancelled",
+    Offline: "Offline mode is on, so {url} was not fetched",
+    RateLimited: "Rate limit exceeded. Add a GitHub token in Settings to raise the limit.",
+    RateLimitedUntil: "Rate limit exceeded until {resetAt}. Add a GitHub token in Settings to raise the limit.",
+    PathOutsideAllowedRoot: "Path is outside the allowed directories: {path}",
+    NotFound: "Path does not exist: {path}",
+    AlreadyExists: "Target already exists: {path}",
+    MissingSkillMd: "Directory does not contain SKILL.md: {path}",
+    SymlinkRejected: "Refusing to follow symlinked content: {path}",
+    InvalidIdeDir: "Invalid IDE directory: {label}",
+    UnsupportedSource: "Only GitHub repository URLs, GitHub subdirectory URLs, or ZIP download URLs are supported.",
+    NetworkError: "Network request failed: {detail}",
+    ZipSlip: "The archive tries to write outside of the extraction directory: {entry}",
+    InvalidArchive: "Invalid archive: {detail}",
+    InvalidMarketIndex: "The skills index at {url} is invalid: {detail}",
+    LinkFailed: "Failed to create a link for {skill} in {target}: {detail}"
+  },
   update: {
     available: "New version available: {version}",
     view: "View Release",
@@ -229,7 +328,9 @@ export default {
     downloading: "Downloading...",
     done: "Done",
     error: "Download failed",
-    retry: "Retry"
+    retry: "Retry",
+    cancel: "Cancel",
+    cancelFailed: "This download has already finished"
   },
   projects: {
     title: "Projects",
@@ -244,6 +345,9 @@ export default {
     deselect: "Deselect",
     configure: "Configure",
     configureTitle: "Configure Project IDE Targets",
+    sync: "Sync skills.toml",
+    syncing: "Syncing...",
+    syncHint: "Install and link the skills the project's skills.toml declares, remove undeclared links and write skills.lock",
     linkSkills: "Link Skills",
     openDirectory: "Open Directory",
     emptyHint: "No projects yet. Click the button above to add one.",
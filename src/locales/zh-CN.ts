@@ -49,6 +49,37 @@ export default {
       light: "浅色",
       dark: "深色",
       system: "跟随系统"
+    },
+    cache: {
+      title: "下载缓存",
+      usage: "{count} 个压缩包，{size} / {max}",
+      clear: "清空缓存",
+      cleared: "已释放 {size}",
+      clearFailed: "清空缓存失败",
+      hint: "下载过的仓库压缩包会被保留，从同一来源安装更多技能或更新未变化的技能时无需重新下载。"
+    },
+    network: {
+      title: "网络",
+      proxy: "代理地址",
+      noProxy: "不走代理的主机（逗号分隔）",
+      caBundle: "额外的 CA 证书（PEM 文件）",
+      retries: "连接或服务器出错时的重试次数",
+      mirrors: "GitHub 镜像（每行一个），GitHub 访问失败时依次尝试",
+      offline: "离线模式",
+      save: "保存",
+      saved: "网络设置已保存",
+      saveFailed: "保存网络设置失败",
+      hint: "未填写代理地址时使用环境变量 HTTPS_PROXY / HTTP_PROXY 和 NO_PROXY。离线模式下不会进行任何下载，市场搜索只使用已缓存的索引。"
+    },
+    github: {
+      title: "GitHub 令牌",
+      placeholder: "ghp_... 或 github_pat_...",
+      save: "保存",
+      clear: "移除",
+      saved: "GitHub 令牌已保存",
+      cleared: "GitHub 令牌已移除",
+      saveFailed: "保存 GitHub 令牌失败",
+      hint: "用于访问私有仓库并提高 API 请求限额。GITHUB_TOKEN 或 GH_TOKEN 环境变量优先；都未设置时使用 `gh auth login` 保存的令牌。令牌只会发送给 GitHub。"
     }
   },
   market: {
@@ -62,7 +93,7 @@ export default {
     manualAddTitle: "手动添加 Skill",
     manualUrlLabel: "来源 URL",
     manualUrlPlaceholder: "例如 https://github.com/owner/repo/tree/main/skills/foo",
-    manualUrlHint: "支持 GitHub 仓库、GitHub 子目录和 ZIP 下载链接。",
+    manualUrlHint: "支持 GitHub、GitLab、Gitea、Bitbucket 仓库或子目录，git 远程地址（可追加 #分支:子目录 指定目录）、officialskills.sh 页面和 .zip / .tar.gz 下载链接。",
     manualNameLabel: "技能名称（可选）",
     manualNamePlaceholder: "留空时自动推断",
     manualNameHint: "若无法从 URL 推断技能名，请手动填写。",
@@ -83,7 +114,20 @@ export default {
     unavailable: "暂不可用",
     source: "来源：{source}",
     viewSource: "查看",
-    loadMore: "加载更多"
+    loadMore: "加载更多",
+    sortLabel: "结果排序",
+    sort: {
+      relevance: "最相关",
+      name: "名称",
+      recent: "最近添加",
+      stars: "星标最多",
+      installs: "安装最多"
+    },
+    facet: "{label}（{count}）",
+    browseSkills: "浏览技能",
+    browsing: "加载中...",
+    browseEmpty: "该来源中没有找到 SKILL.md",
+    installSelected: "安装选中的 {count} 个"
   },
   local: {
     title: "已有 Skills",
@@ -99,9 +143,14 @@ export default {
     installSelected: "批量安装到编辑器 ({count})",
     updateOne: "更新",
     updateSelected: "更新选中 ({count})",
+    checkUpdates: "检查更新",
+    checkingUpdates: "检查中...",
+    updateAll: "全部更新 ({count})",
+    updateAvailable: "可更新：+{added} ~{modified} -{removed}",
     exportOne: "导出",
     exportSelected: "导出选中 ({count})",
     import: "导入本地 Skill",
+    importArchive: "导入压缩包",
     preview: "预览",
     previewTitle: "Skill 预览",
     previewEmptyDescription: "暂无描述",
@@ -115,9 +164,16 @@ export default {
     deleteAll: "全部删除",
     selectExportPath: "选择导出路径",
     selectSkillDir: "选择 Skill 目录",
+    selectSkillArchive: "选择 Skill 压缩包（.zip / .tar.gz）",
     processing: "处理中...",
     linked: "已关联",
-    unused: "未关联"
+    unused: "未关联",
+    pinnedCommit: "提交 {commit}",
+    installedAt: "安装于 {time}",
+    versions: "历史版本",
+    versionsTitle: "{name} 的历史版本",
+    versionsEmpty: "暂无历史版本，更新技能时会自动保留旧版本。",
+    restoreVersion: "恢复"
   },
   ide: {
     title: "IDE 浏览",
@@ -134,6 +190,7 @@ export default {
     sourceLink: "链接",
     sourceLocal: "本地",
     unmanaged: "未托管",
+    stale: "副本已过期",
     openDir: "打开目录",
     adopt: "纳入统一管理",
     uninstall: "卸载",
@@ -150,7 +207,17 @@ export default {
     noProjects: "暂无项目",
     installToIde: "安装到 IDE",
     installToProject: "安装到项目",
-    cancel: "取消"
+    cancel: "取消",
+    strategy: "链接方式",
+    strategyDefault: "默认",
+    strategies: {
+      symlink: "软链接",
+      "relative-symlink": "相对软链接",
+      copy: "复制",
+      "hardlink-tree": "硬链接目录树"
+    },
+    vendor: "复制到项目中",
+    vendorHint: "将技能复制到项目的 .skills-manager/skills 并使用相对软链接，可随项目一起提交"
   },
   uninstallModal: {
     title: "确认卸载",
@@ -181,12 +248,26 @@ export default {
     handled: "已处理 {linked} 个目标，跳过 {skipped} 个目标。",
     imported: "成功导入 {success} 个 Skill，失败 {failed} 个。",
     exported: "已导出到 {path}",
+    installedFromSource: "已安装 {count} 个技能",
+    updatesAvailable: "{count} 个技能有更新",
+    upToDate: "所有技能均为最新",
+    rolledBack: "已恢复 {name} 的历史版本",
+    projectSynced: "已同步 {name}：安装 {installed} 个，链接 {linked} 个，移除 {removed} 个",
+    projectInSync: "{name} 已与 skills.toml 一致",
+    indexRefreshed: "技能索引已更新（{date}）：新增 {added} 个，移除 {removed} 个",
     selectSkillsForProject: "请为项目 {name} 选择要安装的 Skills"
   },
   errors: {
     searchFailed: "搜索失败，请重试。",
+    indexRefreshFailed: "刷新技能索引失败。",
+    marketSourceFailed: "{market} 暂不可用：{message}",
     downloadFailed: "下载失败。",
+    listSourceFailed: "无法列出该来源中的技能。",
     updateFailed: "更新失败。",
+    updateCheckFailed: "检查更新失败。",
+    versionsFailed: "获取历史版本失败。",
+    rollbackFailed: "恢复版本失败。",
+    projectSyncFailed: "同步项目失败。",
     scanFailed: "扫描本地 Skill 失败。",
     installFailed: "安装失败。",
     uninstallFailed: "卸载失败。",
@@ -205,6 +286,24 @@ export default {
     invalidPath: "路径必须是相对路径或有效的绝对路径。",
     projectNoIdeTargets: "项目尚未配置 IDE 目标，请先配置。"
   },
+  backendErrors: {
+    Cancelled: "下载已取消",
+    Offline: "已开启离线模式，未获取 {url}",
+    RateLimited: "已超出请求频率限制。在设置中添加 GitHub 令牌可提高限额。",
+    RateLimitedUntil: "已超出请求频率限制，{resetAt} 后恢复。在设置中添加 GitHub 令牌可提高限额。",
+    PathOutsideAllowedRoot: "路径不在允许范围内：{path}",
+    NotFound: "路径不存在：{path}",
+    AlreadyExists: "目标目录已存在，请更换名称或先清理：{path}",
+    MissingSkillMd: "目录中缺少 SKILL.md：{path}",
+    SymlinkRejected: "检测到符号链接，已拒绝处理：{path}",
+    InvalidIdeDir: "无效的 IDE 目录：{label}",
+    UnsupportedSource: "仅支持 GitHub 仓库链接、GitHub 子目录链接或 ZIP 下载链接。",
+    NetworkError: "网络请求失败：{detail}",
+    ZipSlip: "压缩包试图写入解压目录之外：{entry}",
+    InvalidArchive: "压缩包无效：{detail}",
+    InvalidMarketIndex: "技能索引无效（{url}）：{detail}",
+    LinkFailed: "无法在 {target} 中为 {skill} 创建链接：{detail}"
+  },
   update: {
     available: "发现新版本: {version}",
     view: "查看更新",
@@ -228,7 +327,9 @@ export default {
     downloading: "下载中...",
     done: "完成",
     error: "下载失败",
-    retry: "重试"
+    retry: "重试",
+    cancel: "取消",
+    cancelFailed: "该下载已经结束"
   },
   projects: {
     title: "项目管理",
@@ -243,6 +344,9 @@ export default {
     deselect: "取消",
     configure: "配置",
     configureTitle: "配置项目 IDE 目标",
+    sync: "同步 skills.toml",
+    syncing: "同步中...",
+    syncHint: "安装并链接项目 skills.toml 中声明的 Skills，移除未声明的链接并写入 skills.lock",
     linkSkills: "安装 Skills",
     openDirectory: "打开目录",
     emptyHint: "暂无项目，点击上方按钮添加。",